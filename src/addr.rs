@@ -0,0 +1,491 @@
+//! Definitions of virtual and physical addresses.
+
+use core::{fmt, ops};
+
+use crate::structures::paging::bits64::PageMapLevel;
+
+/// A canonical 64-bit virtual address.
+///
+/// A [`VirtAddr`] is always canonical with respect to 57-bit linear addresses, which covers every
+/// address canonical with respect to 48-bit linear addresses. Whether a [`VirtAddr`] is canonical
+/// when 4-level paging is in use can be checked with [`VirtAddr::is_canonical_48()`].
+///
+/// Arithmetic on a [`VirtAddr`], including the arithmetic of [`Page`][page]s and their ranges,
+/// only checks that the result is canonical with respect to 57-bit linear addresses. For example,
+/// adding `0x1000` to `0x7FFF_FFFF_F000` succeeds, even though the result lies in the
+/// non-canonical hole of 4-level paging. [`VirtAddr::checked_add_48()`] and
+/// [`VirtAddr::checked_sub_48()`] reject such results.
+///
+/// [page]: crate::structures::paging::bits64::page::Page
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VirtAddr(u64);
+
+impl VirtAddr {
+    /// Creates a new [`VirtAddr`] from a 48-bit canonical `address`.
+    ///
+    /// Returns [`None`] if `address` is not canonical with respect to 48-bit linear addresses.
+    pub const fn new(address: u64) -> Option<Self> {
+        if Self::new_truncate(address).0 != address {
+            return None;
+        }
+
+        Some(Self(address))
+    }
+
+    /// Creates a new [`VirtAddr`] from a 57-bit canonical `address`.
+    ///
+    /// Returns [`None`] if `address` is not canonical with respect to 57-bit linear addresses.
+    pub const fn new_la57(address: u64) -> Option<Self> {
+        if Self::new_truncate_la57(address).0 != address {
+            return None;
+        }
+
+        Some(Self(address))
+    }
+
+    /// Creates a new [`VirtAddr`] by sign extending bit 47 of `address`.
+    pub const fn new_truncate(address: u64) -> Self {
+        Self(((address << 16) as i64 >> 16) as u64)
+    }
+
+    /// Creates a new [`VirtAddr`] by sign extending bit 56 of `address`.
+    pub const fn new_truncate_la57(address: u64) -> Self {
+        Self(((address << 7) as i64 >> 7) as u64)
+    }
+
+    /// Creates a new [`VirtAddr`] from the address of `ptr`.
+    pub fn from_ptr<T: ?Sized>(ptr: *const T) -> Self {
+        Self::new_truncate_la57(ptr.cast::<()>() as usize as u64)
+    }
+
+    /// Creates a [`VirtAddr`] pointing to address 0.
+    pub const fn zero() -> Self {
+        Self(0)
+    }
+
+    /// Returns the value of this [`VirtAddr`].
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Returns this [`VirtAddr`] as a raw pointer.
+    pub const fn as_ptr<T>(self) -> *const T {
+        self.0 as usize as *const T
+    }
+
+    /// Returns this [`VirtAddr`] as a mutable raw pointer.
+    pub const fn as_mut_ptr<T>(self) -> *mut T {
+        self.0 as usize as *mut T
+    }
+
+    /// Returns `true` if this [`VirtAddr`] is canonical with respect to 48-bit linear addresses.
+    pub const fn is_canonical_48(self) -> bool {
+        Self::new_truncate(self.0).0 == self.0
+    }
+
+    /// Returns the offset of this [`VirtAddr`] into its 4 KiB page.
+    pub const fn page_offset(self) -> u16 {
+        (self.0 & 0xFFF) as u16
+    }
+
+    /// Returns the index into a [`PageTable<L>`][pt] used to translate this [`VirtAddr`].
+    ///
+    /// [pt]: crate::structures::paging::bits64::PageTable
+    pub const fn index<L: PageMapLevel>(self) -> usize {
+        ((self.0 >> L::INDEX_SHIFT) & 0x1FF) as usize
+    }
+
+    /// Returns `true` if this [`VirtAddr`] is aligned to `align`.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two.
+    pub const fn is_aligned(self, align: u64) -> bool {
+        assert!(align.is_power_of_two(), "`align` must be a power of two");
+        self.0 & (align - 1) == 0
+    }
+
+    /// Aligns this [`VirtAddr`] downwards to `align`.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two.
+    pub const fn align_down(self, align: u64) -> Self {
+        assert!(align.is_power_of_two(), "`align` must be a power of two");
+        Self::new_truncate_la57(self.0 & !(align - 1))
+    }
+
+    /// Aligns this [`VirtAddr`] upwards to `align`.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two or if the aligned address is not canonical with
+    /// respect to 57-bit linear addresses.
+    pub const fn align_up(self, align: u64) -> Self {
+        assert!(align.is_power_of_two(), "`align` must be a power of two");
+        match self.checked_add((align - (self.0 & (align - 1))) & (align - 1)) {
+            Some(address) => address,
+            None => panic!("aligned address is not canonical"),
+        }
+    }
+
+    /// Adds `rhs` to this [`VirtAddr`].
+    ///
+    /// Returns [`None`] if the result overflows or is not canonical with respect to 57-bit linear
+    /// addresses.
+    pub const fn checked_add(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_add(rhs) {
+            Some(address) => Self::new_la57(address),
+            None => None,
+        }
+    }
+
+    /// Subtracts `rhs` from this [`VirtAddr`].
+    ///
+    /// Returns [`None`] if the result overflows or is not canonical with respect to 57-bit linear
+    /// addresses.
+    pub const fn checked_sub(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_sub(rhs) {
+            Some(address) => Self::new_la57(address),
+            None => None,
+        }
+    }
+
+    /// Adds `rhs` to this [`VirtAddr`].
+    ///
+    /// Returns [`None`] if the result overflows or is not canonical with respect to 48-bit linear
+    /// addresses.
+    pub const fn checked_add_48(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_add(rhs) {
+            Some(address) => Self::new(address),
+            None => None,
+        }
+    }
+
+    /// Subtracts `rhs` from this [`VirtAddr`].
+    ///
+    /// Returns [`None`] if the result overflows or is not canonical with respect to 48-bit linear
+    /// addresses.
+    pub const fn checked_sub_48(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_sub(rhs) {
+            Some(address) => Self::new(address),
+            None => None,
+        }
+    }
+}
+
+impl fmt::Debug for VirtAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VirtAddr")
+            .field(&format_args!("{:#x}", self.0))
+            .finish()
+    }
+}
+
+impl fmt::LowerHex for VirtAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::UpperHex for VirtAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
+impl ops::Add<u64> for VirtAddr {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the result overflows or is not canonical.
+    fn add(self, rhs: u64) -> Self::Output {
+        self.checked_add(rhs).expect("virtual address overflow")
+    }
+}
+
+impl ops::AddAssign<u64> for VirtAddr {
+    fn add_assign(&mut self, rhs: u64) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::Sub<u64> for VirtAddr {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the result overflows or is not canonical.
+    fn sub(self, rhs: u64) -> Self::Output {
+        self.checked_sub(rhs).expect("virtual address underflow")
+    }
+}
+
+impl ops::SubAssign<u64> for VirtAddr {
+    fn sub_assign(&mut self, rhs: u64) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::Sub<VirtAddr> for VirtAddr {
+    type Output = u64;
+
+    /// # Panics
+    /// Panics if `rhs` is greater than `self`.
+    fn sub(self, rhs: VirtAddr) -> Self::Output {
+        self.0
+            .checked_sub(rhs.0)
+            .expect("virtual address underflow")
+    }
+}
+
+/// A 64-bit physical address.
+///
+/// A [`PhysAddr`] is always less than 2<sup>52</sup>, the largest physical address width
+/// supported by the architecture. The physical address width of the processor, `MAXPHYADDR`, may
+/// be lower and can be enforced with [`PhysAddr::truncate_to()`].
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PhysAddr(u64);
+
+impl PhysAddr {
+    /// The largest physical address width supported by the architecture.
+    pub const MAX_WIDTH: u8 = 52;
+
+    /// Creates a new [`PhysAddr`] from `address`.
+    ///
+    /// Returns [`None`] if `address` is not less than 2<sup>52</sup>.
+    pub const fn new(address: u64) -> Option<Self> {
+        if Self::new_truncate(address).0 != address {
+            return None;
+        }
+
+        Some(Self(address))
+    }
+
+    /// Creates a new [`PhysAddr`] by clearing bits 52 to 63 of `address`.
+    pub const fn new_truncate(address: u64) -> Self {
+        Self(address & ((1 << Self::MAX_WIDTH) - 1))
+    }
+
+    /// Creates a [`PhysAddr`] pointing to address 0.
+    pub const fn zero() -> Self {
+        Self(0)
+    }
+
+    /// Returns the value of this [`PhysAddr`].
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if this [`PhysAddr`] can be accessed by a processor with a physical address
+    /// width of `max_phys_addr` bits.
+    pub const fn fits(self, max_phys_addr: u8) -> bool {
+        self.truncate_to(max_phys_addr).0 == self.0
+    }
+
+    /// Clears the bits of this [`PhysAddr`] that are not accessible to a processor with a physical
+    /// address width of `max_phys_addr` bits.
+    pub const fn truncate_to(self, max_phys_addr: u8) -> Self {
+        if max_phys_addr >= Self::MAX_WIDTH {
+            return self;
+        }
+
+        Self(self.0 & ((1 << max_phys_addr) - 1))
+    }
+
+    /// Returns `true` if this [`PhysAddr`] is aligned to `align`.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two.
+    pub const fn is_aligned(self, align: u64) -> bool {
+        assert!(align.is_power_of_two(), "`align` must be a power of two");
+        self.0 & (align - 1) == 0
+    }
+
+    /// Aligns this [`PhysAddr`] downwards to `align`.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two.
+    pub const fn align_down(self, align: u64) -> Self {
+        assert!(align.is_power_of_two(), "`align` must be a power of two");
+        Self(self.0 & !(align - 1))
+    }
+
+    /// Aligns this [`PhysAddr`] upwards to `align`.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two or if the aligned address is not less than
+    /// 2<sup>52</sup>.
+    pub const fn align_up(self, align: u64) -> Self {
+        assert!(align.is_power_of_two(), "`align` must be a power of two");
+        match self.checked_add((align - (self.0 & (align - 1))) & (align - 1)) {
+            Some(address) => address,
+            None => panic!("aligned address is out of range"),
+        }
+    }
+
+    /// Adds `rhs` to this [`PhysAddr`].
+    ///
+    /// Returns [`None`] if the result is not less than 2<sup>52</sup>.
+    pub const fn checked_add(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_add(rhs) {
+            Some(address) => Self::new(address),
+            None => None,
+        }
+    }
+
+    /// Subtracts `rhs` from this [`PhysAddr`].
+    ///
+    /// Returns [`None`] if the result underflows.
+    pub const fn checked_sub(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_sub(rhs) {
+            Some(address) => Some(Self(address)),
+            None => None,
+        }
+    }
+}
+
+impl fmt::Debug for PhysAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PhysAddr")
+            .field(&format_args!("{:#x}", self.0))
+            .finish()
+    }
+}
+
+impl fmt::LowerHex for PhysAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::UpperHex for PhysAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
+impl ops::Add<u64> for PhysAddr {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the result is not less than 2<sup>52</sup>.
+    fn add(self, rhs: u64) -> Self::Output {
+        self.checked_add(rhs).expect("physical address overflow")
+    }
+}
+
+impl ops::AddAssign<u64> for PhysAddr {
+    fn add_assign(&mut self, rhs: u64) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::Sub<u64> for PhysAddr {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the result underflows.
+    fn sub(self, rhs: u64) -> Self::Output {
+        self.checked_sub(rhs).expect("physical address underflow")
+    }
+}
+
+impl ops::SubAssign<u64> for PhysAddr {
+    fn sub_assign(&mut self, rhs: u64) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::Sub<PhysAddr> for PhysAddr {
+    type Output = u64;
+
+    /// # Panics
+    /// Panics if `rhs` is greater than `self`.
+    fn sub(self, rhs: PhysAddr) -> Self::Output {
+        self.0
+            .checked_sub(rhs.0)
+            .expect("physical address underflow")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PhysAddr, VirtAddr};
+    use crate::structures::paging::bits64::{Pml1e, Pml4e, Pml5e};
+
+    #[test]
+    fn canonical() {
+        assert_eq!(
+            VirtAddr::new(0x0000_7FFF_FFFF_FFFF).unwrap().as_u64(),
+            0x7FFF_FFFF_FFFF
+        );
+        assert!(VirtAddr::new(0x0000_8000_0000_0000).is_none());
+        assert!(VirtAddr::new_la57(0x0000_8000_0000_0000).is_some());
+        assert!(VirtAddr::new_la57(0x0100_0000_0000_0000).is_none());
+
+        assert_eq!(
+            VirtAddr::new_truncate(0x0000_8000_0000_1000).as_u64(),
+            0xFFFF_8000_0000_1000
+        );
+        assert_eq!(
+            VirtAddr::new_truncate_la57(0x0100_0000_0000_1000).as_u64(),
+            0xFF00_0000_0000_1000
+        );
+        assert!(!VirtAddr::new_la57(0x00FF_0000_0000_0000)
+            .unwrap()
+            .is_canonical_48());
+    }
+
+    #[test]
+    fn virt_indices() {
+        let address = VirtAddr::new_la57(0xFFF7_FC01_FF00_5ABC).unwrap();
+
+        assert_eq!(address.page_offset(), 0xABC);
+        assert_eq!(address.index::<Pml1e>(), 5);
+        assert_eq!(address.index::<Pml4e>(), 0x1F8);
+        assert_eq!(address.index::<Pml5e>(), 0x1F7);
+    }
+
+    #[test]
+    fn alignment() {
+        let address = VirtAddr::new(0x7FFF_FFFF_F123).unwrap();
+        assert!(!address.is_aligned(0x1000));
+        assert_eq!(address.align_down(0x1000).as_u64(), 0x7FFF_FFFF_F000);
+        assert!(VirtAddr::new_la57(0x00FF_FFFF_FFFF_F000)
+            .unwrap()
+            .checked_add(0x1000)
+            .is_none());
+        let top = address.align_down(0x1000);
+        assert_eq!(top.checked_add(0x1000).unwrap().as_u64(), 0x8000_0000_0000);
+        assert!(top.checked_add_48(0x1000).is_none());
+        assert!(VirtAddr::new_truncate(0xFFFF_8000_0000_0000)
+            .checked_sub_48(1)
+            .is_none());
+        assert_eq!(
+            top.checked_sub_48(0x1000).unwrap().as_u64(),
+            0x7FFF_FFFF_E000
+        );
+        assert_eq!(
+            VirtAddr::new(0x1001).unwrap().align_up(0x1000).as_u64(),
+            0x2000
+        );
+
+        let address = PhysAddr::new(0x1234_5678).unwrap();
+        assert_eq!(address.align_up(0x20_0000).as_u64(), 0x1240_0000);
+        assert_eq!(address.align_down(0x20_0000).as_u64(), 0x1220_0000);
+        assert_eq!(address - PhysAddr::new(0x1234_0000).unwrap(), 0x5678);
+    }
+
+    #[test]
+    fn phys_width() {
+        assert!(PhysAddr::new(1 << 52).is_none());
+        assert_eq!(
+            PhysAddr::new_truncate(u64::MAX).as_u64(),
+            0x000F_FFFF_FFFF_FFFF
+        );
+
+        let address = PhysAddr::new(0x0000_FF12_3456_7000).unwrap();
+        assert!(!address.fits(36));
+        assert!(address.fits(48));
+        assert_eq!(address.truncate_to(36).as_u64(), 0x2_3456_7000);
+    }
+}
@@ -1,6 +1,32 @@
 //! Definitions and interfaces to interact with `x86` and `x86_64`'s CPUID instruction.
 
-use core::arch::asm;
+use core::{arch::asm, fmt};
+
+use crate::registers::xcontrol::XCr0;
+
+/// Generates accessors for feature bits of a leaf stored in a field of `$ty`, which defaults to
+/// [`CpuFeatures`].
+macro_rules! features {
+    ($ty:ident, $leaf:tt.$register:ident { $($(#[$meta:meta])* $name:ident = $bit:literal,)* }) => {
+        impl $ty {
+            $(
+                $(#[$meta])*
+                pub const fn $name(&self) -> bool {
+                    self.$leaf.$register & (1 << $bit) == (1 << $bit)
+                }
+            )*
+        }
+    };
+    ($leaf:ident.$register:ident $bits:tt) => {
+        features!(CpuFeatures, $leaf.$register $bits);
+    };
+}
+
+pub mod cache;
+pub mod hypervisor;
+#[cfg(test)]
+mod testing;
+pub mod topology;
 
 /// Returns `true` if the processor supports the `cpuid` instruction.
 pub fn has_cpuid() -> bool {
@@ -88,3 +114,840 @@ pub struct Cpuid {
     /// Value stored into edx.
     pub edx: u32,
 }
+
+/// The features of the processor, decoded from the CPUID leaves that report them.
+///
+/// Leaves beyond the maximum leaf reported by the processor are treated as if every feature they
+/// report is missing.
+///
+/// The leaves are read once, so a [`CpuFeatures`] read with [`CpuFeatures::new()`] can be passed
+/// to constructors such as [`RdRand::from_features()`][rdrand] instead of querying the processor
+/// again.
+///
+/// [rdrand]: crate::instructions::random::RdRand::from_features
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct CpuFeatures {
+    /// Whether the leaves were read from this processor.
+    native: bool,
+    /// The maximum basic leaf.
+    max_leaf: u32,
+    /// The maximum extended leaf.
+    max_extended_leaf: u32,
+    /// Leaf `0x1`.
+    leaf_1: Cpuid,
+    /// Leaf `0x7`, subleaf `0x0`.
+    leaf_7_0: Cpuid,
+    /// Leaf `0x7`, subleaf `0x1`.
+    leaf_7_1: Cpuid,
+    /// Leaf `0xD`, subleaf `0x0`.
+    leaf_d_0: Cpuid,
+    /// Leaf `0xD`, subleaf `0x1`.
+    leaf_d_1: Cpuid,
+    /// Leaf `0x8000_0001`.
+    leaf_8000_0001: Cpuid,
+    /// Leaf `0x8000_0007`.
+    leaf_8000_0007: Cpuid,
+    /// Leaf `0x8000_0008`.
+    leaf_8000_0008: Cpuid,
+}
+
+impl CpuFeatures {
+    /// Reads the [`CpuFeatures`] of this processor.
+    ///
+    /// Returns [`None`] if the processor does not support the `cpuid` instruction.
+    pub fn new() -> Option<Self> {
+        if !has_cpuid() {
+            return None;
+        }
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction.
+        let features = Self::from_cpuid(|leaf, subleaf| unsafe { cpuid(leaf, subleaf) });
+
+        Some(Self {
+            native: true,
+            ..features
+        })
+    }
+
+    /// Decodes [`CpuFeatures`] from the [`Cpuid`]s returned by `query` for a leaf and subleaf.
+    ///
+    /// `query` is only called for leaves that are not beyond the maximum leaf reported by leaf
+    /// `0x0` or `0x8000_0000`.
+    ///
+    /// As `query` may not describe this processor, the returned [`CpuFeatures`] are not
+    /// [native](CpuFeatures::native) and do not enable any instruction.
+    pub fn from_cpuid<F: FnMut(u32, u32) -> Cpuid>(mut query: F) -> Self {
+        let empty = Cpuid {
+            eax: 0,
+            ebx: 0,
+            ecx: 0,
+            edx: 0,
+        };
+
+        let max_leaf = query(0, 0).eax;
+        let max_extended_leaf = query(0x8000_0000, 0).eax;
+        let mut leaf = |leaf: u32, subleaf: u32| {
+            let max = if leaf < 0x8000_0000 {
+                max_leaf
+            } else {
+                max_extended_leaf
+            };
+
+            if leaf <= max {
+                query(leaf, subleaf)
+            } else {
+                empty
+            }
+        };
+
+        let leaf_1 = leaf(0x1, 0);
+        let leaf_7_0 = leaf(0x7, 0);
+        let leaf_7_1 = if leaf_7_0.eax >= 1 {
+            leaf(0x7, 1)
+        } else {
+            empty
+        };
+
+        Self {
+            native: false,
+            max_leaf,
+            max_extended_leaf,
+            leaf_1,
+            leaf_7_0,
+            leaf_7_1,
+            leaf_d_0: leaf(0xD, 0),
+            leaf_d_1: leaf(0xD, 1),
+            leaf_8000_0001: leaf(0x8000_0001, 0),
+            leaf_8000_0007: leaf(0x8000_0007, 0),
+            leaf_8000_0008: leaf(0x8000_0008, 0),
+        }
+    }
+
+    /// Returns `true` if these [`CpuFeatures`] were read from this processor with
+    /// [`CpuFeatures::new()`].
+    pub const fn native(&self) -> bool {
+        self.native
+    }
+
+    /// Returns the maximum basic leaf supported by the processor.
+    pub const fn max_leaf(&self) -> u32 {
+        self.max_leaf
+    }
+
+    /// Returns the maximum extended leaf supported by the processor.
+    pub const fn max_extended_leaf(&self) -> u32 {
+        self.max_extended_leaf
+    }
+
+    /// Returns the state components that the processor supports enabling in `XCR0`.
+    ///
+    /// Returns [`XCr0::empty()`] if the processor does not support `xsave`.
+    pub const fn xsave_components(&self) -> XCr0 {
+        if !self.xsave() {
+            return XCr0::empty();
+        }
+
+        XCr0::from_raw(((self.leaf_d_0.edx as u64) << 32) | self.leaf_d_0.eax as u64)
+    }
+
+    /// Returns the number of physical address bits supported by the processor.
+    ///
+    /// Returns [`None`] if leaf `0x8000_0008` is not supported.
+    pub const fn physical_address_bits(&self) -> Option<u8> {
+        match self.leaf_8000_0008.eax as u8 {
+            0 => None,
+            bits => Some(bits),
+        }
+    }
+
+    /// Returns the number of linear address bits supported by the processor.
+    ///
+    /// Returns [`None`] if leaf `0x8000_0008` is not supported.
+    pub const fn linear_address_bits(&self) -> Option<u8> {
+        match (self.leaf_8000_0008.eax >> 8) as u8 {
+            0 => None,
+            bits => Some(bits),
+        }
+    }
+}
+
+features!(leaf_1.ecx {
+    /// Returns `true` if the processor supports SSE3.
+    sse3 = 0,
+    /// Returns `true` if the processor supports `pclmulqdq`.
+    pclmulqdq = 1,
+    /// Returns `true` if the processor supports `monitor` and `mwait`.
+    monitor = 3,
+    /// Returns `true` if the processor supports VMX.
+    vmx = 5,
+    /// Returns `true` if the processor supports SMX.
+    smx = 6,
+    /// Returns `true` if the processor supports SSSE3.
+    ssse3 = 9,
+    /// Returns `true` if the processor supports FMA3.
+    fma = 12,
+    /// Returns `true` if the processor supports `cmpxchg16b`.
+    cmpxchg16b = 13,
+    /// Returns `true` if the processor supports process-context identifiers.
+    pcid = 17,
+    /// Returns `true` if the processor supports SSE4.1.
+    sse4_1 = 19,
+    /// Returns `true` if the processor supports SSE4.2.
+    sse4_2 = 20,
+    /// Returns `true` if the processor supports x2APIC mode.
+    x2apic = 21,
+    /// Returns `true` if the processor supports `movbe`.
+    movbe = 22,
+    /// Returns `true` if the processor supports `popcnt`.
+    popcnt = 23,
+    /// Returns `true` if the local APIC timer supports TSC deadline mode.
+    tsc_deadline = 24,
+    /// Returns `true` if the processor supports AES-NI.
+    aes = 25,
+    /// Returns `true` if the processor supports `xsave`.
+    xsave = 26,
+    /// Returns `true` if [`Cr4::OS_XSAVE`][osxsave] is set.
+    ///
+    /// [osxsave]: crate::registers::control::Cr4::OS_XSAVE
+    osxsave = 27,
+    /// Returns `true` if the processor supports AVX.
+    avx = 28,
+    /// Returns `true` if the processor supports half-precision conversions.
+    f16c = 29,
+    /// Returns `true` if the processor supports `rdrand`.
+    rdrand = 30,
+    /// Returns `true` if the processor is running under a hypervisor.
+    hypervisor = 31,
+});
+
+features!(leaf_1.edx {
+    /// Returns `true` if the processor has an x87 FPU.
+    fpu = 0,
+    /// Returns `true` if the processor supports `rdtsc`.
+    tsc = 4,
+    /// Returns `true` if the processor supports `rdmsr` and `wrmsr`.
+    msr = 5,
+    /// Returns `true` if the processor supports physical address extension paging.
+    pae = 6,
+    /// Returns `true` if the processor has a local APIC.
+    apic = 9,
+    /// Returns `true` if the processor supports `sysenter` and `sysexit`.
+    sysenter = 11,
+    /// Returns `true` if the processor supports MTRRs.
+    mtrr = 12,
+    /// Returns `true` if the processor supports global pages.
+    pge = 13,
+    /// Returns `true` if the processor supports the page attribute table.
+    pat = 16,
+    /// Returns `true` if the processor supports `clflush`.
+    clflush = 19,
+    /// Returns `true` if the processor supports MMX.
+    mmx = 23,
+    /// Returns `true` if the processor supports `fxsave` and `fxrstor`.
+    fxsr = 24,
+    /// Returns `true` if the processor supports SSE.
+    sse = 25,
+    /// Returns `true` if the processor supports SSE2.
+    sse2 = 26,
+    /// Returns `true` if the processor package may contain more than one logical processor.
+    htt = 28,
+});
+
+features!(leaf_7_0.ebx {
+    /// Returns `true` if the processor supports `rdfsbase`, `rdgsbase`, `wrfsbase`, and
+    /// `wrgsbase`.
+    fsgsbase = 0,
+    /// Returns `true` if the processor supports BMI1.
+    bmi1 = 3,
+    /// Returns `true` if the processor supports AVX2.
+    avx2 = 5,
+    /// Returns `true` if the processor supports supervisor-mode execution prevention.
+    smep = 7,
+    /// Returns `true` if the processor supports BMI2.
+    bmi2 = 8,
+    /// Returns `true` if the processor supports enhanced `rep movsb` and `rep stosb`.
+    erms = 9,
+    /// Returns `true` if the processor supports `invpcid`.
+    invpcid = 10,
+    /// Returns `true` if the processor supports restricted transactional memory.
+    rtm = 11,
+    /// Returns `true` if the processor supports AVX-512 foundation instructions.
+    avx512f = 16,
+    /// Returns `true` if the processor supports AVX-512 doubleword and quadword instructions.
+    avx512dq = 17,
+    /// Returns `true` if the processor supports `rdseed`.
+    rdseed = 18,
+    /// Returns `true` if the processor supports `adcx` and `adox`.
+    adx = 19,
+    /// Returns `true` if the processor supports supervisor-mode access prevention.
+    smap = 20,
+    /// Returns `true` if the processor supports `clflushopt`.
+    clflushopt = 23,
+    /// Returns `true` if the processor supports `clwb`.
+    clwb = 24,
+    /// Returns `true` if the processor supports AVX-512 conflict detection instructions.
+    avx512cd = 28,
+    /// Returns `true` if the processor supports SHA extensions.
+    sha = 29,
+    /// Returns `true` if the processor supports AVX-512 byte and word instructions.
+    avx512bw = 30,
+    /// Returns `true` if the processor supports AVX-512 vector length extensions.
+    avx512vl = 31,
+});
+
+features!(leaf_7_0.ecx {
+    /// Returns `true` if the processor supports user-mode instruction prevention.
+    umip = 2,
+    /// Returns `true` if the processor supports protection keys for user-mode pages.
+    pku = 3,
+    /// Returns `true` if [`Cr4::PROTECTION_KEYS_USER`][pke] is set.
+    ///
+    /// [pke]: crate::registers::control::Cr4::PROTECTION_KEYS_USER
+    ospke = 4,
+    /// Returns `true` if the processor supports CET shadow stacks.
+    cet_ss = 7,
+    /// Returns `true` if the processor supports Galois field instructions.
+    gfni = 8,
+    /// Returns `true` if the processor supports vector AES instructions.
+    vaes = 9,
+    /// Returns `true` if the processor supports 5-level paging.
+    la57 = 16,
+    /// Returns `true` if the processor supports `rdpid`.
+    rdpid = 22,
+    /// Returns `true` if the processor supports Key Locker.
+    key_locker = 23,
+    /// Returns `true` if the processor supports protection keys for supervisor-mode pages.
+    pks = 31,
+});
+
+features!(leaf_7_0.edx {
+    /// Returns `true` if the processor supports fast short `rep movsb`.
+    fsrm = 4,
+    /// Returns `true` if the processor supports user interrupts.
+    uintr = 5,
+    /// Returns `true` if the processor supports `serialize`.
+    serialize = 14,
+    /// Returns `true` if the processor supports CET indirect branch tracking.
+    cet_ibt = 20,
+    /// Returns `true` if the processor supports AMX bfloat16 instructions.
+    amx_bf16 = 22,
+    /// Returns `true` if the processor supports AMX tile architecture.
+    amx_tile = 24,
+    /// Returns `true` if the processor supports AMX 8-bit integer instructions.
+    amx_int8 = 25,
+});
+
+features!(leaf_7_1.eax {
+    /// Returns `true` if the processor supports AVX VNNI instructions.
+    avx_vnni = 4,
+    /// Returns `true` if the processor supports AVX-512 bfloat16 instructions.
+    avx512_bf16 = 5,
+    /// Returns `true` if the processor supports linear address space separation.
+    lass = 6,
+    /// Returns `true` if the processor supports linear address masking.
+    lam = 26,
+});
+
+features!(leaf_d_1.eax {
+    /// Returns `true` if the processor supports `xsaveopt`.
+    xsaveopt = 0,
+    /// Returns `true` if the processor supports `xsavec`.
+    xsavec = 1,
+    /// Returns `true` if the processor supports `xgetbv` with `ecx` set to 1.
+    xgetbv1 = 2,
+    /// Returns `true` if the processor supports `xsaves`, `xrstors`, and the `IA32_XSS` MSR.
+    xsaves = 3,
+});
+
+features!(leaf_8000_0001.ecx {
+    /// Returns `true` if the processor supports `lahf` and `sahf` in 64-bit mode.
+    lahf_lm = 0,
+    /// Returns `true` if the processor supports secure virtual machine extensions.
+    svm = 2,
+    /// Returns `true` if the processor supports `lzcnt`.
+    lzcnt = 5,
+    /// Returns `true` if the processor supports topology extensions, including leaf
+    /// `0x8000_001D`.
+    topology_extensions = 22,
+});
+
+features!(leaf_8000_0001.edx {
+    /// Returns `true` if the processor supports `syscall` and `sysret`.
+    syscall = 11,
+    /// Returns `true` if the processor supports the no execute bit.
+    nx = 20,
+    /// Returns `true` if the processor supports 1 GiB pages.
+    page_1gib = 26,
+    /// Returns `true` if the processor supports `rdtscp`.
+    rdtscp = 27,
+    /// Returns `true` if the processor supports 64-bit mode.
+    long_mode = 29,
+});
+
+features!(leaf_8000_0007.edx {
+    /// Returns `true` if the time stamp counter runs at a constant rate in every state.
+    invariant_tsc = 8,
+});
+
+features!(leaf_8000_0008.ebx {
+    /// Returns `true` if the processor supports `invlpgb` and `tlbsync`.
+    invlpgb = 3,
+    /// Returns `true` if the processor supports `wbnoinvd`.
+    wbnoinvd = 9,
+});
+
+/// The vendor of a processor or hypervisor, identified by the 12-byte string reported in leaf
+/// `0x0` or `0x4000_0000`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Vendor {
+    /// `GenuineIntel`.
+    Intel,
+    /// `AuthenticAMD`.
+    Amd,
+    /// `HygonGenuine`.
+    Hygon,
+    /// `  Shanghai  `.
+    Zhaoxin,
+    /// `CentaurHauls`, used by VIA and older Zhaoxin processors.
+    Centaur,
+    /// `KVMKVMKVM`.
+    Kvm,
+    /// `Microsoft Hv`.
+    HyperV,
+    /// `VMwareVMware`.
+    VMware,
+    /// `XenVMMXenVMM`.
+    Xen,
+    /// `TCGTCGTCGTCG`, used by QEMU without hardware acceleration.
+    Tcg,
+    /// `VBoxVBoxVBox`.
+    VirtualBox,
+    /// `prl hyperv  ` or ` lrpepyh  vr`.
+    Parallels,
+    /// `bhyve bhyve `.
+    Bhyve,
+    /// `ACRNACRNACRN`.
+    Acrn,
+    /// An unknown vendor string.
+    Unknown([u8; 12]),
+}
+
+impl Vendor {
+    /// Returns the [`Vendor`] of this processor.
+    ///
+    /// Returns [`None`] if the processor does not support the `cpuid` instruction.
+    pub fn new() -> Option<Self> {
+        if !has_cpuid() {
+            return None;
+        }
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction.
+        Some(Self::from_cpuid(unsafe { cpuid(0, 0) }))
+    }
+
+    /// Decodes the [`Vendor`] reported by leaf `0x0`, which is stored in `ebx`, `edx`, and
+    /// `ecx`.
+    pub const fn from_cpuid(leaf: Cpuid) -> Self {
+        let mut bytes = [0; 12];
+        let registers = [leaf.ebx, leaf.edx, leaf.ecx];
+
+        let mut index = 0;
+        while index < bytes.len() {
+            bytes[index] = registers[index / 4].to_le_bytes()[index % 4];
+            index += 1;
+        }
+
+        Self::from_bytes(bytes)
+    }
+
+    /// Creates a [`Vendor`] from its 12-byte vendor string.
+    pub const fn from_bytes(bytes: [u8; 12]) -> Self {
+        match &bytes {
+            b"GenuineIntel" => Self::Intel,
+            b"AuthenticAMD" => Self::Amd,
+            b"HygonGenuine" => Self::Hygon,
+            b"  Shanghai  " => Self::Zhaoxin,
+            b"CentaurHauls" => Self::Centaur,
+            b"KVMKVMKVM\0\0\0" => Self::Kvm,
+            b"Microsoft Hv" => Self::HyperV,
+            b"VMwareVMware" => Self::VMware,
+            b"XenVMMXenVMM" => Self::Xen,
+            b"TCGTCGTCGTCG" => Self::Tcg,
+            b"VBoxVBoxVBox" => Self::VirtualBox,
+            b"prl hyperv  " | b" lrpepyh  vr" => Self::Parallels,
+            b"bhyve bhyve " => Self::Bhyve,
+            b"ACRNACRNACRN" => Self::Acrn,
+            _ => Self::Unknown(bytes),
+        }
+    }
+
+    /// Returns `true` if this [`Vendor`] is a hypervisor.
+    pub const fn is_hypervisor(self) -> bool {
+        matches!(
+            self,
+            Self::Kvm
+                | Self::HyperV
+                | Self::VMware
+                | Self::Xen
+                | Self::Tcg
+                | Self::VirtualBox
+                | Self::Parallels
+                | Self::Bhyve
+                | Self::Acrn
+        )
+    }
+}
+
+impl fmt::Display for Vendor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Intel => "Intel",
+            Self::Amd => "AMD",
+            Self::Hygon => "Hygon",
+            Self::Zhaoxin => "Zhaoxin",
+            Self::Centaur => "Centaur",
+            Self::Kvm => "KVM",
+            Self::HyperV => "Hyper-V",
+            Self::VMware => "VMware",
+            Self::Xen => "Xen",
+            Self::Tcg => "QEMU TCG",
+            Self::VirtualBox => "VirtualBox",
+            Self::Parallels => "Parallels",
+            Self::Bhyve => "bhyve",
+            Self::Acrn => "ACRN",
+            Self::Unknown(bytes) => {
+                for &byte in bytes {
+                    let c = if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '?'
+                    };
+                    write!(f, "{c}")?;
+                }
+
+                return Ok(());
+            }
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// The 48-byte brand string of a processor, reported in leaves `0x8000_0002` to `0x8000_0004`.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct BrandString([u8; 48]);
+
+impl BrandString {
+    /// Returns the [`BrandString`] of this processor.
+    ///
+    /// Returns [`None`] if the processor does not support the `cpuid` instruction or does not
+    /// report a brand string.
+    pub fn new() -> Option<Self> {
+        if !has_cpuid() {
+            return None;
+        }
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction.
+        if unsafe { cpuid(0x8000_0000, 0) }.eax < 0x8000_0004 {
+            return None;
+        }
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction and leaves `0x8000_0002` to
+        // `0x8000_0004`.
+        Some(Self::from_cpuid(
+            [0x8000_0002, 0x8000_0003, 0x8000_0004].map(|leaf| unsafe { cpuid(leaf, 0) }),
+        ))
+    }
+
+    /// Decodes the [`BrandString`] reported by leaves `0x8000_0002` to `0x8000_0004`, in that
+    /// order.
+    pub const fn from_cpuid(leaves: [Cpuid; 3]) -> Self {
+        let mut bytes = [0; 48];
+
+        let mut index = 0;
+        while index < bytes.len() {
+            let leaf = leaves[index / 16];
+            let register = match (index / 4) % 4 {
+                0 => leaf.eax,
+                1 => leaf.ebx,
+                2 => leaf.ecx,
+                _ => leaf.edx,
+            };
+            bytes[index] = register.to_le_bytes()[index % 4];
+            index += 1;
+        }
+
+        Self(bytes)
+    }
+
+    /// Returns the raw bytes of this [`BrandString`].
+    pub const fn as_bytes(&self) -> &[u8; 48] {
+        &self.0
+    }
+
+    /// Returns this [`BrandString`] up to its NUL terminator, without leading and trailing
+    /// whitespace.
+    ///
+    /// The brand string is cut at the first byte that is not valid UTF-8.
+    pub fn as_str(&self) -> &str {
+        let len = self.0.iter().position(|&byte| byte == 0).unwrap_or(48);
+        let bytes = &self.0[..len];
+        let string = match core::str::from_utf8(bytes) {
+            Ok(string) => string,
+            Err(error) => core::str::from_utf8(&bytes[..error.valid_up_to()]).unwrap_or(""),
+        };
+
+        string.trim()
+    }
+}
+
+impl fmt::Debug for BrandString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BrandString").field(&self.as_str()).finish()
+    }
+}
+
+impl fmt::Display for BrandString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The family, model, and stepping of a processor, reported in `eax` of leaf `0x1`.
+#[repr(transparent)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Signature(u32);
+
+impl Signature {
+    /// Returns the [`Signature`] of this processor.
+    ///
+    /// Returns [`None`] if the processor does not support the `cpuid` instruction.
+    pub fn new() -> Option<Self> {
+        if !has_cpuid() {
+            return None;
+        }
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction.
+        Some(Self::from_cpuid(unsafe { cpuid(1, 0) }))
+    }
+
+    /// Decodes the [`Signature`] reported by leaf `0x1`.
+    pub const fn from_cpuid(leaf: Cpuid) -> Self {
+        Self(leaf.eax)
+    }
+
+    /// Creates a new [`Signature`] from the raw value of `eax`.
+    pub const fn from_raw(value: u32) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw value of `eax`.
+    pub const fn to_raw(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the family of the processor.
+    ///
+    /// The extended family is added to the family if the family is `0xF`.
+    pub const fn family(self) -> u16 {
+        let family = ((self.0 >> 8) & 0xF) as u16;
+        if family == 0xF {
+            family + ((self.0 >> 20) & 0xFF) as u16
+        } else {
+            family
+        }
+    }
+
+    /// Returns the model of the processor.
+    ///
+    /// The extended model makes up the upper 4 bits of the model if the family is `0x6` or
+    /// greater, which covers the rules of every vendor.
+    pub const fn model(self) -> u8 {
+        let model = ((self.0 >> 4) & 0xF) as u8;
+        if self.family() >= 0x6 {
+            (((self.0 >> 16) & 0xF) as u8) << 4 | model
+        } else {
+            model
+        }
+    }
+
+    /// Returns the stepping of the processor.
+    pub const fn stepping(self) -> u8 {
+        (self.0 & 0xF) as u8
+    }
+
+    /// Returns the processor type, which is 0 for original OEM processors.
+    pub const fn processor_type(self) -> u8 {
+        ((self.0 >> 12) & 0b11) as u8
+    }
+}
+
+impl fmt::Debug for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signature")
+            .field("family", &self.family())
+            .field("model", &self.model())
+            .field("stepping", &self.stepping())
+            .finish()
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "family {:#x}, model {:#x}, stepping {:#x}",
+            self.family(),
+            self.model(),
+            self.stepping()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::format;
+
+    use super::{testing::regs, BrandString, CpuFeatures, Cpuid, Signature, Vendor};
+    use crate::{
+        instructions::{
+            paging::FlushStrategy,
+            random::{RdRand, RdSeed},
+        },
+        registers::xcontrol::XCr0,
+    };
+
+    /// Returns the [`Cpuid`] of a processor with a maximum basic leaf of 7.
+    fn query(leaf: u32, subleaf: u32) -> Cpuid {
+        let (eax, ebx, ecx, edx) = match (leaf, subleaf) {
+            (0x0, _) => (0x7, 0x756E_6547, 0x6C65_746E, 0x4965_6E69),
+            (0x1, _) => (0x0009_06EA, 0x0010_0800, 0x7FFA_FBFF, 0xBFEB_FBFF),
+            (0x7, 0) => (0x0, 0x029C_67AF, 0x4000_0000, 0xBC00_0400),
+            (0x7, 1) => panic!("subleaf 1 of leaf 7 is not reported"),
+            (0xD, _) => panic!("leaf 0xD is beyond the maximum basic leaf"),
+            (0x8000_0000, _) => (0x8000_0008, 0, 0, 0),
+            (0x8000_0001, _) => (0x0, 0x0, 0x121, 0x2C10_0800),
+            (0x8000_0007, _) => (0x0, 0x0, 0x0, 0x100),
+            (0x8000_0008, _) => (0x3027, 0x0, 0x0, 0x0),
+            _ => (0, 0, 0, 0),
+        };
+
+        Cpuid { eax, ebx, ecx, edx }
+    }
+
+    #[test]
+    fn features() {
+        let features = CpuFeatures::from_cpuid(query);
+        assert_eq!(features.max_leaf(), 0x7);
+        assert_eq!(features.max_extended_leaf(), 0x8000_0008);
+
+        assert!(features.sse4_2() && features.avx() && features.rdrand() && features.x2apic());
+        assert!(features.pcid() && features.tsc_deadline() && features.xsave());
+        assert!(!features.hypervisor());
+        assert!(features.avx2() && features.smep() && features.smap() && features.invpcid());
+        assert!(features.rdseed() && features.fsgsbase());
+        assert!(!features.avx512f() && !features.la57() && !features.pku());
+        assert!(features.nx() && features.page_1gib() && features.rdtscp());
+        assert!(features.long_mode() && features.syscall() && features.invariant_tsc());
+        assert!(!features.lam() && !features.xsaves());
+
+        assert_eq!(features.xsave_components(), XCr0::empty());
+        assert_eq!(features.physical_address_bits(), Some(39));
+        assert_eq!(features.linear_address_bits(), Some(48));
+
+        // Decoded leaves may not describe this processor, so they enable no instruction.
+        assert!(!features.native());
+        assert_eq!(RdRand::from_features(&features), None);
+        assert_eq!(RdSeed::from_features(&features), None);
+        assert!(!FlushStrategy::from_features(&features).invpcid());
+    }
+
+    #[test]
+    fn vendor() {
+        let leaf = |ebx, ecx, edx| Vendor::from_cpuid(regs(0xD, ebx, ecx, edx));
+        assert_eq!(leaf(0x756E_6547, 0x6C65_746E, 0x4965_6E69), Vendor::Intel);
+        assert_eq!(leaf(0x6874_7541, 0x444D_4163, 0x6974_6E65), Vendor::Amd);
+        assert_eq!(leaf(0x6F67_7948, 0x656E_6975, 0x6E65_476E), Vendor::Hygon);
+        assert_eq!(leaf(0x6853_2020, 0x2020_6961, 0x6867_6E61), Vendor::Zhaoxin);
+        assert_eq!(leaf(0x746E_6543, 0x736C_7561, 0x4872_7561), Vendor::Centaur);
+
+        assert!(Vendor::from_bytes(*b"Microsoft Hv").is_hypervisor());
+        assert!(!Vendor::Intel.is_hypervisor());
+        assert_eq!(
+            format!("{}", Vendor::from_bytes(*b"Vortex86\0SoC")),
+            "Vortex86?SoC"
+        );
+    }
+
+    #[test]
+    fn brand_string() {
+        let i7_8700k = BrandString::from_cpuid([
+            regs(0x6574_6E49, 0x2952_286C, 0x726F_4320, 0x4D54_2865),
+            regs(0x3769_2029, 0x3037_382D, 0x4320_4B30, 0x4020_5550),
+            regs(0x372E_3320, 0x7A48_4730, 0x0000_0000, 0x0000_0000),
+        ]);
+        assert_eq!(
+            i7_8700k.as_str(),
+            "Intel(R) Core(TM) i7-8700K CPU @ 3.70GHz"
+        );
+
+        let ryzen_5950x = BrandString::from_cpuid([
+            regs(0x2044_4D41, 0x657A_7952, 0x2039_206E, 0x3035_3935),
+            regs(0x3631_2058, 0x726F_432D, 0x7250_2065, 0x7365_636F),
+            regs(0x2072_6F73, 0x2020_2020, 0x2020_2020, 0x0020_2020),
+        ]);
+        assert_eq!(
+            format!("{ryzen_5950x}"),
+            "AMD Ryzen 9 5950X 16-Core Processor"
+        );
+
+        let pentium_4 = BrandString::from_cpuid([
+            regs(0x2020_2020, 0x2020_2020, 0x2020_2020, 0x6E49_2020),
+            regs(0x286C_6574, 0x5020_2952, 0x6974_6E65, 0x5228_6D75),
+            regs(0x2034_2029, 0x2055_5043, 0x3034_2E32, 0x007A_4847),
+        ]);
+        assert_eq!(pentium_4.as_str(), "Intel(R) Pentium(R) 4 CPU 2.40GHz");
+        assert_eq!(pentium_4.as_bytes()[47], 0);
+    }
+
+    #[test]
+    fn signature() {
+        // Intel Core i7-8700K.
+        let signature = Signature::from_cpuid(regs(0x0009_06EA, 0, 0, 0));
+        assert_eq!(
+            (signature.family(), signature.model(), signature.stepping()),
+            (0x6, 0x9E, 0xA)
+        );
+
+        // AMD Ryzen 9 5950X.
+        let signature = Signature::from_raw(0x00A2_0F10);
+        assert_eq!(
+            (signature.family(), signature.model(), signature.stepping()),
+            (0x19, 0x21, 0x0)
+        );
+        assert_eq!(
+            format!("{signature}"),
+            "family 0x19, model 0x21, stepping 0x0"
+        );
+
+        // Intel Pentium 4 2.40GHz.
+        let signature = Signature::from_raw(0x0000_0F29);
+        assert_eq!(
+            (signature.family(), signature.model(), signature.stepping()),
+            (0xF, 0x2, 0x9)
+        );
+
+        // A family 0x5 signature, whose extended model is ignored.
+        let signature = Signature::from_raw(0x0001_0543);
+        assert_eq!(
+            (signature.family(), signature.model(), signature.stepping()),
+            (0x5, 0x4, 0x3)
+        );
+        assert_eq!(signature.processor_type(), 0);
+    }
+}
@@ -0,0 +1,637 @@
+//! Definitions to enumerate the caches and TLBs of a processor through CPUID leaves `0x2`, `0x4`,
+//! and `0x8000_001D`.
+
+use core::fmt;
+
+use crate::instructions::cpuid::{cpuid, CpuFeatures, Cpuid};
+
+/// The maximum number of subleaves enumerated by [`Caches`].
+const MAX_SUBLEAVES: u32 = 32;
+
+/// The kind of data held by a cache.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CacheType {
+    /// The cache only holds data.
+    Data = 1,
+    /// The cache only holds instructions.
+    Instruction = 2,
+    /// The cache holds both data and instructions.
+    Unified = 3,
+}
+
+impl CacheType {
+    /// Creates a [`CacheType`] from its encoding in leaf `0x4` or `0x8000_001D`.
+    ///
+    /// Returns [`None`] if `val` does not encode a [`CacheType`], which marks the end of the
+    /// caches.
+    pub const fn from_u8(val: u8) -> Option<Self> {
+        match val {
+            1 => Some(Self::Data),
+            2 => Some(Self::Instruction),
+            3 => Some(Self::Unified),
+            _ => None,
+        }
+    }
+}
+
+/// The parameters of a cache, reported by a subleaf of leaf `0x4` or `0x8000_001D`.
+#[repr(transparent)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CacheParameters(Cpuid);
+
+impl CacheParameters {
+    /// Decodes the [`CacheParameters`] reported by a subleaf of leaf `0x4` or `0x8000_001D`.
+    ///
+    /// Returns [`None`] if `leaf` does not describe a cache, which marks the end of the caches.
+    pub const fn from_cpuid(leaf: Cpuid) -> Option<Self> {
+        match CacheType::from_u8((leaf.eax & 0x1F) as u8) {
+            Some(_) => Some(Self(leaf)),
+            None => None,
+        }
+    }
+
+    /// Returns the [`CacheType`] of this cache.
+    pub const fn kind(self) -> CacheType {
+        match CacheType::from_u8((self.0.eax & 0x1F) as u8) {
+            Some(kind) => kind,
+            None => unreachable!(),
+        }
+    }
+
+    /// Returns the level of this cache, starting at 1.
+    pub const fn level(self) -> u8 {
+        ((self.0.eax >> 5) & 0b111) as u8
+    }
+
+    /// Returns `true` if this cache does not need to be initialized by software.
+    pub const fn self_initializing(self) -> bool {
+        self.0.eax & (1 << 8) == (1 << 8)
+    }
+
+    /// Returns `true` if this cache is fully associative.
+    pub const fn fully_associative(self) -> bool {
+        self.0.eax & (1 << 9) == (1 << 9)
+    }
+
+    /// Returns the maximum number of logical processors sharing this cache.
+    pub const fn sharing_threads(self) -> u32 {
+        ((self.0.eax >> 14) & 0xFFF) + 1
+    }
+
+    /// Returns the size of a line of this cache in bytes.
+    pub const fn line_size(self) -> u32 {
+        (self.0.ebx & 0xFFF) + 1
+    }
+
+    /// Returns the number of physical line partitions of this cache.
+    pub const fn partitions(self) -> u32 {
+        ((self.0.ebx >> 12) & 0x3FF) + 1
+    }
+
+    /// Returns the number of ways of associativity of this cache.
+    pub const fn ways(self) -> u32 {
+        (self.0.ebx >> 22) + 1
+    }
+
+    /// Returns the number of sets of this cache.
+    pub const fn sets(self) -> u32 {
+        self.0.ecx + 1
+    }
+
+    /// Returns the size of this cache in bytes.
+    pub const fn size(self) -> u64 {
+        self.ways() as u64 * self.partitions() as u64 * self.line_size() as u64 * self.sets() as u64
+    }
+
+    /// Returns `true` if `wbinvd` may not invalidate this cache in other logical processors
+    /// sharing it.
+    pub const fn wbinvd_not_shared(self) -> bool {
+        self.0.edx & 0b1 == 0b1
+    }
+
+    /// Returns `true` if this cache includes the lower levels of caches.
+    pub const fn inclusive(self) -> bool {
+        self.0.edx & 0b10 == 0b10
+    }
+
+    /// Returns `true` if this cache uses a complex function to index its sets.
+    pub const fn complex_indexing(self) -> bool {
+        self.0.edx & 0b100 == 0b100
+    }
+}
+
+impl fmt::Debug for CacheParameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacheParameters")
+            .field("kind", &self.kind())
+            .field("level", &self.level())
+            .field("size", &self.size())
+            .field("line_size", &self.line_size())
+            .field("ways", &self.ways())
+            .field("sets", &self.sets())
+            .field("sharing_threads", &self.sharing_threads())
+            .finish()
+    }
+}
+
+/// An iterator over the [`CacheParameters`] reported by the subleaves of leaf `0x4` or
+/// `0x8000_001D`.
+#[derive(Clone)]
+pub struct Caches<F> {
+    /// The function used to query a leaf and subleaf.
+    query: F,
+    /// The leaf being enumerated.
+    leaf: u32,
+    /// The next subleaf to query.
+    subleaf: u32,
+}
+
+impl Caches<fn(u32, u32) -> Cpuid> {
+    /// Returns the [`Caches`] of this processor, using leaf `0x8000_001D` if the processor
+    /// supports topology extensions and leaf `0x4` otherwise.
+    ///
+    /// Returns [`None`] if the processor does not support the `cpuid` instruction or neither
+    /// leaf.
+    pub fn new() -> Option<Self> {
+        Self::from_features(&CpuFeatures::new()?)
+    }
+
+    /// Returns the [`Caches`] of this processor, selecting the leaf to enumerate from `features`
+    /// like [`Caches::new()`].
+    ///
+    /// Returns [`None`] if `features` are not [native](CpuFeatures::native) or report neither
+    /// leaf.
+    pub fn from_features(features: &CpuFeatures) -> Option<Self> {
+        if !features.native() {
+            return None;
+        }
+
+        let leaf = if features.topology_extensions() && features.max_extended_leaf() >= 0x8000_001D
+        {
+            0x8000_001D
+        } else if features.max_leaf() >= 0x4 {
+            0x4
+        } else {
+            return None;
+        };
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction, as `features` were read from it.
+        Some(Self::from_cpuid(leaf, |leaf, subleaf| unsafe {
+            cpuid(leaf, subleaf)
+        }))
+    }
+}
+
+impl<F: FnMut(u32, u32) -> Cpuid> Caches<F> {
+    /// Creates a new [`Caches`] enumerating the subleaves of `leaf`, which must be `0x4` or
+    /// `0x8000_001D`, with the [`Cpuid`]s returned by `query`.
+    pub const fn from_cpuid(leaf: u32, query: F) -> Self {
+        Self {
+            query,
+            leaf,
+            subleaf: 0,
+        }
+    }
+}
+
+impl<F: FnMut(u32, u32) -> Cpuid> Iterator for Caches<F> {
+    type Item = CacheParameters;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.subleaf >= MAX_SUBLEAVES {
+            return None;
+        }
+
+        let parameters = CacheParameters::from_cpuid((self.query)(self.leaf, self.subleaf));
+        self.subleaf = match parameters {
+            Some(_) => self.subleaf + 1,
+            None => MAX_SUBLEAVES,
+        };
+
+        parameters
+    }
+}
+
+/// The associativity of a TLB.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Associativity {
+    /// Set associative with the given number of ways.
+    Ways(u8),
+    /// Fully associative.
+    Full,
+}
+
+/// The kind of translations held by a TLB.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TlbType {
+    /// The TLB only holds translations for instruction fetches.
+    Instruction,
+    /// The TLB only holds translations for data accesses.
+    Data,
+    /// The TLB holds translations for both, usually as a second level.
+    Shared,
+}
+
+/// The page sizes whose translations can be held by a TLB.
+#[repr(transparent)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct TlbPages(u8);
+
+#[allow(clippy::missing_docs_in_private_items)]
+impl TlbPages {
+    const K4: Self = Self(0b0001);
+    const M2: Self = Self(0b0010);
+    const M4: Self = Self(0b0100);
+    const G1: Self = Self(0b1000);
+    const M2_M4: Self = Self(Self::M2.0 | Self::M4.0);
+    const K4_M2: Self = Self(Self::K4.0 | Self::M2.0);
+    const K4_M4: Self = Self(Self::K4.0 | Self::M4.0);
+}
+
+impl TlbPages {
+    /// Returns `true` if the TLB holds translations of 4 KiB pages.
+    pub const fn size_4kib(self) -> bool {
+        self.0 & Self::K4.0 != 0
+    }
+
+    /// Returns `true` if the TLB holds translations of 2 MiB pages.
+    pub const fn size_2mib(self) -> bool {
+        self.0 & Self::M2.0 != 0
+    }
+
+    /// Returns `true` if the TLB holds translations of 4 MiB pages.
+    pub const fn size_4mib(self) -> bool {
+        self.0 & Self::M4.0 != 0
+    }
+
+    /// Returns `true` if the TLB holds translations of 1 GiB pages.
+    pub const fn size_1gib(self) -> bool {
+        self.0 & Self::G1.0 != 0
+    }
+}
+
+impl fmt::Debug for TlbPages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_set();
+        for (present, name) in [
+            (self.size_4kib(), "4KiB"),
+            (self.size_2mib(), "2MiB"),
+            (self.size_4mib(), "4MiB"),
+            (self.size_1gib(), "1GiB"),
+        ] {
+            if present {
+                list.entry(&format_args!("{name}"));
+            }
+        }
+
+        list.finish()
+    }
+}
+
+/// A cache or TLB descriptor reported by leaf `0x2`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Descriptor {
+    /// A cache.
+    Cache {
+        /// The kind of data held by the cache.
+        kind: CacheType,
+        /// The level of the cache, starting at 1.
+        level: u8,
+        /// The size of the cache in KiB.
+        size_kib: u32,
+        /// The number of ways of associativity of the cache.
+        ways: u8,
+        /// The size of a line of the cache in bytes.
+        line_size: u16,
+    },
+    /// A TLB.
+    Tlb {
+        /// The kind of translations held by the TLB.
+        kind: TlbType,
+        /// The page sizes whose translations can be held by the TLB.
+        pages: TlbPages,
+        /// The number of entries of the TLB.
+        entries: u16,
+        /// The associativity of the TLB.
+        associativity: Associativity,
+    },
+    /// The processor prefetches the given number of bytes.
+    Prefetch(u16),
+    /// There is no second level cache or, if there is, no third level cache.
+    NoHigherLevelCache,
+    /// The caches and TLBs are enumerated by leaf `0x4` and leaf `0x18`.
+    UseLeaf4,
+    /// A descriptor that is not decoded, either because it is unknown or because it describes
+    /// several structures at once.
+    Unknown(u8),
+}
+
+impl Descriptor {
+    /// Decodes the [`Descriptor`] encoded by `val`.
+    ///
+    /// Returns [`None`] if `val` is the null descriptor.
+    pub const fn from_u8(val: u8) -> Option<Self> {
+        use Associativity::{Full, Ways};
+        use CacheType::{Data, Instruction, Unified};
+        use TlbType::{Data as D, Instruction as I, Shared as S};
+
+        let (kind, level, size_kib, ways, line_size) = match val {
+            0x00 => return None,
+            0x01 => return Some(Self::tlb(I, TlbPages::K4, 32, Ways(4))),
+            0x02 => return Some(Self::tlb(I, TlbPages::M4, 2, Full)),
+            0x03 => return Some(Self::tlb(D, TlbPages::K4, 64, Ways(4))),
+            0x04 => return Some(Self::tlb(D, TlbPages::M4, 8, Ways(4))),
+            0x05 => return Some(Self::tlb(D, TlbPages::M4, 32, Ways(4))),
+            0x06 => (Instruction, 1, 8, 4, 32),
+            0x08 => (Instruction, 1, 16, 4, 32),
+            0x09 => (Instruction, 1, 32, 4, 64),
+            0x0A => (Data, 1, 8, 2, 32),
+            0x0B => return Some(Self::tlb(I, TlbPages::M4, 4, Ways(4))),
+            0x0C => (Data, 1, 16, 4, 32),
+            0x0D => (Data, 1, 16, 4, 64),
+            0x0E => (Data, 1, 24, 6, 64),
+            0x1D => (Unified, 2, 128, 2, 64),
+            0x21 => (Unified, 2, 256, 8, 64),
+            0x22 => (Unified, 3, 512, 4, 64),
+            0x23 => (Unified, 3, 1024, 8, 64),
+            0x24 => (Unified, 2, 1024, 16, 64),
+            0x25 => (Unified, 3, 2048, 8, 64),
+            0x29 => (Unified, 3, 4096, 8, 64),
+            0x2C => (Data, 1, 32, 8, 64),
+            0x30 => (Instruction, 1, 32, 8, 64),
+            0x40 => return Some(Self::NoHigherLevelCache),
+            0x41 => (Unified, 2, 128, 4, 32),
+            0x42 => (Unified, 2, 256, 4, 32),
+            0x43 => (Unified, 2, 512, 4, 32),
+            0x44 => (Unified, 2, 1024, 4, 32),
+            0x45 => (Unified, 2, 2048, 4, 32),
+            0x46 => (Unified, 3, 4096, 4, 64),
+            0x47 => (Unified, 3, 8192, 8, 64),
+            0x48 => (Unified, 2, 3072, 12, 64),
+            0x4A => (Unified, 3, 6144, 12, 64),
+            0x4B => (Unified, 3, 8192, 16, 64),
+            0x4C => (Unified, 3, 12288, 12, 64),
+            0x4D => (Unified, 3, 16384, 16, 64),
+            0x4E => (Unified, 2, 6144, 24, 64),
+            0x55 => return Some(Self::tlb(I, TlbPages::M2_M4, 7, Full)),
+            0x56 => return Some(Self::tlb(D, TlbPages::M4, 16, Ways(4))),
+            0x57 => return Some(Self::tlb(D, TlbPages::K4, 16, Ways(4))),
+            0x59 => return Some(Self::tlb(D, TlbPages::K4, 16, Full)),
+            0x5A => return Some(Self::tlb(D, TlbPages::M2_M4, 32, Ways(4))),
+            0x60 => (Data, 1, 16, 8, 64),
+            0x61 => return Some(Self::tlb(I, TlbPages::K4, 48, Full)),
+            0x66 => (Data, 1, 8, 4, 64),
+            0x67 => (Data, 1, 16, 4, 64),
+            0x68 => (Data, 1, 32, 4, 64),
+            0x6A => return Some(Self::tlb(D, TlbPages::K4, 64, Ways(8))),
+            0x6B => return Some(Self::tlb(D, TlbPages::K4, 256, Ways(8))),
+            0x6C => return Some(Self::tlb(D, TlbPages::M2_M4, 128, Ways(8))),
+            0x6D => return Some(Self::tlb(D, TlbPages::G1, 16, Full)),
+            0x76 => return Some(Self::tlb(I, TlbPages::M2_M4, 8, Full)),
+            0x78 => (Unified, 2, 1024, 4, 64),
+            0x79 => (Unified, 2, 128, 8, 64),
+            0x7A => (Unified, 2, 256, 8, 64),
+            0x7B => (Unified, 2, 512, 8, 64),
+            0x7C => (Unified, 2, 1024, 8, 64),
+            0x7D => (Unified, 2, 2048, 8, 64),
+            0x7F => (Unified, 2, 512, 2, 64),
+            0x80 => (Unified, 2, 512, 8, 64),
+            0x82 => (Unified, 2, 256, 8, 32),
+            0x83 => (Unified, 2, 512, 8, 32),
+            0x84 => (Unified, 2, 1024, 8, 32),
+            0x85 => (Unified, 2, 2048, 8, 32),
+            0x86 => (Unified, 2, 512, 4, 64),
+            0x87 => (Unified, 2, 1024, 8, 64),
+            0xA0 => return Some(Self::tlb(D, TlbPages::K4, 32, Full)),
+            0xB0 => return Some(Self::tlb(I, TlbPages::K4, 128, Ways(4))),
+            0xB2 => return Some(Self::tlb(I, TlbPages::K4, 64, Ways(4))),
+            0xB3 => return Some(Self::tlb(D, TlbPages::K4, 128, Ways(4))),
+            0xB4 => return Some(Self::tlb(D, TlbPages::K4, 256, Ways(4))),
+            0xB5 => return Some(Self::tlb(I, TlbPages::K4, 64, Ways(8))),
+            0xB6 => return Some(Self::tlb(I, TlbPages::K4, 128, Ways(8))),
+            0xBA => return Some(Self::tlb(D, TlbPages::K4, 64, Ways(4))),
+            0xC0 => return Some(Self::tlb(D, TlbPages::K4_M4, 8, Ways(4))),
+            0xC1 => return Some(Self::tlb(S, TlbPages::K4_M2, 1024, Ways(8))),
+            0xC2 => return Some(Self::tlb(D, TlbPages::K4_M2, 16, Ways(4))),
+            0xC4 => return Some(Self::tlb(D, TlbPages::M2_M4, 32, Ways(4))),
+            0xCA => return Some(Self::tlb(S, TlbPages::K4, 512, Ways(4))),
+            0xD0 => (Unified, 3, 512, 4, 64),
+            0xD1 => (Unified, 3, 1024, 4, 64),
+            0xD2 => (Unified, 3, 2048, 4, 64),
+            0xD6 => (Unified, 3, 1024, 8, 64),
+            0xD7 => (Unified, 3, 2048, 8, 64),
+            0xD8 => (Unified, 3, 4096, 8, 64),
+            0xDC => (Unified, 3, 1536, 12, 64),
+            0xDD => (Unified, 3, 3072, 12, 64),
+            0xDE => (Unified, 3, 6144, 12, 64),
+            0xE2 => (Unified, 3, 2048, 16, 64),
+            0xE3 => (Unified, 3, 4096, 16, 64),
+            0xE4 => (Unified, 3, 8192, 16, 64),
+            0xEA => (Unified, 3, 12288, 24, 64),
+            0xEB => (Unified, 3, 18432, 24, 64),
+            0xEC => (Unified, 3, 24576, 24, 64),
+            0xF0 => return Some(Self::Prefetch(64)),
+            0xF1 => return Some(Self::Prefetch(128)),
+            0xFF => return Some(Self::UseLeaf4),
+            _ => return Some(Self::Unknown(val)),
+        };
+
+        Some(Self::Cache {
+            kind,
+            level,
+            size_kib,
+            ways,
+            line_size,
+        })
+    }
+
+    /// Creates a [`Descriptor::Tlb`].
+    const fn tlb(
+        kind: TlbType,
+        pages: TlbPages,
+        entries: u16,
+        associativity: Associativity,
+    ) -> Self {
+        Self::Tlb {
+            kind,
+            pages,
+            entries,
+            associativity,
+        }
+    }
+}
+
+/// An iterator over the [`Descriptor`]s reported by leaf `0x2`.
+#[derive(Clone, Debug)]
+pub struct Descriptors {
+    /// The descriptor bytes, with the bytes of invalid registers cleared.
+    bytes: [u8; 16],
+    /// The index of the next byte to decode.
+    index: usize,
+}
+
+impl Descriptors {
+    /// Creates a new [`Descriptors`] decoding the descriptor bytes reported by leaf `0x2`.
+    ///
+    /// The low byte of `eax` and registers whose bit 31 is set do not hold descriptors and are
+    /// skipped.
+    pub const fn from_cpuid(leaf: Cpuid) -> Self {
+        let mut bytes = [0; 16];
+        let registers = [leaf.eax, leaf.ebx, leaf.ecx, leaf.edx];
+
+        let mut index = 0;
+        while index < registers.len() {
+            if registers[index] & (1 << 31) == 0 {
+                let register = registers[index].to_le_bytes();
+                bytes[index * 4] = register[0];
+                bytes[index * 4 + 1] = register[1];
+                bytes[index * 4 + 2] = register[2];
+                bytes[index * 4 + 3] = register[3];
+            }
+            index += 1;
+        }
+
+        Self { bytes, index: 1 }
+    }
+}
+
+impl Iterator for Descriptors {
+    type Item = Descriptor;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.bytes.len() {
+            let byte = self.bytes[self.index];
+            self.index += 1;
+
+            if let Some(descriptor) = Descriptor::from_u8(byte) {
+                return Some(descriptor);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::{
+        Associativity, CacheParameters, CacheType, Caches, Descriptor, Descriptors, TlbPages,
+        TlbType,
+    };
+    use crate::instructions::cpuid::Cpuid;
+
+    /// Returns the subleaves of leaf `0x4` of an Intel processor and of leaf `0x8000_001D` of an
+    /// AMD processor.
+    fn query(leaf: u32, subleaf: u32) -> Cpuid {
+        let (eax, ebx, ecx, edx) = match (leaf, subleaf) {
+            (0x4, 0) => (0x1C00_4121, 0x01C0_003F, 0x0000_003F, 0x0),
+            (0x4, 1) => (0x1C00_4122, 0x01C0_003F, 0x0000_003F, 0x0),
+            (0x4, 2) => (0x1C00_4143, 0x00C0_003F, 0x0000_03FF, 0x0),
+            (0x4, 3) => (0x1C03_C163, 0x03C0_003F, 0x0000_2FFF, 0x6),
+            (0x8000_001D, 0) => (0x0000_4121, 0x01C0_003F, 0x0000_003F, 0x0),
+            (0x8000_001D, 1) => (0x0000_4122, 0x01C0_003F, 0x0000_003F, 0x0),
+            (0x8000_001D, 2) => (0x0000_4143, 0x01C0_003F, 0x0000_03FF, 0x2),
+            (0x8000_001D, 3) => (0x0003_C163, 0x03C0_003F, 0x0000_3FFF, 0x1),
+            _ => (0, 0, 0, 0),
+        };
+
+        Cpuid { eax, ebx, ecx, edx }
+    }
+
+    /// Returns the kind, level, size, and sharing threads of `parameters`.
+    fn summary(parameters: CacheParameters) -> (CacheType, u8, u64, u32) {
+        (
+            parameters.kind(),
+            parameters.level(),
+            parameters.size(),
+            parameters.sharing_threads(),
+        )
+    }
+
+    #[test]
+    fn caches() {
+        let intel: Vec<_> = Caches::from_cpuid(0x4, query).collect();
+        assert_eq!(
+            intel.iter().copied().map(summary).collect::<Vec<_>>(),
+            [
+                (CacheType::Data, 1, 32 << 10, 2),
+                (CacheType::Instruction, 1, 32 << 10, 2),
+                (CacheType::Unified, 2, 256 << 10, 2),
+                (CacheType::Unified, 3, 12 << 20, 16),
+            ]
+        );
+
+        let l3 = intel[3];
+        assert_eq!((l3.ways(), l3.sets(), l3.line_size()), (16, 12288, 64));
+        assert!(l3.self_initializing() && !l3.fully_associative());
+        assert!(l3.inclusive() && l3.complex_indexing() && !l3.wbinvd_not_shared());
+
+        let amd: Vec<_> = Caches::from_cpuid(0x8000_001D, query).collect();
+        assert_eq!(amd.len(), 4);
+        assert_eq!(summary(amd[2]), (CacheType::Unified, 2, 512 << 10, 2));
+        assert_eq!(summary(amd[3]), (CacheType::Unified, 3, 16 << 20, 16));
+        assert!(amd[3].wbinvd_not_shared() && !amd[3].inclusive());
+    }
+
+    #[test]
+    fn descriptors() {
+        let leaf = Cpuid {
+            eax: 0x302C_F001,
+            ebx: 0x8012_3456,
+            ecx: 0x0000_B2FF,
+            edx: 0x6BCA_4070,
+        };
+
+        let descriptors: Vec<_> = Descriptors::from_cpuid(leaf).collect();
+        assert_eq!(
+            descriptors,
+            [
+                Descriptor::Prefetch(64),
+                Descriptor::Cache {
+                    kind: CacheType::Data,
+                    level: 1,
+                    size_kib: 32,
+                    ways: 8,
+                    line_size: 64,
+                },
+                Descriptor::Cache {
+                    kind: CacheType::Instruction,
+                    level: 1,
+                    size_kib: 32,
+                    ways: 8,
+                    line_size: 64,
+                },
+                Descriptor::UseLeaf4,
+                Descriptor::Tlb {
+                    kind: TlbType::Instruction,
+                    pages: TlbPages::K4,
+                    entries: 64,
+                    associativity: Associativity::Ways(4),
+                },
+                Descriptor::Unknown(0x70),
+                Descriptor::NoHigherLevelCache,
+                Descriptor::Tlb {
+                    kind: TlbType::Shared,
+                    pages: TlbPages::K4,
+                    entries: 512,
+                    associativity: Associativity::Ways(4),
+                },
+                Descriptor::Tlb {
+                    kind: TlbType::Data,
+                    pages: TlbPages::K4,
+                    entries: 256,
+                    associativity: Associativity::Ways(8),
+                },
+            ]
+        );
+
+        let Some(Descriptor::Tlb { pages, .. }) = Descriptor::from_u8(0x5A) else {
+            panic!("0x5A is a TLB descriptor");
+        };
+        assert!(pages.size_2mib() && pages.size_4mib() && !pages.size_4kib());
+        assert_eq!(Descriptor::from_u8(0x00), None);
+    }
+}
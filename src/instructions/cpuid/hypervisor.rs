@@ -0,0 +1,386 @@
+//! Definitions to detect a hypervisor and decode the CPUID leaves it reports from
+//! `0x4000_0000`.
+
+use crate::instructions::cpuid::{cpuid, has_cpuid, CpuFeatures, Cpuid, Vendor};
+
+/// The first leaf of the hypervisor range.
+const BASE_LEAF: u32 = 0x4000_0000;
+
+/// The leaf past the last base leaf searched by [`Hypervisor::find()`].
+const END_LEAF: u32 = 0x4001_0000;
+
+/// The distance between the base leaves searched by [`Hypervisor::find()`].
+const BASE_STRIDE: u32 = 0x100;
+
+/// The interface signature reported by hypervisors implementing the Hyper-V interface, `Hv#1`.
+const HYPERV_INTERFACE: u32 = 0x3123_7648;
+
+/// A hypervisor that reports its identity in a range of CPUID leaves.
+///
+/// Whether the processor is running under a hypervisor at all is reported by
+/// [`CpuFeatures::hypervisor()`][hypervisor].
+///
+/// [hypervisor]: crate::instructions::cpuid::CpuFeatures::hypervisor
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Hypervisor {
+    /// The first leaf of the range.
+    base: u32,
+    /// The maximum leaf of the range.
+    max_leaf: u32,
+    /// The vendor of the hypervisor.
+    vendor: Vendor,
+}
+
+impl Hypervisor {
+    /// Returns the [`Hypervisor`] that this processor is running under.
+    ///
+    /// Returns [`None`] if the processor does not support the `cpuid` instruction or is not
+    /// running under a hypervisor.
+    pub fn new() -> Option<Self> {
+        if !has_cpuid() {
+            return None;
+        }
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction.
+        Self::from_cpuid(|leaf, subleaf| unsafe { cpuid(leaf, subleaf) })
+    }
+
+    /// Decodes the [`Hypervisor`] reporting its identity at leaf `0x4000_0000` from the
+    /// [`Cpuid`]s returned by `query` for a leaf and subleaf.
+    ///
+    /// Returns [`None`] if the [`CpuFeatures`] decoded from `query` do not report a
+    /// [hypervisor](CpuFeatures::hypervisor).
+    pub fn from_cpuid<F: FnMut(u32, u32) -> Cpuid>(mut query: F) -> Option<Self> {
+        if !CpuFeatures::from_cpuid(&mut query).hypervisor() {
+            return None;
+        }
+
+        Some(Self::from_base(BASE_LEAF, query(BASE_LEAF, 0)))
+    }
+
+    /// Searches the hypervisor range for a [`Hypervisor`] from `vendor`, using the [`Cpuid`]s
+    /// returned by `query` for a leaf and subleaf.
+    ///
+    /// Hypervisors that implement the interface of another hypervisor, such as KVM with Hyper-V
+    /// enlightenments, report their own identity at a base leaf above `0x4000_0000`, which is a
+    /// multiple of `0x100`.
+    ///
+    /// Returns [`None`] if the processor is not running under a hypervisor from `vendor`.
+    pub fn find<F: FnMut(u32, u32) -> Cpuid>(vendor: Vendor, mut query: F) -> Option<Self> {
+        if !CpuFeatures::from_cpuid(&mut query).hypervisor() {
+            return None;
+        }
+
+        (BASE_LEAF..END_LEAF)
+            .step_by(BASE_STRIDE as usize)
+            .map(|base| Self::from_base(base, query(base, 0)))
+            .find(|hypervisor| hypervisor.vendor == vendor)
+    }
+
+    /// Decodes the [`Hypervisor`] reporting its identity at `base`.
+    const fn from_base(base: u32, leaf: Cpuid) -> Self {
+        let mut bytes = [0; 12];
+        let registers = [leaf.ebx, leaf.ecx, leaf.edx];
+
+        let mut index = 0;
+        while index < bytes.len() {
+            bytes[index] = registers[index / 4].to_le_bytes()[index % 4];
+            index += 1;
+        }
+
+        Self {
+            base,
+            max_leaf: leaf.eax,
+            vendor: Vendor::from_bytes(bytes),
+        }
+    }
+
+    /// Returns the [`Vendor`] of this [`Hypervisor`].
+    pub const fn vendor(&self) -> Vendor {
+        self.vendor
+    }
+
+    /// Returns the first leaf of the range of this [`Hypervisor`].
+    pub const fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Returns the maximum leaf of the range of this [`Hypervisor`].
+    pub const fn max_leaf(&self) -> u32 {
+        self.max_leaf
+    }
+}
+
+/// The paravirtualized features offered by KVM, reported by the leaf after its base leaf.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct KvmFeatures(Cpuid);
+
+impl KvmFeatures {
+    /// Returns the [`KvmFeatures`] offered to this processor.
+    ///
+    /// Returns [`None`] if the processor does not support the `cpuid` instruction or is not
+    /// running under KVM.
+    pub fn new() -> Option<Self> {
+        if !has_cpuid() {
+            return None;
+        }
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction.
+        Self::from_cpuid(|leaf, subleaf| unsafe { cpuid(leaf, subleaf) })
+    }
+
+    /// Decodes the [`KvmFeatures`] from the [`Cpuid`]s returned by `query` for a leaf and
+    /// subleaf, searching for the base leaf of KVM with [`Hypervisor::find()`].
+    ///
+    /// Returns [`None`] if the processor is not running under KVM.
+    pub fn from_cpuid<F: FnMut(u32, u32) -> Cpuid>(mut query: F) -> Option<Self> {
+        let hypervisor = Hypervisor::find(Vendor::Kvm, &mut query)?;
+
+        Some(Self(query(hypervisor.base() + 1, 0)))
+    }
+}
+
+features!(KvmFeatures, 0.eax {
+    /// Returns `true` if the kvmclock MSRs at `0x11` and `0x12` are available.
+    clocksource = 0,
+    /// Returns `true` if delays on I/O port `0x80` are not needed.
+    nop_io_delay = 1,
+    /// Returns `true` if the deprecated MMU hypercalls are available.
+    mmu_op = 2,
+    /// Returns `true` if the kvmclock MSRs at `0x4B56_4D00` and `0x4B56_4D01` are available.
+    clocksource2 = 3,
+    /// Returns `true` if asynchronous page faults are available.
+    async_pf = 4,
+    /// Returns `true` if steal time accounting is available.
+    steal_time = 5,
+    /// Returns `true` if paravirtualized end of interrupt is available.
+    pv_eoi = 6,
+    /// Returns `true` if paravirtualized spinlocks can halt and kick vCPUs.
+    pv_unhalt = 7,
+    /// Returns `true` if paravirtualized TLB flushes are available.
+    pv_tlb_flush = 9,
+    /// Returns `true` if asynchronous page faults can be delivered as VM exits.
+    async_pf_vmexit = 10,
+    /// Returns `true` if paravirtualized IPIs are available.
+    pv_send_ipi = 11,
+    /// Returns `true` if host-side halt polling can be disabled.
+    poll_control = 12,
+    /// Returns `true` if paravirtualized yields to preempted vCPUs are available.
+    pv_sched_yield = 13,
+    /// Returns `true` if asynchronous page faults can be delivered as interrupts.
+    async_pf_int = 14,
+    /// Returns `true` if MSI addresses can hold extended destination IDs.
+    msi_ext_dest_id = 15,
+    /// Returns `true` if the `MAP_GPA_RANGE` hypercall is available.
+    hc_map_gpa_range = 16,
+    /// Returns `true` if the migration control MSR is available.
+    migration_control = 17,
+    /// Returns `true` if the kvmclock is stable and consistent across vCPUs.
+    clocksource_stable = 24,
+});
+
+features!(KvmFeatures, 0.edx {
+    /// Returns `true` if vCPUs are never preempted for long, so that spinning is preferred.
+    realtime_hint = 0,
+});
+
+/// The features and enlightenments offered by a hypervisor implementing the Hyper-V interface,
+/// reported by leaves `0x4000_0003` and `0x4000_0004`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct HyperVFeatures {
+    /// Leaf `0x4000_0003`.
+    features: Cpuid,
+    /// Leaf `0x4000_0004`.
+    recommendations: Cpuid,
+}
+
+impl HyperVFeatures {
+    /// Returns the [`HyperVFeatures`] offered to this processor.
+    ///
+    /// Returns [`None`] if the processor does not support the `cpuid` instruction or is not
+    /// running under a hypervisor implementing the Hyper-V interface.
+    pub fn new() -> Option<Self> {
+        if !has_cpuid() {
+            return None;
+        }
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction.
+        Self::from_cpuid(|leaf, subleaf| unsafe { cpuid(leaf, subleaf) })
+    }
+
+    /// Decodes the [`HyperVFeatures`] from the [`Cpuid`]s returned by `query` for a leaf and
+    /// subleaf.
+    ///
+    /// Returns [`None`] if the hypervisor does not report the `Hv#1` interface signature in leaf
+    /// `0x4000_0001` or does not report leaf `0x4000_0004`.
+    pub fn from_cpuid<F: FnMut(u32, u32) -> Cpuid>(mut query: F) -> Option<Self> {
+        let hypervisor = Hypervisor::from_cpuid(&mut query)?;
+        if hypervisor.max_leaf() < BASE_LEAF + 4 || query(BASE_LEAF + 1, 0).eax != HYPERV_INTERFACE
+        {
+            return None;
+        }
+
+        Some(Self {
+            features: query(BASE_LEAF + 3, 0),
+            recommendations: query(BASE_LEAF + 4, 0),
+        })
+    }
+
+    /// Returns the number of attempts to acquire a spinlock before notifying the hypervisor.
+    ///
+    /// Returns [`None`] if the hypervisor should never be notified.
+    pub const fn spinlock_retries(&self) -> Option<u32> {
+        match self.recommendations.ebx {
+            u32::MAX => None,
+            retries => Some(retries),
+        }
+    }
+}
+
+features!(HyperVFeatures, features.eax {
+    /// Returns `true` if the virtual processor run time MSR is available.
+    vp_runtime = 0,
+    /// Returns `true` if the partition reference counter MSR is available.
+    time_reference_count = 1,
+    /// Returns `true` if the synthetic interrupt controller MSRs are available.
+    synic = 2,
+    /// Returns `true` if the synthetic timer MSRs are available.
+    synthetic_timers = 3,
+    /// Returns `true` if the APIC access MSRs are available.
+    apic_access = 4,
+    /// Returns `true` if the hypercall MSRs are available.
+    hypercall = 5,
+    /// Returns `true` if the virtual processor index MSR is available.
+    vp_index = 6,
+    /// Returns `true` if the virtual system reset MSR is available.
+    reset = 7,
+    /// Returns `true` if the statistics page MSRs are available.
+    stats = 8,
+    /// Returns `true` if the partition reference TSC MSR is available.
+    reference_tsc = 9,
+    /// Returns `true` if the guest idle MSR is available.
+    guest_idle = 10,
+    /// Returns `true` if the TSC and APIC timer frequency MSRs are available.
+    frequency_msrs = 11,
+    /// Returns `true` if the synthetic debugging MSRs are available.
+    debug_msrs = 12,
+    /// Returns `true` if the reenlightenment control MSRs are available.
+    reenlightenment = 13,
+});
+
+features!(HyperVFeatures, features.edx {
+    /// Returns `true` if the guest debugging interface is available.
+    guest_debugging = 1,
+    /// Returns `true` if performance monitoring is available.
+    performance_monitors = 2,
+    /// Returns `true` if XMM registers can be used to pass input to fast hypercalls.
+    xmm_hypercall_input = 4,
+    /// Returns `true` if the guest idle state is available.
+    guest_idle_state = 5,
+    /// Returns `true` if the NUMA distance query is available.
+    numa_distance_query = 7,
+    /// Returns `true` if the TSC and APIC timer frequencies can be queried.
+    frequency_regs = 8,
+    /// Returns `true` if synthetic machine checks are available.
+    synthetic_machine_check = 9,
+    /// Returns `true` if the guest crash MSRs are available.
+    guest_crash = 10,
+});
+
+features!(HyperVFeatures, recommendations.eax {
+    /// Returns `true` if address space switches should use a hypercall instead of `mov cr3`.
+    address_space_switch_recommended = 0,
+    /// Returns `true` if local TLB flushes should use a hypercall instead of `invlpg`.
+    local_tlb_flush_recommended = 1,
+    /// Returns `true` if remote TLB flushes should use a hypercall instead of IPIs.
+    remote_tlb_flush_recommended = 2,
+    /// Returns `true` if the APIC should be accessed through MSRs instead of MMIO.
+    apic_msr_access_recommended = 3,
+    /// Returns `true` if the system should be reset through the reset MSR.
+    system_reset_recommended = 4,
+    /// Returns `true` if watchdog timeouts should be relaxed.
+    relaxed_timing_recommended = 5,
+    /// Returns `true` if auto end of interrupt should not be used.
+    deprecating_aeoi_recommended = 9,
+    /// Returns `true` if IPIs should be sent with a hypercall.
+    cluster_ipi_recommended = 10,
+    /// Returns `true` if hypercalls should use sparse processor sets.
+    ex_processor_masks_recommended = 11,
+    /// Returns `true` if enlightened VMCS should be used by nested hypervisors.
+    enlightened_vmcs_recommended = 14,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::{HyperVFeatures, Hypervisor, KvmFeatures};
+    use crate::instructions::cpuid::{testing::regs, Cpuid, Vendor};
+
+    /// Returns the [`Cpuid`]s of a processor running under KVM, which reports its identity at
+    /// `0x4000_0100` and implements the Hyper-V interface at `0x4000_0000`.
+    fn query(leaf: u32, _: u32) -> Cpuid {
+        match leaf {
+            0x0 => regs(0x1, 0x756E_6547, 0x6C65_746E, 0x4965_6E69),
+            0x1 => regs(0x0009_06EA, 0x0, 0x8000_0000, 0x0),
+            0x4000_0000 => regs(0x4000_000B, 0x7263_694D, 0x666F_736F, 0x7648_2074),
+            0x4000_0001 => regs(0x3123_7648, 0x0, 0x0, 0x0),
+            0x4000_0003 => regs(0x0000_2E7F, 0x0, 0x0, 0x0000_0734),
+            0x4000_0004 => regs(0x0000_0E24, 0xFFFF_FFFF, 0x0, 0x0),
+            0x4000_0100 => regs(0x4000_0101, 0x4B4D_564B, 0x564B_4D56, 0x0000_004D),
+            0x4000_0101 => regs(0x0100_7AFB, 0x0, 0x0, 0x0),
+            _ => regs(0, 0, 0, 0),
+        }
+    }
+
+    #[test]
+    fn hypervisor() {
+        let hypervisor = Hypervisor::from_cpuid(query).unwrap();
+        assert_eq!(hypervisor.vendor(), Vendor::HyperV);
+        assert_eq!(hypervisor.max_leaf(), 0x4000_000B);
+
+        let kvm = Hypervisor::find(Vendor::Kvm, query).unwrap();
+        assert_eq!((kvm.base(), kvm.max_leaf()), (0x4000_0100, 0x4000_0101));
+        assert!(kvm.vendor().is_hypervisor());
+        assert_eq!(Hypervisor::find(Vendor::Xen, query), None);
+
+        let bare_metal = |leaf, subleaf| match leaf {
+            0x1 => regs(0x0009_06EA, 0x0, 0x7FFA_FBFF, 0x0),
+            _ => query(leaf, subleaf),
+        };
+        assert_eq!(Hypervisor::from_cpuid(bare_metal), None);
+        assert_eq!(KvmFeatures::from_cpuid(bare_metal), None);
+    }
+
+    #[test]
+    fn kvm() {
+        let features = KvmFeatures::from_cpuid(query).unwrap();
+        assert!(features.clocksource() && features.clocksource2() && features.clocksource_stable());
+        assert!(features.async_pf() && features.steal_time() && features.pv_eoi());
+        assert!(features.pv_unhalt() && features.pv_tlb_flush() && features.pv_send_ipi());
+        assert!(features.pv_sched_yield() && features.async_pf_int());
+        assert!(!features.mmu_op() && !features.async_pf_vmexit() && !features.realtime_hint());
+    }
+
+    #[test]
+    fn hyperv() {
+        let features = HyperVFeatures::from_cpuid(query).unwrap();
+        assert!(features.vp_runtime() && features.synic() && features.synthetic_timers());
+        assert!(features.hypercall() && features.vp_index() && features.reference_tsc());
+        assert!(features.guest_idle() && !features.reset() && !features.stats());
+        assert!(features.xmm_hypercall_input() && features.frequency_regs());
+        assert!(features.guest_crash() && !features.guest_debugging());
+        assert!(features.remote_tlb_flush_recommended() && features.relaxed_timing_recommended());
+        assert!(features.deprecating_aeoi_recommended() && features.cluster_ipi_recommended());
+        assert!(!features.local_tlb_flush_recommended());
+        assert_eq!(features.spinlock_retries(), None);
+
+        let kvm_only = |leaf, subleaf| match leaf {
+            0x4000_0000..=0x4000_00FF => regs(0, 0, 0, 0),
+            _ => query(leaf, subleaf),
+        };
+        assert_eq!(HyperVFeatures::from_cpuid(kvm_only), None);
+    }
+}
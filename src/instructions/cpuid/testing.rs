@@ -0,0 +1,8 @@
+//! Utilities used to test the CPUID decoders on the host.
+
+use crate::instructions::cpuid::Cpuid;
+
+/// Creates a [`Cpuid`] from its registers.
+pub const fn regs(eax: u32, ebx: u32, ecx: u32, edx: u32) -> Cpuid {
+    Cpuid { eax, ebx, ecx, edx }
+}
@@ -0,0 +1,395 @@
+//! Definitions to decode the topology of a processor through CPUID leaves `0xB`, `0x1F`, and
+//! `0x8000_001E`, falling back to leaves `0x1` and `0x4`.
+
+use crate::instructions::cpuid::{cpuid, has_cpuid, Cpuid};
+
+/// The maximum number of subleaves walked by [`Topology::from_extended()`].
+const MAX_SUBLEAVES: u32 = 16;
+
+/// A level of the processor topology, from the innermost to the outermost.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LevelType {
+    /// The logical processors sharing a core.
+    Smt = 1,
+    /// The cores.
+    Core = 2,
+    /// The modules.
+    Module = 3,
+    /// The tiles.
+    Tile = 4,
+    /// The dies.
+    Die = 5,
+    /// The groups of dies.
+    DieGroup = 6,
+}
+
+impl LevelType {
+    /// Every [`LevelType`], from the innermost to the outermost.
+    const ALL: [Self; 6] = [
+        Self::Smt,
+        Self::Core,
+        Self::Module,
+        Self::Tile,
+        Self::Die,
+        Self::DieGroup,
+    ];
+
+    /// Creates a [`LevelType`] from its encoding in leaf `0xB` or `0x1F`.
+    ///
+    /// Returns [`None`] if `val` does not encode a [`LevelType`].
+    pub const fn from_u8(val: u8) -> Option<Self> {
+        match val {
+            1 => Some(Self::Smt),
+            2 => Some(Self::Core),
+            3 => Some(Self::Module),
+            4 => Some(Self::Tile),
+            5 => Some(Self::Die),
+            6 => Some(Self::DieGroup),
+            _ => None,
+        }
+    }
+}
+
+/// The layout of the APIC IDs of a processor, made up of the number of bits below each
+/// enumerated [`LevelType`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Topology {
+    /// The shift of the next level ID for each [`LevelType`], if it is enumerated.
+    shifts: [Option<u8>; 6],
+    /// The APIC ID of the processor that decoded this [`Topology`].
+    apic_id: u32,
+}
+
+impl Topology {
+    /// Returns the [`Topology`] of this processor.
+    ///
+    /// Returns [`None`] if the processor does not support the `cpuid` instruction.
+    pub fn new() -> Option<Self> {
+        if !has_cpuid() {
+            return None;
+        }
+
+        // SAFETY:
+        // The processor supports the `cpuid` instruction.
+        Some(Self::from_cpuid(|leaf, subleaf| unsafe {
+            cpuid(leaf, subleaf)
+        }))
+    }
+
+    /// Decodes the [`Topology`] from the [`Cpuid`]s returned by `query` for a leaf and subleaf.
+    ///
+    /// Leaf `0x1F` is preferred over leaf `0xB`, and leaves `0x1` and `0x4` are used if neither
+    /// is supported.
+    pub fn from_cpuid<F: FnMut(u32, u32) -> Cpuid>(mut query: F) -> Self {
+        let max_leaf = query(0, 0).eax;
+
+        for leaf in [0x1F, 0xB] {
+            if max_leaf >= leaf {
+                if let Some(topology) = Self::from_extended(leaf, &mut query) {
+                    return topology;
+                }
+            }
+        }
+
+        let leaf_4 = if max_leaf >= 0x4 {
+            Some(query(0x4, 0))
+        } else {
+            None
+        };
+
+        Self::from_legacy(query(0x1, 0), leaf_4)
+    }
+
+    /// Decodes the [`Topology`] by walking the subleaves of `leaf`, which must be `0xB` or
+    /// `0x1F`, with the [`Cpuid`]s returned by `query`.
+    ///
+    /// Returns [`None`] if `leaf` is not supported by the processor.
+    pub fn from_extended<F: FnMut(u32, u32) -> Cpuid>(leaf: u32, mut query: F) -> Option<Self> {
+        let first = query(leaf, 0);
+        if first.ebx & 0xFFFF == 0 {
+            return None;
+        }
+
+        let mut shifts = [None; 6];
+        let mut subleaf = 0;
+        let mut current = first;
+        while subleaf < MAX_SUBLEAVES {
+            let Some(kind) = LevelType::from_u8((current.ecx >> 8) as u8) else {
+                break;
+            };
+
+            shifts[kind as usize - 1] = Some((current.eax & 0x1F) as u8);
+            subleaf += 1;
+            current = query(leaf, subleaf);
+        }
+
+        Some(Self {
+            shifts,
+            apic_id: first.edx,
+        })
+    }
+
+    /// Decodes the [`Topology`] of processors without leaf `0xB` from leaf `0x1` and, if it is
+    /// supported, leaf `0x4`.
+    ///
+    /// The SMT and core widths are derived from the maximum number of logical processors and
+    /// cores in the package, and the APIC ID is the 8-bit initial APIC ID.
+    pub const fn from_legacy(leaf_1: Cpuid, leaf_4: Option<Cpuid>) -> Self {
+        let logical = if leaf_1.edx & (1 << 28) == (1 << 28) {
+            (leaf_1.ebx >> 16) & 0xFF
+        } else {
+            1
+        };
+        let cores = match leaf_4 {
+            Some(leaf_4) => (leaf_4.eax >> 26) + 1,
+            None => 1,
+        };
+
+        let smt_width = width_of(logical / cores);
+        let core_width = width_of(cores);
+
+        let mut shifts = [None; 6];
+        shifts[LevelType::Smt as usize - 1] = Some(smt_width);
+        shifts[LevelType::Core as usize - 1] = Some(smt_width + core_width);
+
+        Self {
+            shifts,
+            apic_id: leaf_1.ebx >> 24,
+        }
+    }
+
+    /// Returns the APIC ID of the processor that decoded this [`Topology`].
+    pub const fn apic_id(&self) -> u32 {
+        self.apic_id
+    }
+
+    /// Returns the number of APIC ID bits below the level above `level`, which is the shift of
+    /// the ID of the next level.
+    ///
+    /// Returns [`None`] if `level` is not enumerated.
+    pub const fn shift(&self, level: LevelType) -> Option<u8> {
+        self.shifts[level as usize - 1]
+    }
+
+    /// Returns the number of APIC ID bits holding the ID of `level`.
+    ///
+    /// Returns [`None`] if `level` is not enumerated.
+    pub const fn width(&self, level: LevelType) -> Option<u8> {
+        let Some(shift) = self.shift(level) else {
+            return None;
+        };
+
+        let mut below = 0;
+        let mut index = 0;
+        while index < level as usize - 1 {
+            if let Some(shift) = self.shifts[index] {
+                below = shift;
+            }
+            index += 1;
+        }
+
+        Some(shift.saturating_sub(below))
+    }
+
+    /// Returns the number of APIC ID bits below the package ID.
+    pub const fn package_shift(&self) -> u8 {
+        let mut shift = 0;
+        let mut index = 0;
+        while index < self.shifts.len() {
+            if let Some(level) = self.shifts[index] {
+                shift = level;
+            }
+            index += 1;
+        }
+
+        shift
+    }
+
+    /// Splits `apic_id` into the IDs of each level of this [`Topology`].
+    ///
+    /// The IDs of levels that are not enumerated are 0.
+    pub fn split(&self, apic_id: u32) -> TopologyIds {
+        let mut ids = [0; 6];
+        let mut below = 0;
+        for level in LevelType::ALL {
+            if let Some(shift) = self.shift(level) {
+                let mask = 1u32
+                    .checked_shl(shift.saturating_sub(below).into())
+                    .unwrap_or(0)
+                    .wrapping_sub(1);
+                ids[level as usize - 1] = apic_id.checked_shr(below.into()).unwrap_or(0) & mask;
+                below = shift;
+            }
+        }
+
+        TopologyIds {
+            thread: ids[0],
+            core: ids[1],
+            module: ids[2],
+            tile: ids[3],
+            die: ids[4],
+            die_group: ids[5],
+            package: apic_id.checked_shr(below.into()).unwrap_or(0),
+        }
+    }
+}
+
+/// The IDs of each level of the topology of a logical processor, relative to the enclosing level.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct TopologyIds {
+    /// The ID of the logical processor within its core.
+    pub thread: u32,
+    /// The ID of the core within its module.
+    pub core: u32,
+    /// The ID of the module within its tile.
+    pub module: u32,
+    /// The ID of the tile within its die.
+    pub tile: u32,
+    /// The ID of the die within its group of dies.
+    pub die: u32,
+    /// The ID of the group of dies within its package.
+    pub die_group: u32,
+    /// The ID of the package.
+    pub package: u32,
+}
+
+/// The topology of an AMD or Hygon logical processor, reported by leaf `0x8000_001E`.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct AmdTopology(Cpuid);
+
+impl AmdTopology {
+    /// Decodes the [`AmdTopology`] reported by leaf `0x8000_001E`.
+    pub const fn from_cpuid(leaf: Cpuid) -> Self {
+        Self(leaf)
+    }
+
+    /// Returns the extended APIC ID of the logical processor.
+    pub const fn extended_apic_id(self) -> u32 {
+        self.0.eax
+    }
+
+    /// Returns the ID of the core, or compute unit, of the logical processor.
+    pub const fn core_id(self) -> u8 {
+        self.0.ebx as u8
+    }
+
+    /// Returns the number of logical processors per core, or compute unit.
+    pub const fn threads_per_core(self) -> u8 {
+        ((self.0.ebx >> 8) & 0xFF) as u8 + 1
+    }
+
+    /// Returns the ID of the node of the logical processor.
+    pub const fn node_id(self) -> u8 {
+        self.0.ecx as u8
+    }
+
+    /// Returns the number of nodes per package.
+    pub const fn nodes_per_package(self) -> u8 {
+        ((self.0.ecx >> 8) & 0b111) as u8 + 1
+    }
+}
+
+/// Returns the number of bits needed to hold `count` distinct IDs.
+const fn width_of(count: u32) -> u8 {
+    if count <= 1 {
+        0
+    } else {
+        (u32::BITS - (count - 1).leading_zeros()) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AmdTopology, LevelType, Topology, TopologyIds};
+    use crate::instructions::cpuid::testing::regs;
+
+    #[test]
+    fn extended() {
+        let query = |leaf, subleaf| match (leaf, subleaf) {
+            (0x0, _) => regs(0x1F, 0, 0, 0),
+            (0x1F, 0) => regs(0x1, 0x2, 0x100, 0x1B5),
+            (0x1F, 1) => regs(0x7, 0x80, 0x201, 0x1B5),
+            (0x1F, 2) => regs(0x8, 0x100, 0x502, 0x1B5),
+            (0x1F, 3) => regs(0x0, 0x0, 0x3, 0x1B5),
+            _ => panic!("unexpected leaf {leaf:#x}, subleaf {subleaf}"),
+        };
+
+        let topology = Topology::from_cpuid(query);
+        assert_eq!(topology.apic_id(), 0x1B5);
+        assert_eq!(topology.shift(LevelType::Core), Some(7));
+        assert_eq!(topology.width(LevelType::Core), Some(6));
+        assert_eq!(topology.width(LevelType::Die), Some(1));
+        assert_eq!(topology.shift(LevelType::Module), None);
+        assert_eq!(topology.package_shift(), 8);
+        assert_eq!(
+            topology.split(topology.apic_id()),
+            TopologyIds {
+                thread: 1,
+                core: 0x1A,
+                die: 1,
+                package: 1,
+                ..TopologyIds::default()
+            }
+        );
+
+        // Leaf 0xB is used if leaf 0x1F is beyond the maximum leaf.
+        let query = |leaf, subleaf| match (leaf, subleaf) {
+            (0x0, _) => regs(0x16, 0, 0, 0),
+            (0xB, 0) => regs(0x1, 0x2, 0x100, 0x7),
+            (0xB, 1) => regs(0x4, 0xC, 0x201, 0x7),
+            (0xB, _) => regs(0x0, 0x0, subleaf, 0x7),
+            _ => panic!("unexpected leaf {leaf:#x}, subleaf {subleaf}"),
+        };
+        let topology = Topology::from_cpuid(query);
+        assert_eq!(topology.package_shift(), 4);
+        assert_eq!(
+            topology.split(0x17),
+            TopologyIds {
+                thread: 1,
+                core: 3,
+                package: 1,
+                ..TopologyIds::default()
+            }
+        );
+    }
+
+    #[test]
+    fn legacy() {
+        let query = |leaf, _| match leaf {
+            0x0 => regs(0xA, 0, 0, 0),
+            0x1 => regs(0x0001_067A, 0x0310_0800, 0x0, 0x1000_0000),
+            0x4 => regs(0x1C00_4121, 0x01C0_003F, 0x3F, 0x0),
+            0xB => panic!("leaf 0xB is beyond the maximum leaf"),
+            _ => regs(0, 0, 0, 0),
+        };
+
+        let topology = Topology::from_cpuid(query);
+        assert_eq!(topology.apic_id(), 3);
+        assert_eq!(topology.width(LevelType::Smt), Some(1));
+        assert_eq!(topology.shift(LevelType::Core), Some(4));
+        assert_eq!(
+            topology.split(3),
+            TopologyIds {
+                thread: 1,
+                core: 1,
+                ..TopologyIds::default()
+            }
+        );
+
+        // Without HTT, there is a single logical processor per package.
+        let topology = Topology::from_legacy(regs(0x0000_0F29, 0x0000_0800, 0, 0), None);
+        assert_eq!(topology.package_shift(), 0);
+        assert_eq!(topology.split(5).package, 5);
+    }
+
+    #[test]
+    fn amd() {
+        let topology = AmdTopology::from_cpuid(regs(0x25, 0x0112, 0x0101, 0x0));
+        assert_eq!(topology.extended_apic_id(), 0x25);
+        assert_eq!(topology.core_id(), 0x12);
+        assert_eq!(topology.threads_per_core(), 2);
+        assert_eq!(topology.node_id(), 1);
+        assert_eq!(topology.nodes_per_package(), 2);
+    }
+}
@@ -4,3 +4,4 @@ pub mod cpuid;
 pub mod paging;
 pub mod port;
 pub mod random;
+pub mod tables;
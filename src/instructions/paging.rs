@@ -1,94 +1,552 @@
 //! Definitions and interfaces for `x86` and `x86_64` instructions related to paging.
 
+pub use crate::registers::control::Pcid;
+use crate::{
+    addr::VirtAddr,
+    instructions::cpuid::CpuFeatures,
+    registers::control::{Cr3, Cr4},
+    structures::paging::bits64::mapper::MapperFlush,
+};
+
 /// Invalidates the TLB entries for the page of `address`.
 ///
 /// Executes `invlpg` under the hood.
-pub fn invalidate_page(address: usize) {
+pub fn invalidate_page(address: VirtAddr) {
     // SAFETY:
     // Should not cause any problems if called repeatedly.
     unsafe {
         core::arch::asm!(
             "invlpg [{}]",
-            in(reg) address,
+            in(reg) address.as_u64() as usize,
             options(nomem, nostack, preserves_flags)
         )
     }
 }
 
+/// The TLB entries invalidated by `invpcid`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InvalidationKind {
+    /// The TLB entries for the address of the [`InvPcidDescriptor`] associated with its PCID,
+    /// except entries marked as global.
+    IndividualAddress = 0,
+    /// The TLB entries associated with the PCID of the [`InvPcidDescriptor`], except entries
+    /// marked as global.
+    SingleContext = 1,
+    /// All TLB entries, including entries marked as global.
+    AllContextsIncludingGlobal = 2,
+    /// All TLB entries, except entries marked as global.
+    AllContexts = 3,
+}
+
+impl InvalidationKind {
+    /// Creates an [`InvalidationKind`] from its `invpcid` type.
+    ///
+    /// Returns [`None`] if `val` is greater than 3.
+    pub const fn from_u8(val: u8) -> Option<Self> {
+        match val {
+            0 => Some(Self::IndividualAddress),
+            1 => Some(Self::SingleContext),
+            2 => Some(Self::AllContextsIncludingGlobal),
+            3 => Some(Self::AllContexts),
+            _ => None,
+        }
+    }
+}
+
+/// The memory operand of `invpcid`.
+///
+/// Bits 0 to 11 hold the PCID, bits 12 to 63 are reserved, and bits 64 to 127 hold the linear
+/// address.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct InvPcidDescriptor {
+    /// The PCID, zero extended to 64 bits.
+    pcid: u64,
+    /// The linear address.
+    address: u64,
+}
+
+impl InvPcidDescriptor {
+    /// Creates a new [`InvPcidDescriptor`] for `pcid` and `address`.
+    pub const fn new(pcid: Pcid, address: VirtAddr) -> Self {
+        Self {
+            pcid: pcid.as_u16() as u64,
+            address: address.as_u64(),
+        }
+    }
+
+    /// Creates a new [`InvPcidDescriptor`] for `pcid`, with its address set to 0.
+    ///
+    /// This is suitable for [`InvalidationKind::SingleContext`].
+    pub const fn pcid_only(pcid: Pcid) -> Self {
+        Self::new(pcid, VirtAddr::zero())
+    }
+
+    /// Creates a new [`InvPcidDescriptor`] with its PCID and address set to 0.
+    ///
+    /// This is suitable for [`InvalidationKind::AllContextsIncludingGlobal`] and
+    /// [`InvalidationKind::AllContexts`].
+    pub const fn zero() -> Self {
+        Self {
+            pcid: 0,
+            address: 0,
+        }
+    }
+
+    /// Returns the PCID of this [`InvPcidDescriptor`].
+    pub const fn pcid(self) -> Pcid {
+        Pcid::new_truncate(self.pcid as u16)
+    }
+
+    /// Returns the address of this [`InvPcidDescriptor`].
+    pub const fn address(self) -> VirtAddr {
+        VirtAddr::new_truncate_la57(self.address)
+    }
+}
+
 /// Invalidates the TLB entries for the page of `address` with PCID `pcid`.
 ///
 /// # Safety
 /// - The processor must have the `invpcid` CPUID feature.
-/// - `pcid` must be a valid PCID.
-/// - `address` must be a canonical address.
-pub unsafe fn invalid_pcid_address(pcid: u16, address: usize) {
-    debug_assert!(pcid < (1 << 12));
-
-    let descriptor = pcid as u128 | ((address as u128) << 64);
-
+/// - `address` must be canonical with respect to the paging mode in use.
+pub unsafe fn invalidate_pcid_address(pcid: Pcid, address: VirtAddr) {
     // SAFETY:
-    // - 0 is a valid `invalidation_type`.
-    // - `picd | (address << 64)` is a valid descriptor value for `invalidation_type` 0.
-    unsafe { invpcid(0, &descriptor) }
+    // - The processor has the `invpcid` CPUID feature.
+    // - `address` is canonical with respect to the paging mode in use.
+    unsafe {
+        invpcid(
+            InvalidationKind::IndividualAddress,
+            &InvPcidDescriptor::new(pcid, address),
+        )
+    }
 }
 
-/// Invalidates the TLB entries associated with PCID `pcid`.
+/// Invalidates the TLB entries associated with PCID `pcid`, except entries marked as global.
 ///
 /// # Safety
 /// - The processor must have the `invpcid` CPUID feature.
-/// - `pcid` must be a valid PCID.
-pub unsafe fn invalidate_pcid(pcid: u16) {
-    debug_assert!(pcid < (1 << 12));
-
-    let descriptor = pcid as u128;
+pub unsafe fn invalidate_pcid(pcid: Pcid) {
     // SAFETY:
-    // - 1 is a valid `invalidation_type`.
-    // - `pcid` is a valid descriptor value for `invalidation_type` 1.
-    unsafe { invpcid(1, &descriptor) }
+    // The processor has the `invpcid` CPUID feature.
+    unsafe {
+        invpcid(
+            InvalidationKind::SingleContext,
+            &InvPcidDescriptor::pcid_only(pcid),
+        )
+    }
 }
 
-/// Invalidates all TLB entries, including entries marked as global.
+/// Invalidates all TLB entries, except entries marked as global.
 ///
 /// # Safety
 /// - The processor must have the `invpcid` CPUID feature.
 pub unsafe fn invalidate_non_global() {
     // SAFETY:
-    // - 2 is a valid `invalidation_type`.
-    // - 0 is a valid descriptor value for `invalidation_type` 2.
-    unsafe { invpcid(2, &0) }
+    // The processor has the `invpcid` CPUID feature.
+    unsafe { invpcid(InvalidationKind::AllContexts, &InvPcidDescriptor::zero()) }
 }
 
-/// Invalidates all TLB entries, except entries marked as global.
+/// Invalidates all TLB entries, including entries marked as global.
 ///
 /// # Safety
 /// - The processor must have the `invpcid` CPUID feature.
 pub unsafe fn invalidate() {
     // SAFETY:
-    // - 3 is a valid `invalidation_type`.
-    // - 0 is a valid descriptor value for `invalidation_type` 3.
-    unsafe { invpcid(3, &0) }
+    // The processor has the `invpcid` CPUID feature.
+    unsafe {
+        invpcid(
+            InvalidationKind::AllContextsIncludingGlobal,
+            &InvPcidDescriptor::zero(),
+        )
+    }
 }
 
-/// Executes `invpcid` with the given `invalidation_type` using `descriptor`.
+/// Executes `invpcid` to invalidate the TLB entries selected by `kind` and `descriptor`.
 ///
 /// # Safety
-/// - `invalidation_type` must be be a valid `invpcid` type.
-/// - `descriptor` must be suitable for the `invpcid` call.
-unsafe fn invpcid(invalidation_type: usize, descriptor: *const u128) {
-    debug_assert!(invalidation_type < 4);
-    debug_assert!(crate::instructions::cpuid::has_cpuid());
-    // SAFETY:
-    // The `cpuid` instruction is available on this processor.
-    unsafe { debug_assert!((crate::instructions::cpuid::cpuid(0x7, 0x0).ebx >> 10) & 0b1 == 1) }
+/// - The processor must have the `invpcid` CPUID feature.
+/// - If `kind` is [`InvalidationKind::IndividualAddress`], the address of `descriptor` must be
+///   canonical with respect to the paging mode in use.
+pub unsafe fn invpcid(kind: InvalidationKind, descriptor: &InvPcidDescriptor) {
+    debug_assert!(CpuFeatures::new().is_some_and(|features| features.invpcid()));
 
     // SAFETY:
-    // The processor supports the `invpcid` CPUID feature.
+    // - The processor supports the `invpcid` CPUID feature.
+    // - `kind` is a valid `invpcid` type and `descriptor` is laid out as `invpcid` expects.
+    // - The address of `descriptor` is canonical if it is used.
     unsafe {
         core::arch::asm!(
             "invpcid {}, [{}]",
-            in(reg) invalidation_type,
-            in(reg) descriptor,
+            in(reg) kind as usize,
+            in(reg) core::ptr::from_ref(descriptor),
             options(readonly, nostack, preserves_flags)
         )
     }
 }
+
+/// The instructions used to invalidate TLB entries, selected from the features of the processor.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct FlushStrategy {
+    /// Whether the processor supports the `invpcid` instruction.
+    invpcid: bool,
+    /// The number of pages above which a range is invalidated by a full flush.
+    threshold: u64,
+}
+
+impl FlushStrategy {
+    /// The default number of pages above which a range is invalidated by a full flush.
+    pub const DEFAULT_THRESHOLD: u64 = 33;
+
+    /// Creates a new [`FlushStrategy`], using `invpcid` if the processor supports it.
+    pub fn new() -> Self {
+        CpuFeatures::new().map_or(
+            Self {
+                invpcid: false,
+                threshold: Self::DEFAULT_THRESHOLD,
+            },
+            |features| Self::from_features(&features),
+        )
+    }
+
+    /// Creates a new [`FlushStrategy`], using `invpcid` if `features` report that it is
+    /// supported.
+    ///
+    /// `invpcid` is not used if `features` are not [native](CpuFeatures::native).
+    pub const fn from_features(features: &CpuFeatures) -> Self {
+        Self {
+            invpcid: features.native() && features.invpcid(),
+            threshold: Self::DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// Creates a new [`FlushStrategy`] without checking whether the processor supports the
+    /// `invpcid` instruction.
+    ///
+    /// # Safety
+    /// If `invpcid` is `true`, the processor must support the `invpcid` instruction.
+    pub const unsafe fn new_unchecked(invpcid: bool) -> Self {
+        Self {
+            invpcid,
+            threshold: Self::DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// Returns `true` if this [`FlushStrategy`] uses the `invpcid` instruction.
+    pub const fn invpcid(self) -> bool {
+        self.invpcid
+    }
+
+    /// Returns the number of pages above which a range is invalidated by a full flush.
+    pub const fn threshold(self) -> u64 {
+        self.threshold
+    }
+
+    /// Sets the number of pages above which a range is invalidated by a full flush.
+    pub const fn set_threshold(mut self, threshold: u64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+}
+
+impl Default for FlushStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// TLB entries that must be invalidated after the paging hierarchy has been modified.
+///
+/// [`TlbFlush`]es can be combined with [`TlbFlush::merge()`] so that the TLB entries of several
+/// modifications are invalidated at once.
+#[must_use = "the TLB entries must be invalidated or explicitly ignored"]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TlbFlush {
+    /// The TLB entries of the page containing the given address in the current address space.
+    Page(VirtAddr),
+    /// The TLB entries of `pages` consecutive 4 KiB pages, starting with the page containing
+    /// `start`, in the current address space.
+    Range {
+        /// A virtual address in the first page.
+        start: VirtAddr,
+        /// The number of pages.
+        pages: u64,
+    },
+    /// Every TLB entry associated with the given PCID, except entries marked as global.
+    Pcid(Pcid),
+    /// Every TLB entry, except entries marked as global.
+    NonGlobal,
+    /// Every TLB entry, including entries marked as global.
+    All,
+}
+
+impl TlbFlush {
+    /// Returns a [`TlbFlush`] covering the TLB entries of both this [`TlbFlush`] and `other`.
+    ///
+    /// Pages are merged into the smallest range containing both of them, and any other
+    /// combination is widened to [`TlbFlush::NonGlobal`] or [`TlbFlush::All`].
+    pub fn merge(self, other: Self) -> Self {
+        match (self.range(), other.range()) {
+            (Some((start, pages)), Some((other_start, other_pages))) => {
+                // Page numbers are used, as the end of a range at the top of the address space
+                // is not a valid address.
+                let end = (start.as_u64() >> 12).saturating_add(pages);
+                let other_end = (other_start.as_u64() >> 12).saturating_add(other_pages);
+                let start = start.min(other_start);
+
+                Self::Range {
+                    start,
+                    pages: end.max(other_end) - (start.as_u64() >> 12),
+                }
+            }
+            _ if self == Self::All || other == Self::All => Self::All,
+            // Ranges may include pages marked as global.
+            (Some(_), _) | (_, Some(_)) => Self::All,
+            _ if self == other => self,
+            _ => Self::NonGlobal,
+        }
+    }
+
+    /// Invalidates the TLB entries of this [`TlbFlush`] using `strategy`.
+    ///
+    /// A range of more than [`FlushStrategy::threshold()`] pages is invalidated by flushing every
+    /// TLB entry, including entries marked as global. Without `invpcid`, the TLB entries of a
+    /// PCID are invalidated by flushing every TLB entry.
+    pub fn flush(self, strategy: FlushStrategy) {
+        match self.resolve(strategy) {
+            Self::Page(address) => invalidate_page(address),
+            flush @ Self::Range { .. } => flush.pages().for_each(invalidate_page),
+            Self::Pcid(pcid) => {
+                if strategy.invpcid {
+                    // SAFETY:
+                    // The processor supports the `invpcid` instruction.
+                    unsafe { invalidate_pcid(pcid) }
+                } else {
+                    toggle_global_pages();
+                }
+            }
+            Self::NonGlobal => {
+                if strategy.invpcid {
+                    // SAFETY:
+                    // The processor supports the `invpcid` instruction.
+                    unsafe { invalidate_non_global() }
+                } else if Cr4::get().contains(Cr4::PCID_ENABLE) {
+                    toggle_global_pages();
+                } else {
+                    reload_cr3();
+                }
+            }
+            Self::All => {
+                if strategy.invpcid {
+                    // SAFETY:
+                    // The processor supports the `invpcid` instruction.
+                    unsafe { invalidate() }
+                } else {
+                    toggle_global_pages();
+                }
+            }
+        }
+    }
+
+    /// Ignores the TLB entries of this [`TlbFlush`].
+    ///
+    /// This is correct if the modified paging hierarchy is not active on any processor, or if
+    /// the TLB entries are invalidated by other means.
+    pub fn ignore(self) {}
+
+    /// Returns the [`TlbFlush`] actually performed by [`TlbFlush::flush()`] with `strategy`.
+    fn resolve(self, strategy: FlushStrategy) -> Self {
+        match self {
+            Self::Range { pages, .. } if pages > strategy.threshold => Self::All,
+            flush => flush,
+        }
+    }
+
+    /// Returns the address of the first page and the number of pages of a [`TlbFlush::Page`] or
+    /// [`TlbFlush::Range`].
+    fn range(self) -> Option<(VirtAddr, u64)> {
+        match self {
+            Self::Page(address) => Some((address.align_down(0x1000), 1)),
+            Self::Range { start, pages } => Some((start.align_down(0x1000), pages)),
+            _ => None,
+        }
+    }
+
+    /// Returns the addresses of the pages of a [`TlbFlush::Page`] or [`TlbFlush::Range`].
+    ///
+    /// The pages stop early at the top of the address space.
+    fn pages(self) -> impl Iterator<Item = VirtAddr> {
+        let (start, pages) = self.range().unwrap_or((VirtAddr::zero(), 0));
+
+        core::iter::successors(Some(start), |address| address.checked_add(0x1000))
+            .zip(0..pages)
+            .map(|(address, _)| address)
+    }
+}
+
+impl From<MapperFlush> for TlbFlush {
+    fn from(flush: MapperFlush) -> Self {
+        Self::Page(flush.page())
+    }
+}
+
+/// Invalidates every TLB entry of the current PCID, except entries marked as global, by
+/// reloading CR3.
+fn reload_cr3() {
+    // SAFETY:
+    // Writing back the current value of CR3 only invalidates TLB entries.
+    unsafe { Cr3::set(Cr3::get()) }
+}
+
+/// Invalidates every TLB entry, including entries marked as global and entries of other PCIDs.
+///
+/// Changing [`Cr4::PAGE_GLOBAL_ENABLE`] invalidates every TLB entry, so it is toggled twice if
+/// global pages or PCIDs are enabled. Otherwise, reloading CR3 is sufficient.
+fn toggle_global_pages() {
+    let cr4 = Cr4::get();
+    if cr4.contains(Cr4::PAGE_GLOBAL_ENABLE) {
+        // SAFETY:
+        // Clearing `PAGE_GLOBAL_ENABLE` is always valid.
+        unsafe { Cr4::set(cr4.set_flags(Cr4::PAGE_GLOBAL_ENABLE, false)) }
+    } else if cr4.contains(Cr4::PCID_ENABLE) {
+        // SAFETY:
+        // PCIDs are only available in 64-bit mode, and every processor supporting 64-bit mode
+        // supports global pages.
+        unsafe { Cr4::set(cr4.set_flags(Cr4::PAGE_GLOBAL_ENABLE, true)) }
+    } else {
+        reload_cr3();
+        return;
+    }
+    // SAFETY:
+    // `CR4` is restored to its original value.
+    unsafe { Cr4::set(cr4) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FlushStrategy, InvPcidDescriptor, InvalidationKind, Pcid, TlbFlush};
+    use crate::structures::paging::bits64::testing::virt;
+
+    #[test]
+    fn merge() {
+        let page = TlbFlush::Page(virt(0x5123));
+        assert_eq!(
+            page.merge(TlbFlush::Page(virt(0x2000))),
+            TlbFlush::Range {
+                start: virt(0x2000),
+                pages: 4,
+            }
+        );
+
+        let top = TlbFlush::Page(virt(0xFFFF_FFFF_FFFF_F000));
+        let below = TlbFlush::Page(virt(0xFFFF_FFFF_FFFF_E123));
+        assert_eq!(
+            top.merge(below),
+            TlbFlush::Range {
+                start: virt(0xFFFF_FFFF_FFFF_E000),
+                pages: 2,
+            }
+        );
+        assert_eq!(
+            top.merge(top),
+            TlbFlush::Range {
+                start: virt(0xFFFF_FFFF_FFFF_F000),
+                pages: 1
+            }
+        );
+        assert_eq!(page.merge(TlbFlush::NonGlobal), TlbFlush::All);
+        let (three, four) = (Pcid::new(3).unwrap(), Pcid::new(4).unwrap());
+        assert_eq!(
+            TlbFlush::Pcid(three).merge(TlbFlush::Pcid(three)),
+            TlbFlush::Pcid(three)
+        );
+        assert_eq!(
+            TlbFlush::Pcid(three).merge(TlbFlush::Pcid(four)),
+            TlbFlush::NonGlobal
+        );
+        assert_eq!(TlbFlush::NonGlobal.merge(TlbFlush::All), TlbFlush::All);
+    }
+
+    #[test]
+    fn threshold() {
+        // SAFETY:
+        // `invpcid` is not used.
+        let strategy = unsafe { FlushStrategy::new_unchecked(false) }.set_threshold(4);
+
+        let range = TlbFlush::Range {
+            start: virt(0x1000),
+            pages: 4,
+        };
+        assert_eq!(range.resolve(strategy), range);
+
+        let range = TlbFlush::Range {
+            start: virt(0x1000),
+            pages: 5,
+        };
+        assert_eq!(range.resolve(strategy), TlbFlush::All);
+    }
+
+    #[test]
+    fn pages() {
+        let range = TlbFlush::Range {
+            start: virt(0x1800),
+            pages: 2,
+        };
+        assert!(range.pages().eq([virt(0x1000), virt(0x2000)]));
+
+        let top = TlbFlush::Range {
+            start: virt(0xFFFF_FFFF_FFFF_E000),
+            pages: 4,
+        };
+        assert!(top
+            .pages()
+            .eq([virt(0xFFFF_FFFF_FFFF_E000), virt(0xFFFF_FFFF_FFFF_F000)]));
+        assert_eq!(TlbFlush::NonGlobal.pages().count(), 0);
+    }
+
+    #[test]
+    fn pcid() {
+        assert_eq!(Pcid::new(0xFFF).map(Pcid::as_u16), Some(0xFFF));
+        assert_eq!(Pcid::new(0x1000), None);
+
+        assert_eq!(
+            InvalidationKind::from_u8(0),
+            Some(InvalidationKind::IndividualAddress)
+        );
+        assert_eq!(
+            InvalidationKind::from_u8(1),
+            Some(InvalidationKind::SingleContext)
+        );
+        assert_eq!(
+            InvalidationKind::from_u8(2),
+            Some(InvalidationKind::AllContextsIncludingGlobal)
+        );
+        assert_eq!(
+            InvalidationKind::from_u8(3),
+            Some(InvalidationKind::AllContexts)
+        );
+        assert_eq!(InvalidationKind::from_u8(4), None);
+    }
+
+    #[test]
+    fn descriptor() {
+        /// Returns the memory representation of `descriptor`.
+        fn raw(descriptor: InvPcidDescriptor) -> u128 {
+            // SAFETY:
+            // `InvPcidDescriptor` is made up of two `u64`s without padding.
+            let [low, high] =
+                unsafe { core::mem::transmute::<InvPcidDescriptor, [u64; 2]>(descriptor) };
+            (low as u128) | ((high as u128) << 64)
+        }
+
+        assert_eq!(core::mem::size_of::<InvPcidDescriptor>(), 16);
+
+        let pcid = Pcid::new(0xABC).unwrap();
+        let descriptor = InvPcidDescriptor::new(pcid, virt(0xFFFF_8000_1234_5000));
+        assert_eq!(raw(descriptor), 0xFFFF_8000_1234_5000_0000_0000_0000_0ABC);
+        assert_eq!(descriptor.pcid(), pcid);
+        assert_eq!(descriptor.address(), virt(0xFFFF_8000_1234_5000));
+
+        assert_eq!(raw(InvPcidDescriptor::pcid_only(pcid)), 0xABC);
+        assert_eq!(raw(InvPcidDescriptor::zero()), 0);
+    }
+}
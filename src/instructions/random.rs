@@ -2,7 +2,7 @@
 
 use core::arch::asm;
 
-use crate::instructions::cpuid::{cpuid, has_cpuid};
+use crate::instructions::cpuid::CpuFeatures;
 
 /// Interface to the `rdrand` instruction, which is a hardware RNG.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -13,16 +13,15 @@ impl RdRand {
     ///
     /// If the `rdrand` instruction is not supported, then this function returns [`None`].
     pub fn new() -> Option<Self> {
-        if !has_cpuid() {
-            return None;
-        }
-
-        // SAFETY:
-        // CPUID is supported.
-        let cpuid_ebx = unsafe { cpuid(1, 0).ecx };
+        Self::from_features(&CpuFeatures::new()?)
+    }
 
-        #[allow(clippy::nonminimal_bool)]
-        if !(cpuid_ebx & (1 << 30) == (1 << 30)) {
+    /// Creates a new [`RdRand`] if `features` report that the `rdrand` instruction is supported.
+    ///
+    /// Returns [`None`] if `features` are not [native](CpuFeatures::native) or do not report the
+    /// `rdrand` instruction.
+    pub const fn from_features(features: &CpuFeatures) -> Option<Self> {
+        if !features.native() || !features.rdrand() {
             return None;
         }
 
@@ -117,16 +116,15 @@ impl RdSeed {
     ///
     /// If the `rdseed` instruction is not supported, then this function returns [`None`].
     pub fn new() -> Option<Self> {
-        if !has_cpuid() {
-            return None;
-        }
-
-        // SAFETY:
-        // CPUID is supported.
-        let cpuid_ebx = unsafe { cpuid(7, 0).ebx };
+        Self::from_features(&CpuFeatures::new()?)
+    }
 
-        #[allow(clippy::nonminimal_bool)]
-        if !(cpuid_ebx & (1 << 18) == (1 << 18)) {
+    /// Creates a new [`RdSeed`] if `features` report that the `rdseed` instruction is supported.
+    ///
+    /// Returns [`None`] if `features` are not [native](CpuFeatures::native) or do not report the
+    /// `rdseed` instruction.
+    pub const fn from_features(features: &CpuFeatures) -> Option<Self> {
+        if !features.native() || !features.rdseed() {
             return None;
         }
 
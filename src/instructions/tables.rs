@@ -0,0 +1,161 @@
+//! Definitions and interfaces for `x86` and `x86_64` instructions related to descriptor tables.
+
+use core::arch::asm;
+
+use crate::{
+    registers::segmentation::SegmentSelector, structures::descriptor_table::DescriptorTablePointer,
+    Current,
+};
+
+/// Loads the global descriptor table register with `pointer`.
+///
+/// Executes `lgdt` under the hood.
+///
+/// # Safety
+/// - `pointer` must refer to a valid global descriptor table.
+/// - The global descriptor table must remain valid for as long as it is loaded.
+pub unsafe fn lgdt(pointer: &DescriptorTablePointer<Current>) {
+    // SAFETY:
+    // According to the invariants of this function, this is safe to run.
+    unsafe {
+        asm!(
+            "lgdt [{}]",
+            in(reg) pointer,
+            options(readonly, nostack, preserves_flags)
+        )
+    }
+}
+
+/// Loads the interrupt descriptor table register with `pointer`.
+///
+/// Executes `lidt` under the hood.
+///
+/// # Safety
+/// - `pointer` must refer to a valid interrupt descriptor table.
+/// - The interrupt descriptor table must remain valid for as long as it is loaded.
+pub unsafe fn lidt(pointer: &DescriptorTablePointer<Current>) {
+    // SAFETY:
+    // According to the invariants of this function, this is safe to run.
+    unsafe {
+        asm!(
+            "lidt [{}]",
+            in(reg) pointer,
+            options(readonly, nostack, preserves_flags)
+        )
+    }
+}
+
+/// Returns the current value of the global descriptor table register.
+///
+/// Executes `sgdt` under the hood.
+pub fn sgdt() -> DescriptorTablePointer<Current> {
+    let mut pointer = DescriptorTablePointer::new(0, 0);
+
+    // SAFETY:
+    // `sgdt` only writes to `pointer`, which is large enough to hold the result.
+    unsafe {
+        asm!(
+            "sgdt [{}]",
+            in(reg) &mut pointer,
+            options(nostack, preserves_flags)
+        )
+    }
+
+    pointer
+}
+
+/// Returns the current value of the interrupt descriptor table register.
+///
+/// Executes `sidt` under the hood.
+pub fn sidt() -> DescriptorTablePointer<Current> {
+    let mut pointer = DescriptorTablePointer::new(0, 0);
+
+    // SAFETY:
+    // `sidt` only writes to `pointer`, which is large enough to hold the result.
+    unsafe {
+        asm!(
+            "sidt [{}]",
+            in(reg) &mut pointer,
+            options(nostack, preserves_flags)
+        )
+    }
+
+    pointer
+}
+
+/// Loads the local descriptor table register with `selector`.
+///
+/// Executes `lldt` under the hood.
+///
+/// # Safety
+/// - `selector` must refer to a valid LDT descriptor in the global descriptor table, or be a
+///   null [`SegmentSelector`].
+/// - The local descriptor table must remain valid for as long as it is loaded.
+pub unsafe fn lldt(selector: SegmentSelector) {
+    // SAFETY:
+    // According to the invariants of this function, this is safe to run.
+    unsafe {
+        asm!(
+            "lldt {:x}",
+            in(reg) selector.to_raw(),
+            options(nostack, preserves_flags)
+        )
+    }
+}
+
+/// Returns the [`SegmentSelector`] currently loaded into the local descriptor table register.
+///
+/// Executes `sldt` under the hood.
+pub fn sldt() -> SegmentSelector {
+    let selector: u16;
+
+    // SAFETY:
+    // Reading the local descriptor table register does not adversely affect the processor.
+    unsafe {
+        asm!(
+            "sldt {:x}",
+            out(reg) selector,
+            options(nomem, nostack, preserves_flags)
+        )
+    }
+
+    SegmentSelector::from_raw(selector)
+}
+
+/// Loads the task register with `selector`.
+///
+/// Executes `ltr` under the hood. The processor marks the referenced TSS descriptor as busy.
+///
+/// # Safety
+/// - `selector` must refer to a valid, available TSS descriptor in the global descriptor table.
+/// - The task state segment must remain valid for as long as it is loaded.
+pub unsafe fn ltr(selector: SegmentSelector) {
+    // SAFETY:
+    // According to the invariants of this function, this is safe to run.
+    unsafe {
+        asm!(
+            "ltr {:x}",
+            in(reg) selector.to_raw(),
+            options(nostack, preserves_flags)
+        )
+    }
+}
+
+/// Returns the [`SegmentSelector`] currently loaded into the task register.
+///
+/// Executes `str` under the hood.
+pub fn str() -> SegmentSelector {
+    let selector: u16;
+
+    // SAFETY:
+    // Reading the task register does not adversely affect the processor.
+    unsafe {
+        asm!(
+            "str {:x}",
+            out(reg) selector,
+            options(nomem, nostack, preserves_flags)
+        )
+    }
+
+    SegmentSelector::from_raw(selector)
+}
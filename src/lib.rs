@@ -6,6 +6,7 @@
 
 use core::fmt;
 
+pub mod addr;
 #[cfg(feature = "instructions")]
 pub mod instructions;
 pub mod registers;
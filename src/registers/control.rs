@@ -0,0 +1,434 @@
+//! Definitions and interfaces to interact with the `x86` and `x86_64` control registers.
+
+use crate::addr::PhysAddr;
+#[cfg(feature = "instructions")]
+use crate::addr::VirtAddr;
+
+register_flags! {
+    /// The `CR0` register, which controls the operating mode and state of the processor.
+    Cr0 {
+        /// Enables protected mode.
+        PROTECTION_ENABLE = 0,
+        /// Controls the interaction of `wait` with [`Cr0::TASK_SWITCHED`].
+        MONITOR_COPROCESSOR = 1,
+        /// Indicates that the processor does not have an x87 FPU, so that x87 instructions raise
+        /// a device-not-available exception.
+        EMULATION = 2,
+        /// Set on every task switch, so that the x87 FPU, MMX, and SSE state can be saved lazily.
+        TASK_SWITCHED = 3,
+        /// Indicates support for Intel 387 DX math coprocessor instructions.
+        ///
+        /// This bit is hardcoded to 1 on modern processors.
+        EXTENSION_TYPE = 4,
+        /// Enables native reporting of x87 FPU errors.
+        NUMERIC_ERROR = 5,
+        /// Prevents supervisor-mode code from writing to read-only pages.
+        WRITE_PROTECT = 16,
+        /// Enables alignment checking when [`Flags::ALIGNMENT_CHECK`][ac] is set.
+        ///
+        /// [ac]: crate::registers::flags::Flags::ALIGNMENT_CHECK
+        ALIGNMENT_MASK = 18,
+        /// Disables write-through caching.
+        NOT_WRITE_THROUGH = 29,
+        /// Disables the memory cache.
+        CACHE_DISABLE = 30,
+        /// Enables paging.
+        PAGING = 31,
+    }
+}
+
+register_flags! {
+    /// The `CR4` register, which enables architectural extensions.
+    Cr4 {
+        /// Enables interrupt and exception handling extensions in virtual-8086 mode.
+        VIRTUAL_8086_MODE_EXTENSIONS = 0,
+        /// Enables the virtual interrupt flag in protected mode.
+        PROTECTED_MODE_VIRTUAL_INTERRUPTS = 1,
+        /// Restricts `rdtsc` and `rdtscp` to ring 0.
+        TIMESTAMP_DISABLE = 2,
+        /// Enables I/O breakpoints and makes references to `DR4` and `DR5` fault.
+        DEBUGGING_EXTENSIONS = 3,
+        /// Enables 4 MiB pages with 32-bit paging.
+        PAGE_SIZE_EXTENSION = 4,
+        /// Enables physical address extension paging.
+        PHYSICAL_ADDRESS_EXTENSION = 5,
+        /// Enables the machine-check exception.
+        MACHINE_CHECK_ENABLE = 6,
+        /// Enables global pages.
+        PAGE_GLOBAL_ENABLE = 7,
+        /// Allows `rdpmc` outside of ring 0.
+        PERFORMANCE_COUNTER_ENABLE = 8,
+        /// Indicates operating system support for `fxsave` and `fxrstor`.
+        OS_FXSR = 9,
+        /// Indicates operating system support for unmasked SIMD floating-point exceptions.
+        OS_XMM_EXCEPTIONS = 10,
+        /// Restricts `sgdt`, `sidt`, `sldt`, `smsw`, and `str` to ring 0.
+        USER_MODE_INSTRUCTION_PREVENTION = 11,
+        /// Enables 5-level paging.
+        LA57 = 12,
+        /// Enables VMX operation.
+        VMX_ENABLE = 13,
+        /// Enables SMX operation.
+        SMX_ENABLE = 14,
+        /// Enables `rdfsbase`, `rdgsbase`, `wrfsbase`, and `wrgsbase`.
+        FSGSBASE = 16,
+        /// Enables process-context identifiers.
+        PCID_ENABLE = 17,
+        /// Indicates operating system support for `xsave` and the `XCR0` register.
+        OS_XSAVE = 18,
+        /// Enables the Key Locker instructions.
+        KEY_LOCKER = 19,
+        /// Enables supervisor-mode execution prevention.
+        SUPERVISOR_MODE_EXECUTION_PREVENTION = 20,
+        /// Enables supervisor-mode access prevention.
+        SUPERVISOR_MODE_ACCESS_PREVENTION = 21,
+        /// Enables protection keys for user-mode pages.
+        PROTECTION_KEYS_USER = 22,
+        /// Enables control-flow enforcement technology.
+        CONTROL_FLOW_ENFORCEMENT = 23,
+        /// Enables protection keys for supervisor-mode pages.
+        PROTECTION_KEYS_SUPERVISOR = 24,
+        /// Enables user interrupts.
+        USER_INTERRUPTS = 25,
+    }
+}
+
+/// Generates `get`, `set`, and `update` for a control register holding a value of `$ty`.
+#[cfg(feature = "instructions")]
+macro_rules! control_access {
+    ($ty:ident, $register:literal, $safety:literal) => {
+        impl $ty {
+            #[doc = concat!("Returns the current value of the `", $register, "` register.")]
+            pub fn get() -> Self {
+                let value: usize;
+                // SAFETY:
+                // Reading from a control register does not adversely affect the processor.
+                unsafe {
+                    core::arch::asm!(
+                        concat!("mov {}, ", $register),
+                        out(reg) value,
+                        options(nomem, nostack, preserves_flags)
+                    )
+                }
+
+                Self(value as u64)
+            }
+
+            #[doc = concat!("Loads the `", $register, "` register with `value`.")]
+            ///
+            /// # Safety
+            /// - Loading `value` will not cause undefined behavior.
+            #[doc = concat!("- ", $safety)]
+            pub unsafe fn set(value: Self) {
+                // SAFETY:
+                // Loading `value` will not cause undefined behavior.
+                unsafe {
+                    core::arch::asm!(
+                        concat!("mov ", $register, ", {}"),
+                        in(reg) value.0 as usize,
+                        options(nostack, preserves_flags)
+                    )
+                }
+            }
+
+            #[doc = concat!("Updates the `", $register, "` register with the value returned by `f`.")]
+            ///
+            /// # Safety
+            #[doc = concat!("See [`", stringify!($ty), "::set()`].")]
+            pub unsafe fn update<F: FnOnce(Self) -> Self>(f: F) {
+                // SAFETY:
+                // The invariants of `set()` are upheld by the caller.
+                unsafe { Self::set(f(Self::get())) }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "instructions")]
+control_access!(
+    Cr0,
+    "cr0",
+    "Clearing [`Cr0::PAGING`] or [`Cr0::PROTECTION_ENABLE`] must be done from identity mapped code."
+);
+
+#[cfg(feature = "instructions")]
+control_access!(
+    Cr4,
+    "cr4",
+    "Every extension enabled by `value` must be supported by the processor."
+);
+
+/// The `CR2` register, which holds the linear address that caused the last page fault.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cr2;
+
+impl Cr2 {
+    /// Returns the linear address that caused the last page fault.
+    #[cfg(feature = "instructions")]
+    pub fn get() -> VirtAddr {
+        let value: usize;
+        // SAFETY:
+        // Reading from the `CR2` register does not adversely affect the processor.
+        unsafe {
+            core::arch::asm!(
+                "mov {}, cr2",
+                out(reg) value,
+                options(nomem, nostack, preserves_flags)
+            )
+        }
+
+        VirtAddr::new_truncate_la57(value as u64)
+    }
+}
+
+/// A process-context identifier, which tags the TLB entries of an address space when
+/// [`Cr4::PCID_ENABLE`] is set.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Pcid(u16);
+
+impl Pcid {
+    /// Creates a new [`Pcid`].
+    ///
+    /// Returns [`None`] if `pcid` is not less than 4096.
+    pub const fn new(pcid: u16) -> Option<Self> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(pcid < (1 << 12)) {
+            return None;
+        }
+
+        Some(Self(pcid))
+    }
+
+    /// Creates a new [`Pcid`] from the low 12 bits of `pcid`.
+    pub(crate) const fn new_truncate(pcid: u16) -> Self {
+        Self(pcid & 0xFFF)
+    }
+
+    /// Returns the value of this [`Pcid`].
+    pub const fn as_u16(self) -> u16 {
+        self.0
+    }
+}
+
+/// The `CR3` register, which holds the physical address of the root of the paging hierarchy.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Cr3(u64);
+
+#[allow(clippy::missing_docs_in_private_items)]
+impl Cr3 {
+    const WRITE_THROUGH_BIT: u64 = 1 << 3;
+    const CACHE_DISABLE_BIT: u64 = 1 << 4;
+    const FRAME_MASK: u64 = 0x000F_FFFF_FFFF_F000;
+    const PCID_MASK: u64 = 0xFFF;
+    const NO_FLUSH_BIT: u64 = 1 << 63;
+}
+
+impl Cr3 {
+    /// Creates a new [`Cr3`] referring to the root of the paging hierarchy located at `frame`.
+    ///
+    /// The bits of `frame` below 4 KiB are ignored.
+    pub const fn new(frame: PhysAddr) -> Self {
+        Self(frame.as_u64() & Self::FRAME_MASK)
+    }
+
+    /// Creates a new [`Cr3`] from the raw value of the register.
+    pub const fn from_raw(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw value of the register.
+    pub const fn to_raw(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the physical address of the root of the paging hierarchy.
+    pub const fn frame(self) -> PhysAddr {
+        PhysAddr::new_truncate(self.0 & Self::FRAME_MASK)
+    }
+
+    /// Sets the physical address of the root of the paging hierarchy.
+    ///
+    /// The bits of `frame` below 4 KiB are ignored.
+    pub const fn set_frame(mut self, frame: PhysAddr) -> Self {
+        self.0 = (self.0 & !Self::FRAME_MASK) | (frame.as_u64() & Self::FRAME_MASK);
+        self
+    }
+
+    /// Returns the [`Pcid`] of the current address space.
+    ///
+    /// This is only meaningful if [`Cr4::PCID_ENABLE`] is set.
+    pub const fn pcid(self) -> Pcid {
+        Pcid::new_truncate(self.0 as u16)
+    }
+
+    /// Sets the [`Pcid`] of the current address space.
+    ///
+    /// This is only meaningful if [`Cr4::PCID_ENABLE`] is set, and clears the write through and
+    /// cache disable bits.
+    pub const fn set_pcid(mut self, pcid: Pcid) -> Self {
+        self.0 = (self.0 & !Self::PCID_MASK) | pcid.as_u16() as u64;
+        self
+    }
+
+    /// Returns `true` if loading this [`Cr3`] preserves the TLB entries of its [`Pcid`].
+    ///
+    /// The processor always reads this bit as 0.
+    pub const fn no_flush(self) -> bool {
+        self.0 & Self::NO_FLUSH_BIT == Self::NO_FLUSH_BIT
+    }
+
+    /// Sets whether loading this [`Cr3`] preserves the TLB entries of its [`Pcid`].
+    ///
+    /// This is only meaningful if [`Cr4::PCID_ENABLE`] is set.
+    pub const fn set_no_flush(mut self, no_flush: bool) -> Self {
+        self.0 = (self.0 & !Self::NO_FLUSH_BIT) | ((no_flush as u64) << 63);
+        self
+    }
+
+    /// Returns `true` if the write through bit is set.
+    ///
+    /// This bit determines the memory type used to access the root of the paging hierarchy if
+    /// [`Cr4::PCID_ENABLE`] is clear.
+    pub const fn write_through(self) -> bool {
+        self.0 & Self::WRITE_THROUGH_BIT == Self::WRITE_THROUGH_BIT
+    }
+
+    /// Sets the write through bit.
+    ///
+    /// This bit determines the memory type used to access the root of the paging hierarchy if
+    /// [`Cr4::PCID_ENABLE`] is clear.
+    pub const fn set_write_through(mut self, write_through: bool) -> Self {
+        self.0 = (self.0 & !Self::WRITE_THROUGH_BIT) | ((write_through as u64) << 3);
+        self
+    }
+
+    /// Returns `true` if the cache disable bit is set.
+    ///
+    /// This bit determines the memory type used to access the root of the paging hierarchy if
+    /// [`Cr4::PCID_ENABLE`] is clear.
+    pub const fn cache_disable(self) -> bool {
+        self.0 & Self::CACHE_DISABLE_BIT == Self::CACHE_DISABLE_BIT
+    }
+
+    /// Sets the cache disable bit.
+    ///
+    /// This bit determines the memory type used to access the root of the paging hierarchy if
+    /// [`Cr4::PCID_ENABLE`] is clear.
+    pub const fn set_cache_disable(mut self, cache_disable: bool) -> Self {
+        self.0 = (self.0 & !Self::CACHE_DISABLE_BIT) | ((cache_disable as u64) << 4);
+        self
+    }
+}
+
+#[cfg(feature = "instructions")]
+control_access!(
+    Cr3,
+    "cr3",
+    "The paging hierarchy referred to by `value` must map the code being executed."
+);
+
+/// The `CR8` register, which holds the task priority used to mask external interrupts.
+///
+/// This register is only available in 64-bit mode.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cr8;
+
+#[cfg(all(feature = "instructions", target_arch = "x86_64"))]
+impl Cr8 {
+    /// Returns the current task priority.
+    pub fn get() -> u8 {
+        let value: u64;
+        // SAFETY:
+        // Reading from the `CR8` register does not adversely affect the processor.
+        unsafe {
+            core::arch::asm!(
+                "mov {}, cr8",
+                out(reg) value,
+                options(nomem, nostack, preserves_flags)
+            )
+        }
+
+        value as u8
+    }
+
+    /// Sets the task priority to `priority`.
+    ///
+    /// Interrupts whose priority class is not greater than `priority` are masked.
+    ///
+    /// # Panics
+    /// Panics if `priority` is not less than 16.
+    ///
+    /// # Safety
+    /// - Masking interrupts of priority class up to `priority` will not cause undefined behavior.
+    pub unsafe fn set(priority: u8) {
+        assert!(priority < 16, "`priority` must be less than 16");
+
+        // SAFETY:
+        // Masking interrupts of priority class up to `priority` will not cause undefined behavior.
+        unsafe {
+            core::arch::asm!(
+                "mov cr8, {}",
+                in(reg) u64::from(priority),
+                options(nostack, preserves_flags)
+            )
+        }
+    }
+
+    /// Updates the task priority with the value returned by `f`.
+    ///
+    /// # Panics
+    /// Panics if `f` returns a value that is not less than 16.
+    ///
+    /// # Safety
+    /// See [`Cr8::set()`].
+    pub unsafe fn update<F: FnOnce(u8) -> u8>(f: F) {
+        // SAFETY:
+        // The invariants of `set()` are upheld by the caller.
+        unsafe { Self::set(f(Self::get())) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::format;
+
+    use super::{Cr0, Cr3, Cr4, Pcid};
+    use crate::addr::PhysAddr;
+
+    #[test]
+    fn flags() {
+        let cr0 = Cr0::PROTECTION_ENABLE | Cr0::PAGING | Cr0::EXTENSION_TYPE;
+        assert_eq!(cr0.to_raw(), 0x8000_0011);
+        assert!(cr0.contains(Cr0::PAGING | Cr0::PROTECTION_ENABLE));
+        assert!(!cr0.contains(Cr0::WRITE_PROTECT));
+        assert_eq!(
+            format!("{cr0:?}"),
+            "PROTECTION_ENABLE | EXTENSION_TYPE | PAGING"
+        );
+
+        let cr4 = Cr4::from_raw(0x0030_06A0 | (1 << 30)).set_flags(Cr4::PAGE_GLOBAL_ENABLE, false);
+        assert_eq!(
+            format!("{cr4}"),
+            "PHYSICAL_ADDRESS_EXTENSION | OS_FXSR | OS_XMM_EXCEPTIONS | \
+             SUPERVISOR_MODE_EXECUTION_PREVENTION | SUPERVISOR_MODE_ACCESS_PREVENTION | 0x40000000"
+        );
+        assert_eq!(format!("{}", Cr4::empty()), "(empty)");
+    }
+
+    #[test]
+    fn cr3() {
+        let pcid = Pcid::new(0x123).unwrap();
+        let cr3 = Cr3::new(PhysAddr::new(0x1234_5678).unwrap())
+            .set_pcid(pcid)
+            .set_no_flush(true);
+        assert_eq!(cr3.to_raw(), 0x8000_0000_1234_5123);
+        assert_eq!(cr3.frame(), PhysAddr::new(0x1234_5000).unwrap());
+        assert_eq!(cr3.pcid(), pcid);
+        assert!(cr3.no_flush());
+
+        let cr3 = Cr3::from_raw(0x1234_5018);
+        assert!(cr3.write_through() && cr3.cache_disable());
+        assert_eq!(cr3.set_write_through(false).to_raw(), 0x1234_5010);
+    }
+}
@@ -2,13 +2,21 @@
 
 use core::fmt;
 
-use crate::{Architecture, PrivilegeLevel, X86, X86_64};
+#[cfg(any(feature = "x86", all(feature = "instructions", target_arch = "x86")))]
+use crate::X86;
+#[cfg(any(
+    feature = "x86_64",
+    all(feature = "instructions", target_arch = "x86_64")
+))]
+use crate::X86_64;
+use crate::{Architecture, PrivilegeLevel};
 
 /// The `x86` and `x86_64` flags register.
 #[repr(transparent)]
 pub struct Flags<A: Architecture>(A::GeneralRegister);
 
 #[allow(clippy::missing_docs_in_private_items)]
+#[cfg_attr(not(any(feature = "x86", feature = "x86_64")), allow(dead_code))]
 impl<A: Architecture> Flags<A> {
     const CARRY_BIT: usize = 0;
     const PARITY_BIT: usize = 2;
@@ -32,6 +40,18 @@ impl<A: Architecture> Flags<A> {
     const IOPL_START: usize = 12;
 }
 
+impl<A: Architecture> Flags<A> {
+    /// Creates a new [`Flags`] from the raw value of the register.
+    pub const fn from_raw(value: A::GeneralRegister) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw value of the register.
+    pub const fn to_raw(self) -> A::GeneralRegister {
+        self.0
+    }
+}
+
 impl<A: ArchitectureExt> Flags<A> {
     /// Set by hardware if the last operation generated a carry or borrow out of the
     /// most-significant bit of the result; cleared otherwise.
@@ -347,6 +367,7 @@ impl<A: ArchitectureExt + PartialEq + Copy> fmt::Display for FlagsDisplay<A> {
 }
 
 /// Macro implementing [`ArchitectureExt`].
+#[cfg(any(feature = "x86", feature = "x86_64"))]
 macro_rules! impl_arch_flags {
     ($name:ident, $container:ident) => {
         impl ArchitectureExt for $name {
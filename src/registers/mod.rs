@@ -1,4 +1,154 @@
 //! Access to various `x86` and `x86_64` registers.
 
+use core::fmt;
+
+/// Generates a bitflag-style register type.
+macro_rules! register_flags {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $(
+                $(#[$flag_meta:meta])*
+                $flag:ident = $bit:literal,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(transparent)]
+        #[derive(Clone, Copy, Hash, PartialEq, Eq)]
+        pub struct $name(u64);
+
+        impl $name {
+            $(
+                $(#[$flag_meta])*
+                pub const $flag: Self = Self(1 << $bit);
+            )*
+
+            /// The names of the flags, used for formatting.
+            const NAMES: &'static [(Self, &'static str)] = &[$((Self::$flag, stringify!($flag))),*];
+
+            /// Creates a new value with no flags set.
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            /// Creates a new value from the raw value of the register.
+            pub const fn from_raw(value: u64) -> Self {
+                Self(value)
+            }
+
+            /// Returns the raw value of the register.
+            pub const fn to_raw(self) -> u64 {
+                self.0
+            }
+
+            /// Returns `true` if every flag set in `flags` is also set in this value.
+            pub const fn contains(self, flags: Self) -> bool {
+                self.0 & flags.0 == flags.0
+            }
+
+            /// Sets or clears every flag set in `flags`.
+            pub const fn set_flags(mut self, flags: Self, enabled: bool) -> Self {
+                if enabled {
+                    self.0 |= flags.0;
+                } else {
+                    self.0 &= !flags.0;
+                }
+                self
+            }
+        }
+
+        impl core::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, rhs: Self) -> Self::Output {
+                Self(self.0 & rhs.0)
+            }
+        }
+
+        impl core::ops::BitAndAssign for $name {
+            fn bitand_assign(&mut self, rhs: Self) {
+                *self = Self(self.0 & rhs.0);
+            }
+        }
+
+        impl core::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self::Output {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl core::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                *self = Self(self.0 | rhs.0);
+            }
+        }
+
+        impl core::ops::BitXor for $name {
+            type Output = Self;
+
+            fn bitxor(self, rhs: Self) -> Self::Output {
+                Self(self.0 ^ rhs.0)
+            }
+        }
+
+        impl core::ops::BitXorAssign for $name {
+            fn bitxor_assign(&mut self, rhs: Self) {
+                *self = Self(self.0 ^ rhs.0);
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                $crate::registers::format_flags(f, self.0, Self::NAMES.iter().map(|(flag, name)| (flag.0, *name)))
+            }
+        }
+
+        impl core::fmt::Display for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(self, f)
+            }
+        }
+    };
+}
+
+pub mod control;
 pub mod flags;
+pub mod msr;
 pub mod segmentation;
+pub mod xcontrol;
+
+/// Formats the flags set in `value` using `names`, followed by any remaining unnamed bits.
+fn format_flags(
+    f: &mut fmt::Formatter<'_>,
+    value: u64,
+    names: impl Iterator<Item = (u64, &'static str)>,
+) -> fmt::Result {
+    let mut remaining = value;
+    let mut prev = false;
+    for (flag, name) in names {
+        if value & flag == flag {
+            if prev {
+                write!(f, " | ")?;
+            }
+
+            write!(f, "{name}")?;
+            remaining &= !flag;
+            prev = true;
+        }
+    }
+
+    if remaining != 0 {
+        if prev {
+            write!(f, " | ")?;
+        }
+
+        write!(f, "{remaining:#x}")?;
+    } else if !prev {
+        write!(f, "(empty)")?;
+    }
+
+    Ok(())
+}
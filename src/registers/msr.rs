@@ -0,0 +1,697 @@
+//! Definitions and interfaces to interact with model-specific registers.
+
+use core::fmt;
+
+#[cfg(feature = "instructions")]
+use crate::structures::paging::pat::PatConfiguration;
+use crate::{addr::PhysAddr, registers::segmentation::SegmentSelector};
+#[cfg(all(feature = "instructions", target_arch = "x86_64"))]
+use crate::{addr::VirtAddr, registers::flags::Flags, X86_64};
+
+/// A model-specific register, identified by its index.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Msr(u32);
+
+impl Msr {
+    /// The `IA32_APIC_BASE` MSR, see [`ApicBase`].
+    pub const IA32_APIC_BASE: Self = Self(0x1B);
+    /// The `IA32_FEATURE_CONTROL` MSR, see [`FeatureControl`].
+    pub const IA32_FEATURE_CONTROL: Self = Self(0x3A);
+    /// The `IA32_PAT` MSR, see [`Pat`].
+    pub const IA32_PAT: Self = Self(0x277);
+    /// The `IA32_EFER` MSR, see [`Efer`].
+    pub const IA32_EFER: Self = Self(0xC000_0080);
+    /// The `IA32_STAR` MSR, see [`Star`].
+    pub const IA32_STAR: Self = Self(0xC000_0081);
+    /// The `IA32_LSTAR` MSR, see [`Lstar`].
+    pub const IA32_LSTAR: Self = Self(0xC000_0082);
+    /// The `IA32_CSTAR` MSR, see [`Cstar`].
+    pub const IA32_CSTAR: Self = Self(0xC000_0083);
+    /// The `IA32_FMASK` MSR, see [`SfMask`].
+    pub const IA32_FMASK: Self = Self(0xC000_0084);
+    /// The `IA32_FS_BASE` MSR, see [`FsBase`].
+    pub const IA32_FS_BASE: Self = Self(0xC000_0100);
+    /// The `IA32_GS_BASE` MSR, see [`GsBase`].
+    pub const IA32_GS_BASE: Self = Self(0xC000_0101);
+    /// The `IA32_KERNEL_GS_BASE` MSR, see [`KernelGsBase`].
+    pub const IA32_KERNEL_GS_BASE: Self = Self(0xC000_0102);
+    /// The `IA32_TSC_AUX` MSR, see [`TscAux`].
+    pub const IA32_TSC_AUX: Self = Self(0xC000_0103);
+
+    /// Creates a new [`Msr`] referring to the model-specific register at `index`.
+    pub const fn new(index: u32) -> Self {
+        Self(index)
+    }
+
+    /// Returns the index of this [`Msr`].
+    pub const fn index(self) -> u32 {
+        self.0
+    }
+
+    /// Reads the value of this [`Msr`].
+    ///
+    /// # Safety
+    /// - This [`Msr`] must be supported by the processor.
+    /// - Reading this [`Msr`] will not cause undefined behavior.
+    #[cfg(feature = "instructions")]
+    pub unsafe fn read(self) -> u64 {
+        let low: u32;
+        let high: u32;
+        // SAFETY:
+        // This MSR is supported by the processor and reading it will not cause undefined behavior.
+        unsafe {
+            core::arch::asm!(
+                "rdmsr",
+                in("ecx") self.0,
+                out("eax") low,
+                out("edx") high,
+                options(nomem, nostack, preserves_flags)
+            )
+        }
+
+        ((high as u64) << 32) | low as u64
+    }
+
+    /// Writes `value` to this [`Msr`].
+    ///
+    /// # Safety
+    /// - This [`Msr`] must be supported by the processor and accept `value`.
+    /// - Writing `value` to this [`Msr`] will not cause undefined behavior.
+    #[cfg(feature = "instructions")]
+    pub unsafe fn write(self, value: u64) {
+        // SAFETY:
+        // This MSR is supported by the processor, accepts `value`, and writing `value` to it will
+        // not cause undefined behavior.
+        unsafe {
+            core::arch::asm!(
+                "wrmsr",
+                in("ecx") self.0,
+                in("eax") value as u32,
+                in("edx") (value >> 32) as u32,
+                options(nostack, preserves_flags)
+            )
+        }
+    }
+}
+
+impl fmt::Debug for Msr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Msr({:#x})", self.0)
+    }
+}
+
+register_flags! {
+    /// The `IA32_EFER` MSR, which enables long mode and related extensions.
+    Efer {
+        /// Enables the `syscall` and `sysret` instructions.
+        SYSCALL_ENABLE = 0,
+        /// Enables long mode once paging is enabled.
+        LONG_MODE_ENABLE = 8,
+        /// Set by the processor while long mode is active.
+        LONG_MODE_ACTIVE = 10,
+        /// Enables the no execute bit of page table entries.
+        NO_EXECUTE_ENABLE = 11,
+        /// Enables secure virtual machine extensions.
+        ///
+        /// This flag is only supported by AMD processors.
+        SECURE_VIRTUAL_MACHINE_ENABLE = 12,
+        /// Enables segment limit checks in 64-bit mode.
+        ///
+        /// This flag is only supported by AMD processors.
+        LONG_MODE_SEGMENT_LIMIT_ENABLE = 13,
+        /// Enables fast `fxsave` and `fxrstor`, which skip the SSE state in ring 0.
+        ///
+        /// This flag is only supported by AMD processors.
+        FAST_FXSAVE_FXRSTOR = 14,
+        /// Enables the translation cache extension.
+        ///
+        /// This flag is only supported by AMD processors.
+        TRANSLATION_CACHE_EXTENSION = 15,
+    }
+}
+
+#[cfg(feature = "instructions")]
+impl Efer {
+    /// Returns the current value of the `IA32_EFER` MSR.
+    #[cfg(target_arch = "x86_64")]
+    pub fn get() -> Self {
+        // SAFETY:
+        // `IA32_EFER` is supported by every processor supporting 64-bit mode.
+        Self::from_raw(unsafe { Msr::IA32_EFER.read() })
+    }
+
+    /// Returns the current value of the `IA32_EFER` MSR.
+    ///
+    /// # Safety
+    /// CPUID leaf `0x8000_0001` must report support for long mode or for the no execute bit.
+    #[cfg(target_arch = "x86")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        // `IA32_EFER` is supported by every processor supporting long mode or the no execute
+        // bit, which is upheld by the caller.
+        Self::from_raw(unsafe { Msr::IA32_EFER.read() })
+    }
+
+    /// Loads the `IA32_EFER` MSR with `value`.
+    ///
+    /// # Safety
+    /// - Loading `value` will not cause undefined behavior.
+    /// - Every extension enabled by `value` must be supported by the processor.
+    pub unsafe fn set(value: Self) {
+        // SAFETY:
+        // The invariants of `write()` are upheld by the caller.
+        unsafe { Msr::IA32_EFER.write(value.to_raw()) }
+    }
+
+    /// Updates the `IA32_EFER` MSR with the value returned by `f`.
+    ///
+    /// # Safety
+    /// See [`Efer::get()`] and [`Efer::set()`].
+    pub unsafe fn update<F: FnOnce(Self) -> Self>(f: F) {
+        #[cfg(target_arch = "x86_64")]
+        let value = Self::get();
+        // SAFETY:
+        // The invariants of `get()` are upheld by the caller.
+        #[cfg(target_arch = "x86")]
+        let value = unsafe { Self::get() };
+        // SAFETY:
+        // The invariants of `set()` are upheld by the caller.
+        unsafe { Self::set(f(value)) }
+    }
+}
+
+/// Generates a unit struct for an MSR holding a linear address.
+macro_rules! address_msr {
+    ($(#[$meta:meta])* $name:ident, $msr:ident, $safety:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        pub struct $name;
+
+        #[cfg(all(feature = "instructions", target_arch = "x86_64"))]
+        impl $name {
+            #[doc = concat!("Returns the address held by the `", stringify!($msr), "` MSR.")]
+            pub fn get() -> VirtAddr {
+                // SAFETY:
+                // This MSR is supported by every processor supporting 64-bit mode.
+                VirtAddr::new_truncate_la57(unsafe { Msr::$msr.read() })
+            }
+
+            #[doc = concat!("Loads the `", stringify!($msr), "` MSR with `address`.")]
+            ///
+            /// # Safety
+            /// - Loading `address` will not cause undefined behavior.
+            #[doc = concat!("- ", $safety)]
+            pub unsafe fn set(address: VirtAddr) {
+                // SAFETY:
+                // The invariants of `write()` are upheld by the caller.
+                unsafe { Msr::$msr.write(address.as_u64()) }
+            }
+        }
+    };
+}
+
+address_msr!(
+    /// The `IA32_FS_BASE` MSR, which holds the base address of the `FS` segment in 64-bit mode.
+    FsBase,
+    IA32_FS_BASE,
+    "`address` must be canonical."
+);
+
+address_msr!(
+    /// The `IA32_GS_BASE` MSR, which holds the base address of the `GS` segment in 64-bit mode.
+    GsBase,
+    IA32_GS_BASE,
+    "`address` must be canonical."
+);
+
+address_msr!(
+    /// The `IA32_KERNEL_GS_BASE` MSR, which is exchanged with [`GsBase`] by `swapgs`.
+    KernelGsBase,
+    IA32_KERNEL_GS_BASE,
+    "`address` must be canonical."
+);
+
+address_msr!(
+    /// The `IA32_LSTAR` MSR, which holds the entry point of `syscall` from 64-bit mode.
+    Lstar,
+    IA32_LSTAR,
+    "`address` must be canonical and refer to a valid `syscall` handler."
+);
+
+address_msr!(
+    /// The `IA32_CSTAR` MSR, which holds the entry point of `syscall` from compatibility mode.
+    ///
+    /// `syscall` is only supported in compatibility mode by AMD processors.
+    Cstar,
+    IA32_CSTAR,
+    "`address` must be canonical and refer to a valid `syscall` handler."
+);
+
+/// The `IA32_STAR` MSR, which holds the segment selectors loaded by `syscall` and `sysret`.
+///
+/// `syscall` loads `CS` from [`Star::syscall_base()`] and `SS` from the following descriptor.
+/// `sysret` to 64-bit mode loads `CS` from 2 descriptors after [`Star::sysret_base()`] and `SS`
+/// from the descriptor after it, both with [`PrivilegeLevel::Ring3`][ring3].
+///
+/// [ring3]: crate::PrivilegeLevel::Ring3
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Star(u64);
+
+impl Star {
+    /// Creates a new [`Star`] from the [`SegmentSelector`]s used by `syscall` and `sysret`.
+    pub const fn new(syscall_base: SegmentSelector, sysret_base: SegmentSelector) -> Self {
+        Self(((sysret_base.to_raw() as u64) << 48) | ((syscall_base.to_raw() as u64) << 32))
+    }
+
+    /// Creates a new [`Star`] from the raw value of the MSR.
+    pub const fn from_raw(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw value of the MSR.
+    pub const fn to_raw(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the [`SegmentSelector`] from which `syscall` loads `CS`.
+    pub const fn syscall_base(self) -> SegmentSelector {
+        SegmentSelector::from_raw((self.0 >> 32) as u16)
+    }
+
+    /// Sets the [`SegmentSelector`] from which `syscall` loads `CS`.
+    pub const fn set_syscall_base(mut self, selector: SegmentSelector) -> Self {
+        self.0 = (self.0 & !(0xFFFF << 32)) | ((selector.to_raw() as u64) << 32);
+        self
+    }
+
+    /// Returns the base [`SegmentSelector`] from which `sysret` loads `CS` and `SS`.
+    pub const fn sysret_base(self) -> SegmentSelector {
+        SegmentSelector::from_raw((self.0 >> 48) as u16)
+    }
+
+    /// Sets the base [`SegmentSelector`] from which `sysret` loads `CS` and `SS`.
+    pub const fn set_sysret_base(mut self, selector: SegmentSelector) -> Self {
+        self.0 = (self.0 & !(0xFFFF << 48)) | ((selector.to_raw() as u64) << 48);
+        self
+    }
+
+    /// Returns the entry point of `syscall` in legacy mode.
+    ///
+    /// This field is only supported by AMD processors.
+    pub const fn legacy_entry(self) -> u32 {
+        self.0 as u32
+    }
+
+    /// Sets the entry point of `syscall` in legacy mode.
+    ///
+    /// This field is only supported by AMD processors.
+    pub const fn set_legacy_entry(mut self, entry: u32) -> Self {
+        self.0 = (self.0 & !0xFFFF_FFFF) | entry as u64;
+        self
+    }
+}
+
+#[cfg(all(feature = "instructions", target_arch = "x86_64"))]
+impl Star {
+    /// Returns the current value of the `IA32_STAR` MSR.
+    pub fn get() -> Self {
+        // SAFETY:
+        // `IA32_STAR` is supported by every processor supporting 64-bit mode.
+        Self(unsafe { Msr::IA32_STAR.read() })
+    }
+
+    /// Loads the `IA32_STAR` MSR with `value`.
+    ///
+    /// # Safety
+    /// - Loading `value` will not cause undefined behavior.
+    /// - The descriptors referred to by `value` must be suitable for `syscall` and `sysret`.
+    pub unsafe fn set(value: Self) {
+        // SAFETY:
+        // The invariants of `write()` are upheld by the caller.
+        unsafe { Msr::IA32_STAR.write(value.0) }
+    }
+
+    /// Updates the `IA32_STAR` MSR with the value returned by `f`.
+    ///
+    /// # Safety
+    /// See [`Star::set()`].
+    pub unsafe fn update<F: FnOnce(Self) -> Self>(f: F) {
+        // SAFETY:
+        // The invariants of `set()` are upheld by the caller.
+        unsafe { Self::set(f(Self::get())) }
+    }
+}
+
+/// The `IA32_FMASK` MSR, which holds the [flags](crate::registers::flags::Flags) cleared by
+/// `syscall`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SfMask;
+
+#[cfg(all(feature = "instructions", target_arch = "x86_64"))]
+impl SfMask {
+    /// Returns the [`Flags`] cleared by `syscall`.
+    pub fn get() -> Flags<X86_64> {
+        // SAFETY:
+        // `IA32_FMASK` is supported by every processor supporting 64-bit mode.
+        Flags::from_raw(unsafe { Msr::IA32_FMASK.read() })
+    }
+
+    /// Sets the [`Flags`] cleared by `syscall`.
+    ///
+    /// # Safety
+    /// - Clearing `flags` on entry to the `syscall` handler will not cause undefined behavior.
+    pub unsafe fn set(flags: Flags<X86_64>) {
+        // SAFETY:
+        // The invariants of `write()` are upheld by the caller.
+        unsafe { Msr::IA32_FMASK.write(flags.to_raw()) }
+    }
+}
+
+/// The `IA32_PAT` MSR, which holds the
+/// [page attribute table configuration](crate::structures::paging::pat::PatConfiguration).
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Pat;
+
+#[cfg(feature = "instructions")]
+impl Pat {
+    /// Returns the current [`PatConfiguration`].
+    ///
+    /// # Panics
+    /// Panics if the `IA32_PAT` MSR holds a reserved memory type, which the processor does not
+    /// allow.
+    ///
+    /// # Safety
+    /// - The processor must support the page attribute table.
+    pub unsafe fn get() -> PatConfiguration {
+        // SAFETY:
+        // The processor supports the page attribute table.
+        let value = unsafe { Msr::IA32_PAT.read() };
+
+        PatConfiguration::from_raw(value).expect("`IA32_PAT` holds a reserved memory type")
+    }
+
+    /// Loads the `IA32_PAT` MSR with `configuration`.
+    ///
+    /// # Safety
+    /// - The processor must support the page attribute table.
+    /// - Changing the memory types of mapped memory will not cause undefined behavior.
+    pub unsafe fn set(configuration: PatConfiguration) {
+        // SAFETY:
+        // The invariants of `write()` are upheld by the caller.
+        unsafe { Msr::IA32_PAT.write(configuration.to_raw()) }
+    }
+}
+
+/// The `IA32_APIC_BASE` MSR, which holds the physical address and state of the local APIC.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ApicBase(u64);
+
+#[allow(clippy::missing_docs_in_private_items)]
+impl ApicBase {
+    const BOOTSTRAP_PROCESSOR_BIT: u64 = 1 << 8;
+    const X2APIC_BIT: u64 = 1 << 10;
+    const ENABLE_BIT: u64 = 1 << 11;
+    const BASE_MASK: u64 = 0x000F_FFFF_FFFF_F000;
+}
+
+impl ApicBase {
+    /// Creates a new [`ApicBase`] for a disabled local APIC located at `base`.
+    ///
+    /// The bits of `base` below 4 KiB are ignored.
+    pub const fn new(base: PhysAddr) -> Self {
+        Self(base.as_u64() & Self::BASE_MASK)
+    }
+
+    /// Creates a new [`ApicBase`] from the raw value of the MSR.
+    pub const fn from_raw(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw value of the MSR.
+    pub const fn to_raw(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the physical address of the local APIC.
+    pub const fn base(self) -> PhysAddr {
+        PhysAddr::new_truncate(self.0 & Self::BASE_MASK)
+    }
+
+    /// Sets the physical address of the local APIC.
+    ///
+    /// The bits of `base` below 4 KiB are ignored.
+    pub const fn set_base(mut self, base: PhysAddr) -> Self {
+        self.0 = (self.0 & !Self::BASE_MASK) | (base.as_u64() & Self::BASE_MASK);
+        self
+    }
+
+    /// Returns `true` if the processor is the bootstrap processor.
+    ///
+    /// The processor ignores writes to this bit.
+    pub const fn bootstrap_processor(self) -> bool {
+        self.0 & Self::BOOTSTRAP_PROCESSOR_BIT == Self::BOOTSTRAP_PROCESSOR_BIT
+    }
+
+    /// Returns `true` if the local APIC is in x2APIC mode.
+    pub const fn x2apic(self) -> bool {
+        self.0 & Self::X2APIC_BIT == Self::X2APIC_BIT
+    }
+
+    /// Sets whether the local APIC is in x2APIC mode.
+    pub const fn set_x2apic(mut self, x2apic: bool) -> Self {
+        self.0 = (self.0 & !Self::X2APIC_BIT) | ((x2apic as u64) << 10);
+        self
+    }
+
+    /// Returns `true` if the local APIC is enabled.
+    pub const fn enabled(self) -> bool {
+        self.0 & Self::ENABLE_BIT == Self::ENABLE_BIT
+    }
+
+    /// Sets whether the local APIC is enabled.
+    pub const fn set_enabled(mut self, enabled: bool) -> Self {
+        self.0 = (self.0 & !Self::ENABLE_BIT) | ((enabled as u64) << 11);
+        self
+    }
+}
+
+#[cfg(feature = "instructions")]
+impl ApicBase {
+    /// Returns the current value of the `IA32_APIC_BASE` MSR.
+    ///
+    /// # Safety
+    /// - The processor must have a local APIC.
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        // The processor has a local APIC.
+        Self(unsafe { Msr::IA32_APIC_BASE.read() })
+    }
+
+    /// Loads the `IA32_APIC_BASE` MSR with `value`.
+    ///
+    /// # Safety
+    /// - The processor must have a local APIC.
+    /// - Loading `value` will not cause undefined behavior.
+    /// - The transition between the modes of the local APIC must be allowed.
+    pub unsafe fn set(value: Self) {
+        // SAFETY:
+        // The invariants of `write()` are upheld by the caller.
+        unsafe { Msr::IA32_APIC_BASE.write(value.0) }
+    }
+
+    /// Updates the `IA32_APIC_BASE` MSR with the value returned by `f`.
+    ///
+    /// # Safety
+    /// See [`ApicBase::set()`].
+    pub unsafe fn update<F: FnOnce(Self) -> Self>(f: F) {
+        // SAFETY:
+        // The invariants of `get()` and `set()` are upheld by the caller.
+        let value = unsafe { Self::get() };
+        // SAFETY:
+        // The invariants of `set()` are upheld by the caller.
+        unsafe { Self::set(f(value)) }
+    }
+}
+
+/// The `IA32_TSC_AUX` MSR, which holds the value returned by `rdtscp` and `rdpid`.
+///
+/// Operating systems commonly store the index of the processor in this MSR.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TscAux;
+
+#[cfg(feature = "instructions")]
+impl TscAux {
+    /// Returns the value held by the `IA32_TSC_AUX` MSR.
+    ///
+    /// # Safety
+    /// - The processor must support `rdtscp` or `rdpid`.
+    pub unsafe fn get() -> u32 {
+        // SAFETY:
+        // The processor supports `rdtscp` or `rdpid`.
+        unsafe { Msr::IA32_TSC_AUX.read() as u32 }
+    }
+
+    /// Loads the `IA32_TSC_AUX` MSR with `value`.
+    ///
+    /// # Safety
+    /// - The processor must support `rdtscp` or `rdpid`.
+    pub unsafe fn set(value: u32) {
+        // SAFETY:
+        // The processor supports `rdtscp` or `rdpid`.
+        unsafe { Msr::IA32_TSC_AUX.write(value as u64) }
+    }
+}
+
+register_flags! {
+    /// The `IA32_FEATURE_CONTROL` MSR, which enables VMX, SMX and SGX.
+    ///
+    /// The MSR cannot be written after [`FeatureControl::LOCK`] has been set.
+    FeatureControl {
+        /// Prevents further writes to the MSR until the processor is reset.
+        LOCK = 0,
+        /// Enables `vmxon` while in SMX operation.
+        VMX_INSIDE_SMX = 1,
+        /// Enables `vmxon` outside of SMX operation.
+        VMX_OUTSIDE_SMX = 2,
+        /// Enables `getsec[senter]`.
+        SENTER_ENABLE = 15,
+        /// Enables writes to the SGX launch enclave public key hash MSRs.
+        SGX_LAUNCH_CONTROL = 17,
+        /// Enables SGX.
+        SGX_ENABLE = 18,
+        /// Enables local machine check exceptions.
+        LOCAL_MACHINE_CHECK = 20,
+    }
+}
+
+#[cfg(feature = "instructions")]
+impl FeatureControl {
+    /// Returns the current value of the `IA32_FEATURE_CONTROL` MSR.
+    ///
+    /// # Safety
+    /// - The processor must support VMX, SMX, or local machine check exceptions.
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        // The processor supports `IA32_FEATURE_CONTROL`.
+        Self::from_raw(unsafe { Msr::IA32_FEATURE_CONTROL.read() })
+    }
+
+    /// Loads the `IA32_FEATURE_CONTROL` MSR with `value`.
+    ///
+    /// # Safety
+    /// - The processor must support VMX, SMX, or local machine check exceptions.
+    /// - The MSR must not be locked.
+    /// - Every feature enabled by `value` must be supported by the processor.
+    pub unsafe fn set(value: Self) {
+        // SAFETY:
+        // The invariants of `write()` are upheld by the caller.
+        unsafe { Msr::IA32_FEATURE_CONTROL.write(value.to_raw()) }
+    }
+
+    /// Updates the `IA32_FEATURE_CONTROL` MSR with the value returned by `f`.
+    ///
+    /// # Safety
+    /// See [`FeatureControl::set()`].
+    pub unsafe fn update<F: FnOnce(Self) -> Self>(f: F) {
+        // SAFETY:
+        // The invariants of `get()` and `set()` are upheld by the caller.
+        let value = unsafe { Self::get() };
+        // SAFETY:
+        // The invariants of `set()` are upheld by the caller.
+        unsafe { Self::set(f(value)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::format;
+
+    use super::{ApicBase, Efer, FeatureControl, Msr, Star};
+    use crate::{
+        addr::PhysAddr,
+        registers::segmentation::SegmentSelector,
+        structures::paging::pat::{MemoryType, PatConfiguration},
+        PrivilegeLevel,
+    };
+
+    #[test]
+    fn efer() {
+        let efer = Efer::SYSCALL_ENABLE
+            | Efer::LONG_MODE_ENABLE
+            | Efer::LONG_MODE_ACTIVE
+            | Efer::NO_EXECUTE_ENABLE;
+        assert_eq!(efer.to_raw(), 0xD01);
+        assert_eq!(Efer::from_raw(efer.to_raw()), efer);
+        assert_eq!(
+            format!("{efer:?}"),
+            "SYSCALL_ENABLE | LONG_MODE_ENABLE | LONG_MODE_ACTIVE | NO_EXECUTE_ENABLE"
+        );
+        assert_eq!(format!("{:?}", Msr::IA32_EFER), "Msr(0xc0000080)");
+    }
+
+    #[test]
+    fn star() {
+        let syscall = SegmentSelector::new(1, false, PrivilegeLevel::Ring0);
+        let sysret = SegmentSelector::new(2, false, PrivilegeLevel::Ring3);
+        let star = Star::new(syscall, sysret);
+        assert_eq!(star.to_raw(), 0x0013_0008_0000_0000);
+        assert_eq!(star.syscall_base(), syscall);
+        assert_eq!(star.sysret_base(), sysret);
+
+        let star = Star::from_raw(star.to_raw()).set_legacy_entry(0xDEAD_BEEF);
+        assert_eq!(star.to_raw(), 0x0013_0008_DEAD_BEEF);
+        assert_eq!(star.legacy_entry(), 0xDEAD_BEEF);
+        assert_eq!(
+            star.set_syscall_base(sysret)
+                .set_sysret_base(syscall)
+                .to_raw(),
+            0x0008_0013_DEAD_BEEF
+        );
+    }
+
+    #[test]
+    fn apic_base() {
+        let apic_base = ApicBase::from_raw(0xFEE0_0900);
+        assert_eq!(apic_base.base(), PhysAddr::new(0xFEE0_0000).unwrap());
+        assert!(apic_base.bootstrap_processor() && apic_base.enabled() && !apic_base.x2apic());
+
+        let apic_base = apic_base
+            .set_x2apic(true)
+            .set_base(PhysAddr::new(0x1_0000_0000).unwrap());
+        assert_eq!(apic_base.to_raw(), 0x1_0000_0D00);
+        assert_eq!(
+            ApicBase::new(PhysAddr::new(0xFEE0_0123).unwrap())
+                .set_enabled(true)
+                .to_raw(),
+            0xFEE0_0800
+        );
+    }
+
+    #[test]
+    fn pat() {
+        let configuration = PatConfiguration::DEFAULT.set(7, MemoryType::WriteCombining);
+        assert_eq!(configuration.to_raw(), 0x0107_0406_0007_0406);
+        assert_eq!(
+            PatConfiguration::from_raw(configuration.to_raw()),
+            Some(configuration)
+        );
+    }
+
+    #[test]
+    fn feature_control() {
+        let feature_control = FeatureControl::from_raw(0x5);
+        assert_eq!(
+            feature_control,
+            FeatureControl::LOCK | FeatureControl::VMX_OUTSIDE_SMX
+        );
+        assert!(!feature_control.contains(FeatureControl::VMX_INSIDE_SMX));
+        assert_eq!(
+            feature_control
+                .set_flags(FeatureControl::LOCK, false)
+                .to_raw(),
+            0x4
+        );
+    }
+}
@@ -290,8 +290,11 @@ pub struct SegmentSelector(u16);
 
 impl SegmentSelector {
     /// Creates a new [`SegmentSelector`].
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than or equal to 8192.
     pub const fn new(index: u16, ldt: bool, rpl: PrivilegeLevel) -> Self {
-        assert!(index < 8096);
+        assert!(index < 8192);
 
         Self((index << 3) | ((ldt as u16) << 2) | (rpl as u16))
     }
@@ -318,8 +321,11 @@ impl SegmentSelector {
     }
 
     /// Sets the index of this [`SegmentSelector`] into the GDT or LDT.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than or equal to 8192.
     pub const fn set_index(self, index: u16) -> Self {
-        assert!(index < 8096);
+        assert!(index < 8192);
 
         Self((index << 3) | (self.0 & 0b111))
     }
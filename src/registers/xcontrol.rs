@@ -0,0 +1,272 @@
+//! Definitions and interfaces to interact with the `x86` and `x86_64` extended control registers.
+
+#[cfg(feature = "instructions")]
+use crate::instructions::cpuid::CpuFeatures;
+
+register_flags! {
+    /// The `XCR0` register, which enables the state components managed by `xsave` and `xrstor`.
+    XCr0 {
+        /// The x87 FPU and MMX state, which must always be enabled.
+        X87 = 0,
+        /// The SSE state, made up of the `XMM` registers and `MXCSR`.
+        SSE = 1,
+        /// The upper halves of the `YMM` registers.
+        AVX = 2,
+        /// The MPX bound registers.
+        BNDREG = 3,
+        /// The MPX configuration and status registers.
+        BNDCSR = 4,
+        /// The AVX-512 opmask registers.
+        OPMASK = 5,
+        /// The upper halves of the lower 16 `ZMM` registers.
+        ZMM_HI256 = 6,
+        /// The upper 16 `ZMM` registers.
+        HI16_ZMM = 7,
+        /// The `PKRU` register.
+        PKRU = 9,
+        /// The CET user mode state.
+        ///
+        /// This is a supervisor state component managed through the `IA32_XSS` MSR, so it can
+        /// never be enabled in `XCR0`.
+        CET_USER = 11,
+        /// The CET supervisor mode state.
+        ///
+        /// This is a supervisor state component managed through the `IA32_XSS` MSR, so it can
+        /// never be enabled in `XCR0`.
+        CET_SUPERVISOR = 12,
+        /// The AMX `TILECFG` register.
+        TILECFG = 17,
+        /// The AMX `TMM` registers.
+        TILEDATA = 18,
+    }
+}
+
+#[allow(clippy::missing_docs_in_private_items)]
+impl XCr0 {
+    const MPX: Self = Self(Self::BNDREG.0 | Self::BNDCSR.0);
+    const AVX512: Self = Self(Self::OPMASK.0 | Self::ZMM_HI256.0 | Self::HI16_ZMM.0);
+    const AMX: Self = Self(Self::TILECFG.0 | Self::TILEDATA.0);
+}
+
+impl XCr0 {
+    /// Checks that this [`XCr0`] is a legal value of the `XCR0` register that only enables state
+    /// components set in `supported`.
+    ///
+    /// # Errors
+    /// Returns the first rule of the `XCR0` register broken by this [`XCr0`].
+    pub const fn validate(self, supported: Self) -> Result<(), XCr0Error> {
+        if self.0 & !supported.0 != 0 {
+            return Err(XCr0Error::Unsupported(Self(self.0 & !supported.0)));
+        }
+
+        if !self.contains(Self::X87) {
+            return Err(XCr0Error::MissingX87);
+        }
+
+        if self.contains(Self::AVX) && !self.contains(Self::SSE) {
+            return Err(XCr0Error::AvxWithoutSse);
+        }
+
+        if self.0 & Self::MPX.0 != 0 && !self.contains(Self::MPX) {
+            return Err(XCr0Error::PartialMpx);
+        }
+
+        if self.0 & Self::AVX512.0 != 0 {
+            if !self.contains(Self::AVX512) {
+                return Err(XCr0Error::PartialAvx512);
+            }
+
+            if !self.contains(Self::AVX) {
+                return Err(XCr0Error::Avx512WithoutAvx);
+            }
+        }
+
+        if self.0 & Self::AMX.0 != 0 && !self.contains(Self::AMX) {
+            return Err(XCr0Error::PartialAmx);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "instructions")]
+impl XCr0 {
+    /// Returns the state components that the processor supports enabling in `XCR0`, as reported
+    /// by CPUID leaf `0xD`.
+    ///
+    /// Returns [`XCr0::empty()`] if the processor does not support `xsave`.
+    pub fn supported() -> Self {
+        CpuFeatures::new().map_or(Self::empty(), |features| Self::from_features(&features))
+    }
+
+    /// Returns the state components that `features` report as supported in `XCR0`.
+    ///
+    /// Returns [`XCr0::empty()`] if `features` do not report `xsave`.
+    pub const fn from_features(features: &CpuFeatures) -> Self {
+        features.xsave_components()
+    }
+
+    /// Returns the current value of the `XCR0` register.
+    ///
+    /// # Safety
+    /// - [`Cr4::OS_XSAVE`][osxsave] must be set.
+    ///
+    /// [osxsave]: crate::registers::control::Cr4::OS_XSAVE
+    pub unsafe fn get() -> Self {
+        let low: u32;
+        let high: u32;
+        // SAFETY:
+        // `CR4.OSXSAVE` is set, so `xgetbv` is enabled.
+        unsafe {
+            core::arch::asm!(
+                "xgetbv",
+                in("ecx") 0,
+                out("eax") low,
+                out("edx") high,
+                options(nomem, nostack, preserves_flags)
+            )
+        }
+
+        Self(((high as u64) << 32) | low as u64)
+    }
+
+    /// Loads the `XCR0` register with `value`, after checking it with [`XCr0::validate()`]
+    /// against [`XCr0::supported()`].
+    ///
+    /// # Errors
+    /// Returns the first rule of the `XCR0` register broken by `value`, in which case the
+    /// register is not modified.
+    ///
+    /// # Safety
+    /// - [`Cr4::OS_XSAVE`][osxsave] must be set.
+    /// - Loading `value` will not cause undefined behavior.
+    ///
+    /// [osxsave]: crate::registers::control::Cr4::OS_XSAVE
+    pub unsafe fn set(value: Self) -> Result<(), XCr0Error> {
+        value.validate(Self::supported())?;
+
+        // SAFETY:
+        // `CR4.OSXSAVE` is set and `value` is a legal value of `XCR0` supported by the processor.
+        unsafe {
+            core::arch::asm!(
+                "xsetbv",
+                in("ecx") 0,
+                in("eax") value.0 as u32,
+                in("edx") (value.0 >> 32) as u32,
+                options(nomem, nostack, preserves_flags)
+            )
+        }
+
+        Ok(())
+    }
+
+    /// Updates the `XCR0` register with the value returned by `f`.
+    ///
+    /// # Errors
+    /// See [`XCr0::set()`].
+    ///
+    /// # Safety
+    /// See [`XCr0::set()`].
+    pub unsafe fn update<F: FnOnce(Self) -> Self>(f: F) -> Result<(), XCr0Error> {
+        // SAFETY:
+        // The invariants of `get()` are upheld by the caller.
+        let value = unsafe { Self::get() };
+        // SAFETY:
+        // The invariants of `set()` are upheld by the caller.
+        unsafe { Self::set(f(value)) }
+    }
+}
+
+/// Errors that can occur when loading the `XCR0` register.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum XCr0Error {
+    /// The given state components are not supported by the processor.
+    Unsupported(XCr0),
+    /// [`XCr0::X87`] is not set.
+    MissingX87,
+    /// [`XCr0::AVX`] is set without [`XCr0::SSE`].
+    AvxWithoutSse,
+    /// Only one of [`XCr0::BNDREG`] and [`XCr0::BNDCSR`] is set.
+    PartialMpx,
+    /// Some, but not all, of [`XCr0::OPMASK`], [`XCr0::ZMM_HI256`], and [`XCr0::HI16_ZMM`] are
+    /// set.
+    PartialAvx512,
+    /// The AVX-512 state components are set without [`XCr0::AVX`].
+    Avx512WithoutAvx,
+    /// Only one of [`XCr0::TILECFG`] and [`XCr0::TILEDATA`] is set.
+    PartialAmx,
+}
+
+impl core::fmt::Display for XCr0Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unsupported(components) => {
+                write!(f, "state components {components} are not supported")
+            }
+            Self::MissingX87 => write!(f, "x87 state must be enabled"),
+            Self::AvxWithoutSse => write!(f, "AVX state requires SSE state"),
+            Self::PartialMpx => write!(f, "MPX state must be enabled as a whole"),
+            Self::PartialAvx512 => write!(f, "AVX-512 state must be enabled as a whole"),
+            Self::Avx512WithoutAvx => write!(f, "AVX-512 state requires AVX state"),
+            Self::PartialAmx => write!(f, "AMX state must be enabled as a whole"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::format;
+
+    use super::{XCr0, XCr0Error};
+
+    #[test]
+    fn validate() {
+        let supported = XCr0::from_raw(0x602E7);
+        let avx512 =
+            XCr0::X87 | XCr0::SSE | XCr0::AVX | XCr0::OPMASK | XCr0::ZMM_HI256 | XCr0::HI16_ZMM;
+        assert_eq!(avx512.validate(supported), Ok(()));
+        assert_eq!((avx512 | XCr0::PKRU).validate(supported), Ok(()));
+        assert_eq!(
+            (avx512 | XCr0::TILECFG | XCr0::TILEDATA).validate(supported),
+            Ok(())
+        );
+
+        assert_eq!(
+            (XCr0::X87 | XCr0::BNDREG).validate(supported),
+            Err(XCr0Error::Unsupported(XCr0::BNDREG))
+        );
+        assert_eq!(
+            (XCr0::X87 | XCr0::CET_USER).validate(supported),
+            Err(XCr0Error::Unsupported(XCr0::CET_USER))
+        );
+        assert_eq!(XCr0::SSE.validate(supported), Err(XCr0Error::MissingX87));
+        assert_eq!(
+            (XCr0::X87 | XCr0::AVX).validate(supported),
+            Err(XCr0Error::AvxWithoutSse)
+        );
+        assert_eq!(
+            (avx512 ^ XCr0::ZMM_HI256).validate(supported),
+            Err(XCr0Error::PartialAvx512)
+        );
+        assert_eq!(
+            (avx512 ^ XCr0::AVX).validate(supported),
+            Err(XCr0Error::Avx512WithoutAvx)
+        );
+        assert_eq!(
+            (avx512 | XCr0::TILEDATA).validate(supported),
+            Err(XCr0Error::PartialAmx)
+        );
+        assert_eq!(
+            (XCr0::X87 | XCr0::BNDCSR).validate(XCr0::from_raw(0x1F)),
+            Err(XCr0Error::PartialMpx)
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            format!("{}", XCr0Error::Unsupported(XCr0::PKRU | XCr0::TILECFG)),
+            "state components PKRU | TILECFG are not supported"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Definitions and interfaces to interact with `x86` and `x86_64` descriptor table pointers.
+
+use core::fmt;
+
+use crate::{Architecture, X86, X86_64};
+
+/// A pointer to a descriptor table, as used by the `lgdt`, `lidt`, `sgdt` and `sidt`
+/// instructions.
+#[repr(C, packed(2))]
+pub struct DescriptorTablePointer<A: Architecture> {
+    /// The size of the descriptor table in bytes, minus one.
+    limit: u16,
+    /// The linear address of the descriptor table.
+    base: A::GeneralRegister,
+}
+
+impl<A: Architecture> DescriptorTablePointer<A> {
+    /// Creates a new [`DescriptorTablePointer`] to a descriptor table located at `base` that is
+    /// `limit + 1` bytes long.
+    pub const fn new(limit: u16, base: A::GeneralRegister) -> Self {
+        Self { limit, base }
+    }
+
+    /// Returns the size of the descriptor table in bytes, minus one.
+    pub const fn limit(&self) -> u16 {
+        self.limit
+    }
+
+    /// Returns the linear address of the descriptor table.
+    pub const fn base(&self) -> A::GeneralRegister {
+        self.base
+    }
+}
+
+impl<A: Architecture> Clone for DescriptorTablePointer<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Architecture> Copy for DescriptorTablePointer<A> {}
+
+impl<A: Architecture> fmt::Debug for DescriptorTablePointer<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("DescriptorTablePointer");
+
+        debug_struct.field("limit", &self.limit());
+        debug_struct.field("base", &self.base());
+
+        debug_struct.finish()
+    }
+}
+
+impl<A: Architecture> core::hash::Hash for DescriptorTablePointer<A> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.limit().hash(state);
+        self.base().hash(state);
+    }
+}
+
+impl<A: Architecture> PartialEq for DescriptorTablePointer<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.limit() == other.limit() && self.base() == other.base()
+    }
+}
+
+impl<A: Architecture> Eq for DescriptorTablePointer<A> {}
+
+impl Default for DescriptorTablePointer<X86> {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl Default for DescriptorTablePointer<X86_64> {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{structures::descriptor_table::DescriptorTablePointer, X86, X86_64};
+
+    #[test]
+    fn pointer_layout() {
+        assert_eq!(core::mem::size_of::<DescriptorTablePointer<X86>>(), 6);
+        assert_eq!(core::mem::size_of::<DescriptorTablePointer<X86_64>>(), 10);
+
+        let pointer = DescriptorTablePointer::<X86_64>::new(0xFFF, 0xFFFF_8000_0000_1000);
+        // SAFETY:
+        // `DescriptorTablePointer<X86_64>` is 10 bytes of plain data.
+        let raw =
+            unsafe { core::mem::transmute::<DescriptorTablePointer<X86_64>, [u8; 10]>(pointer) };
+        assert_eq!(
+            raw,
+            [0xFF, 0x0F, 0x00, 0x10, 0x00, 0x00, 0x00, 0x80, 0xFF, 0xFF]
+        );
+    }
+}
@@ -1,22 +1,617 @@
 //! Definitions and interfaces to interact with `x86` and `x86_64` interrupt descriptor tables.
 
-use core::fmt;
+use core::{fmt, marker::PhantomData};
 
 use crate::{
     registers::{
         flags::{ArchitectureExt, Flags},
         segmentation::SegmentSelector,
     },
-    Current,
+    structures::{descriptor_table::DescriptorTablePointer, task_state::InterruptStackIndex},
+    Current, PrivilegeLevel,
 };
 
+/// An interrupt descriptor table.
+///
+/// The first 32 vectors are reserved for architecture-defined exceptions and are exposed as named
+/// fields, each typed with the handler signature appropriate for that exception. The remaining
+/// vectors can be accessed by indexing the [`InterruptDescriptorTable`] with a vector in the
+/// range `32..=255`.
+#[repr(C, align(16))]
+#[derive(Clone, Debug)]
+pub struct InterruptDescriptorTable {
+    /// Vector 0: occurs when dividing by zero or when the result of a division does not fit in
+    /// the destination operand.
+    pub divide_error: Entry<HandlerFunc>,
+    /// Vector 1: occurs when a debug event, such as a breakpoint condition or single-step trap,
+    /// is detected.
+    pub debug: Entry<HandlerFunc>,
+    /// Vector 2: occurs when a non-maskable interrupt is signaled.
+    pub non_maskable_interrupt: Entry<HandlerFunc>,
+    /// Vector 3: occurs when an `int3` instruction is executed.
+    pub breakpoint: Entry<HandlerFunc>,
+    /// Vector 4: occurs when an `into` instruction is executed while the overflow flag is set.
+    pub overflow: Entry<HandlerFunc>,
+    /// Vector 5: occurs when a `bound` instruction detects an out-of-range index.
+    pub bound_range_exceeded: Entry<HandlerFunc>,
+    /// Vector 6: occurs when the processor attempts to execute an invalid or reserved opcode.
+    pub invalid_opcode: Entry<HandlerFunc>,
+    /// Vector 7: occurs when an x87 FPU instruction is executed while the FPU is unavailable.
+    pub device_not_available: Entry<HandlerFunc>,
+    /// Vector 8: occurs when an exception is raised while the processor is delivering a prior
+    /// exception and the two cannot be handled serially.
+    ///
+    /// The error code is always 0 and execution cannot be resumed.
+    pub double_fault: Entry<NoReturnHandlerFuncErrorCode>,
+    /// Vector 9: reserved on modern processors.
+    coprocessor_segment_overrun: Entry<HandlerFunc>,
+    /// Vector 10: occurs when an invalid task state segment is referenced.
+    ///
+    /// The error code is the segment selector index of the invalid segment.
+    pub invalid_tss: Entry<HandlerFuncErrorCode>,
+    /// Vector 11: occurs when a segment whose present bit is clear is loaded.
+    ///
+    /// The error code is the segment selector index of the segment.
+    pub segment_not_present: Entry<HandlerFuncErrorCode>,
+    /// Vector 12: occurs when a stack limit check fails or when a non-present stack segment is
+    /// loaded.
+    pub stack_segment_fault: Entry<HandlerFuncErrorCode>,
+    /// Vector 13: occurs when a protection violation not covered by another exception is
+    /// detected.
+    pub general_protection: Entry<HandlerFuncErrorCode>,
+    /// Vector 14: occurs when a page translation fails or a page-level protection violation is
+    /// detected.
+    ///
+    /// The faulting address is stored in the CR2 register.
+    pub page_fault: Entry<HandlerFuncErrorCode>,
+    /// Vector 15: reserved.
+    reserved_1: Entry<HandlerFunc>,
+    /// Vector 16: occurs when an unmasked x87 floating-point exception is pending.
+    pub x87_floating_point: Entry<HandlerFunc>,
+    /// Vector 17: occurs when an unaligned memory access is performed while alignment checking
+    /// is enabled.
+    pub alignment_check: Entry<HandlerFuncErrorCode>,
+    /// Vector 18: occurs when the processor detects an internal error or a bus error.
+    ///
+    /// Execution cannot be reliably resumed.
+    pub machine_check: Entry<NoReturnHandlerFunc>,
+    /// Vector 19: occurs when an unmasked SIMD floating-point exception is detected.
+    pub simd_floating_point: Entry<HandlerFunc>,
+    /// Vector 20: occurs when an EPT violation is delivered to a guest as a virtualization
+    /// exception.
+    pub virtualization: Entry<HandlerFunc>,
+    /// Vector 21: occurs when a control-flow enforcement violation is detected.
+    pub control_protection: Entry<HandlerFuncErrorCode>,
+    /// Vectors 22 through 27: reserved.
+    reserved_2: [Entry<HandlerFunc>; 6],
+    /// Vector 28: injected by a hypervisor to notify an SEV-SNP guest of an event.
+    pub hypervisor_injection: Entry<HandlerFunc>,
+    /// Vector 29: occurs when an SEV-ES guest performs an action that requires hypervisor
+    /// intervention.
+    pub vmm_communication: Entry<HandlerFuncErrorCode>,
+    /// Vector 30: occurs when a security-sensitive event, such as an INIT under SVM, is detected.
+    pub security: Entry<HandlerFuncErrorCode>,
+    /// Vector 31: reserved.
+    reserved_3: Entry<HandlerFunc>,
+    /// Vectors 32 through 255, which are available for user-defined interrupts.
+    interrupts: [Entry<HandlerFunc>; 224],
+}
+
+impl InterruptDescriptorTable {
+    /// Creates a new [`InterruptDescriptorTable`] in which every [`Entry`] is missing.
+    pub const fn new() -> Self {
+        Self {
+            divide_error: Entry::missing(),
+            debug: Entry::missing(),
+            non_maskable_interrupt: Entry::missing(),
+            breakpoint: Entry::missing(),
+            overflow: Entry::missing(),
+            bound_range_exceeded: Entry::missing(),
+            invalid_opcode: Entry::missing(),
+            device_not_available: Entry::missing(),
+            double_fault: Entry::missing(),
+            coprocessor_segment_overrun: Entry::missing(),
+            invalid_tss: Entry::missing(),
+            segment_not_present: Entry::missing(),
+            stack_segment_fault: Entry::missing(),
+            general_protection: Entry::missing(),
+            page_fault: Entry::missing(),
+            reserved_1: Entry::missing(),
+            x87_floating_point: Entry::missing(),
+            alignment_check: Entry::missing(),
+            machine_check: Entry::missing(),
+            simd_floating_point: Entry::missing(),
+            virtualization: Entry::missing(),
+            control_protection: Entry::missing(),
+            reserved_2: [const { Entry::missing() }; 6],
+            hypervisor_injection: Entry::missing(),
+            vmm_communication: Entry::missing(),
+            security: Entry::missing(),
+            reserved_3: Entry::missing(),
+            interrupts: [const { Entry::missing() }; 224],
+        }
+    }
+
+    /// Resets every [`Entry`] of this [`InterruptDescriptorTable`] to missing.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns a [`DescriptorTablePointer`] which refers to this [`InterruptDescriptorTable`].
+    pub fn pointer(&self) -> DescriptorTablePointer<Current> {
+        let limit = core::mem::size_of::<Self>() - 1;
+
+        DescriptorTablePointer::new(limit as u16, core::ptr::from_ref(self) as _)
+    }
+
+    /// Loads this [`InterruptDescriptorTable`] into the interrupt descriptor table register.
+    #[cfg(feature = "instructions")]
+    pub fn load(&'static self) {
+        // SAFETY:
+        // - The pointer refers to a valid interrupt descriptor table.
+        // - `self` is valid for the `'static` lifetime.
+        unsafe { crate::instructions::tables::lidt(&self.pointer()) }
+    }
+}
+
+impl Default for InterruptDescriptorTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::ops::Index<u8> for InterruptDescriptorTable {
+    type Output = Entry<HandlerFunc>;
+
+    /// Returns the [`Entry`] of the user-defined interrupt `vector`.
+    ///
+    /// # Panics
+    /// Panics if `vector` is less than 32, as those vectors are reserved for exceptions.
+    fn index(&self, vector: u8) -> &Self::Output {
+        assert!(vector >= 32, "vector {vector} is reserved for exceptions");
+
+        &self.interrupts[usize::from(vector - 32)]
+    }
+}
+
+impl core::ops::IndexMut<u8> for InterruptDescriptorTable {
+    /// Returns the [`Entry`] of the user-defined interrupt `vector`.
+    ///
+    /// # Panics
+    /// Panics if `vector` is less than 32, as those vectors are reserved for exceptions.
+    fn index_mut(&mut self, vector: u8) -> &mut Self::Output {
+        assert!(vector >= 32, "vector {vector} is reserved for exceptions");
+
+        &mut self.interrupts[usize::from(vector - 32)]
+    }
+}
+
+/// An entry in an [`InterruptDescriptorTable`].
+///
+/// The type parameter `F` is the handler signature accepted by this [`Entry`].
+#[repr(transparent)]
+pub struct Entry<F> {
+    /// The gate descriptor of the current architecture.
+    gate: CurrentGateDescriptor,
+    /// Phantom data used to ensure type safety.
+    phantom: PhantomData<F>,
+}
+
+/// The gate descriptor used by the current architecture.
+#[cfg(target_arch = "x86_64")]
+type CurrentGateDescriptor = GateDescriptor64;
+/// The gate descriptor used by the current architecture.
+#[cfg(target_arch = "x86")]
+type CurrentGateDescriptor = GateDescriptor32;
+
+impl<F> Entry<F> {
+    /// Creates a new [`Entry`] that is not present.
+    pub const fn missing() -> Self {
+        Self {
+            gate: CurrentGateDescriptor::missing(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets this [`Entry`] to a present interrupt gate which transfers control to the handler
+    /// located at `address` in the code segment referred to by `selector`.
+    ///
+    /// [`Entry::set_options()`] can be used to further configure this [`Entry`].
+    ///
+    /// # Safety
+    /// - `selector` must refer to a valid code segment.
+    /// - `address` must be the address of a handler that follows the calling convention
+    ///   described by `F`.
+    pub unsafe fn set_handler_addr(&mut self, selector: SegmentSelector, address: usize) {
+        self.gate = self
+            .gate
+            .set_offset(address as _)
+            .set_selector(selector)
+            .set_options(*GateOptions::new().set_present(true));
+    }
+
+    /// Returns the address of the handler of this [`Entry`].
+    pub fn handler_addr(&self) -> usize {
+        self.gate.offset() as usize
+    }
+
+    /// Returns the code [`SegmentSelector`] that the handler of this [`Entry`] executes in.
+    pub fn selector(&self) -> SegmentSelector {
+        self.gate.selector()
+    }
+
+    /// Returns `true` if this [`Entry`] is present.
+    pub fn present(&self) -> bool {
+        self.gate.options().present()
+    }
+
+    /// Returns the [`GateOptions`] of this [`Entry`].
+    pub fn options(&self) -> GateOptions {
+        self.gate.options()
+    }
+
+    /// Sets the [`GateOptions`] of this [`Entry`].
+    ///
+    /// # Panics
+    /// On `x86`, panics if `options` selects an [`InterruptStackIndex`], as the interrupt stack
+    /// table is only supported in IA-32e mode.
+    pub fn set_options(&mut self, options: GateOptions) {
+        self.gate = self.gate.set_options(options);
+    }
+}
+
+#[cfg(feature = "abi-x86-interrupt")]
+impl<F: HandlerFuncType> Entry<F> {
+    /// Sets this [`Entry`] to a present interrupt gate which transfers control to `handler` in
+    /// the code segment referred to by `selector`.
+    ///
+    /// [`Entry::set_options()`] can be used to further configure this [`Entry`].
+    ///
+    /// # Safety
+    /// `selector` must refer to a valid code segment.
+    pub unsafe fn set_handler_fn(&mut self, selector: SegmentSelector, handler: F) {
+        // SAFETY:
+        // - `selector` refers to a valid code segment.
+        // - `handler` follows the calling convention described by `F`.
+        unsafe { self.set_handler_addr(selector, handler.to_addr()) }
+    }
+}
+
+impl<F> Clone for Entry<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F> Copy for Entry<F> {}
+
+impl<F> fmt::Debug for Entry<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Entry").field(&self.gate).finish()
+    }
+}
+
+impl<F> PartialEq for Entry<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.gate == other.gate
+    }
+}
+
+impl<F> Eq for Entry<F> {}
+
+/// A 16-byte gate descriptor used in a 64-bit [`InterruptDescriptorTable`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct GateDescriptor64 {
+    /// Bits 0 through 15 of the handler offset.
+    offset_low: u16,
+    /// The code [`SegmentSelector`] that the handler executes in.
+    selector: SegmentSelector,
+    /// The [`GateOptions`] of the [`GateDescriptor64`].
+    options: GateOptions,
+    /// Bits 16 through 31 of the handler offset.
+    offset_middle: u16,
+    /// Bits 32 through 63 of the handler offset.
+    offset_high: u32,
+    #[doc(hidden)]
+    _reserved: u32,
+}
+
+impl GateDescriptor64 {
+    /// Creates a new [`GateDescriptor64`] that is not present.
+    pub const fn missing() -> Self {
+        Self {
+            offset_low: 0,
+            selector: SegmentSelector::from_raw(0),
+            options: GateOptions::new(),
+            offset_middle: 0,
+            offset_high: 0,
+            _reserved: 0,
+        }
+    }
+
+    /// Returns the offset of the handler within the code segment.
+    pub const fn offset(self) -> u64 {
+        (self.offset_low as u64)
+            | ((self.offset_middle as u64) << 16)
+            | ((self.offset_high as u64) << 32)
+    }
+
+    /// Sets the offset of the handler within the code segment.
+    pub const fn set_offset(mut self, offset: u64) -> Self {
+        self.offset_low = offset as u16;
+        self.offset_middle = (offset >> 16) as u16;
+        self.offset_high = (offset >> 32) as u32;
+        self
+    }
+
+    /// Returns the code [`SegmentSelector`] that the handler executes in.
+    pub const fn selector(self) -> SegmentSelector {
+        self.selector
+    }
+
+    /// Sets the code [`SegmentSelector`] that the handler executes in.
+    pub const fn set_selector(mut self, selector: SegmentSelector) -> Self {
+        self.selector = selector;
+        self
+    }
+
+    /// Returns the [`GateOptions`] of this [`GateDescriptor64`].
+    pub const fn options(self) -> GateOptions {
+        self.options
+    }
+
+    /// Sets the [`GateOptions`] of this [`GateDescriptor64`].
+    pub const fn set_options(mut self, options: GateOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns a mutable reference to the [`GateOptions`] of this [`GateDescriptor64`].
+    pub const fn options_mut(&mut self) -> &mut GateOptions {
+        &mut self.options
+    }
+}
+
+impl Default for GateDescriptor64 {
+    fn default() -> Self {
+        Self::missing()
+    }
+}
+
+/// An 8-byte gate descriptor used in a 32-bit [`InterruptDescriptorTable`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct GateDescriptor32 {
+    /// Bits 0 through 15 of the handler offset.
+    offset_low: u16,
+    /// The code [`SegmentSelector`] that the handler executes in.
+    selector: SegmentSelector,
+    /// The [`GateOptions`] of the [`GateDescriptor32`].
+    options: GateOptions,
+    /// Bits 16 through 31 of the handler offset.
+    offset_high: u16,
+}
+
+impl GateDescriptor32 {
+    /// Creates a new [`GateDescriptor32`] that is not present.
+    pub const fn missing() -> Self {
+        Self {
+            offset_low: 0,
+            selector: SegmentSelector::from_raw(0),
+            options: GateOptions::new(),
+            offset_high: 0,
+        }
+    }
+
+    /// Returns the offset of the handler within the code segment.
+    pub const fn offset(self) -> u32 {
+        (self.offset_low as u32) | ((self.offset_high as u32) << 16)
+    }
+
+    /// Sets the offset of the handler within the code segment.
+    pub const fn set_offset(mut self, offset: u32) -> Self {
+        self.offset_low = offset as u16;
+        self.offset_high = (offset >> 16) as u16;
+        self
+    }
+
+    /// Returns the code [`SegmentSelector`] that the handler executes in.
+    pub const fn selector(self) -> SegmentSelector {
+        self.selector
+    }
+
+    /// Sets the code [`SegmentSelector`] that the handler executes in.
+    pub const fn set_selector(mut self, selector: SegmentSelector) -> Self {
+        self.selector = selector;
+        self
+    }
+
+    /// Returns the [`GateOptions`] of this [`GateDescriptor32`].
+    pub const fn options(self) -> GateOptions {
+        self.options
+    }
+
+    /// Sets the [`GateOptions`] of this [`GateDescriptor32`].
+    ///
+    /// # Panics
+    /// Panics if `options` selects an [`InterruptStackIndex`], as the interrupt stack table is
+    /// only supported in IA-32e mode.
+    pub const fn set_options(mut self, options: GateOptions) -> Self {
+        assert!(options.stack_index().is_none());
+
+        self.options = options;
+        self
+    }
+}
+
+impl Default for GateDescriptor32 {
+    fn default() -> Self {
+        Self::missing()
+    }
+}
+
+/// The options of a gate descriptor.
+///
+/// This includes the [`GateType`], the descriptor [`PrivilegeLevel`], the present bit and, for
+/// [`GateDescriptor64`], the [`InterruptStackIndex`].
+#[repr(transparent)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct GateOptions(u16);
+
+#[allow(clippy::missing_docs_in_private_items)]
+impl GateOptions {
+    const STACK_INDEX_MASK: u16 = 0b111;
+    const TYPE_SHIFT: u16 = 8;
+    const TYPE_MASK: u16 = 0xF << Self::TYPE_SHIFT;
+    const DPL_SHIFT: u16 = 13;
+    const PRESENT_BIT: u16 = 1 << 15;
+}
+
+impl GateOptions {
+    /// Creates a new [`GateOptions`] describing a non-present [`GateType::Interrupt`] gate with a
+    /// descriptor [`PrivilegeLevel`] of [`PrivilegeLevel::Ring0`] that does not switch stacks.
+    pub const fn new() -> Self {
+        Self((GateType::Interrupt as u16) << Self::TYPE_SHIFT)
+    }
+
+    /// Returns `true` if the gate is present.
+    pub const fn present(self) -> bool {
+        self.0 & Self::PRESENT_BIT == Self::PRESENT_BIT
+    }
+
+    /// Sets whether the gate is present.
+    pub const fn set_present(&mut self, present: bool) -> &mut Self {
+        self.0 = (self.0 & !Self::PRESENT_BIT) | ((present as u16) << 15);
+        self
+    }
+
+    /// Returns the [`GateType`] of the gate.
+    ///
+    /// Returns [`None`] if the type field does not describe a [`GateType`].
+    pub const fn gate_type(self) -> Option<GateType> {
+        GateType::from_u8(((self.0 & Self::TYPE_MASK) >> Self::TYPE_SHIFT) as u8)
+    }
+
+    /// Sets the [`GateType`] of the gate.
+    pub const fn set_gate_type(&mut self, gate_type: GateType) -> &mut Self {
+        self.0 = (self.0 & !Self::TYPE_MASK) | ((gate_type as u16) << Self::TYPE_SHIFT);
+        self
+    }
+
+    /// Sets whether maskable interrupts are disabled when the handler is entered.
+    ///
+    /// This selects between a [`GateType::Interrupt`] gate, which clears
+    /// [`Flags::INTERRUPT_ENABLE`], and a [`GateType::Trap`] gate, which does not.
+    pub const fn disable_interrupts(&mut self, disable: bool) -> &mut Self {
+        if disable {
+            self.set_gate_type(GateType::Interrupt)
+        } else {
+            self.set_gate_type(GateType::Trap)
+        }
+    }
+
+    /// Returns the descriptor [`PrivilegeLevel`] of the gate.
+    ///
+    /// This is the least privileged [`PrivilegeLevel`] from which the gate can be invoked using
+    /// a software interrupt.
+    pub const fn privilege_level(self) -> PrivilegeLevel {
+        match (self.0 >> Self::DPL_SHIFT) & 0b11 {
+            0 => PrivilegeLevel::Ring0,
+            1 => PrivilegeLevel::Ring1,
+            2 => PrivilegeLevel::Ring2,
+            3 => PrivilegeLevel::Ring3,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sets the descriptor [`PrivilegeLevel`] of the gate.
+    ///
+    /// This is the least privileged [`PrivilegeLevel`] from which the gate can be invoked using
+    /// a software interrupt.
+    pub const fn set_privilege_level(&mut self, dpl: PrivilegeLevel) -> &mut Self {
+        self.0 = (self.0 & !(0b11 << Self::DPL_SHIFT)) | ((dpl as u16) << Self::DPL_SHIFT);
+        self
+    }
+
+    /// Returns the [`InterruptStackIndex`] of the stack switched to when the handler is entered.
+    ///
+    /// Returns [`None`] if the interrupt stack table is not used.
+    pub const fn stack_index(self) -> Option<InterruptStackIndex> {
+        InterruptStackIndex::from_u8((self.0 & Self::STACK_INDEX_MASK) as u8)
+    }
+
+    /// Sets the [`InterruptStackIndex`] of the stack switched to when the handler is entered.
+    ///
+    /// If `index` is [`None`], the interrupt stack table is not used.
+    ///
+    /// # Safety
+    /// If `index` is not [`None`], the corresponding stack of the loaded [`TaskStateSegment64`]
+    /// must be valid and must not be in use by another handler.
+    ///
+    /// [`TaskStateSegment64`]: crate::structures::task_state::TaskStateSegment64
+    pub const unsafe fn set_stack_index(
+        &mut self,
+        index: Option<InterruptStackIndex>,
+    ) -> &mut Self {
+        let index = match index {
+            Some(index) => index as u16,
+            None => 0,
+        };
+
+        self.0 = (self.0 & !Self::STACK_INDEX_MASK) | index;
+        self
+    }
+}
+
+impl Default for GateOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for GateOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("GateOptions");
+
+        debug_struct.field("present", &self.present());
+        debug_struct.field("gate_type", &self.gate_type());
+        debug_struct.field("privilege_level", &self.privilege_level());
+        debug_struct.field("stack_index", &self.stack_index());
+
+        debug_struct.finish()
+    }
+}
+
+/// The types of gates that may be placed in an [`InterruptDescriptorTable`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GateType {
+    /// A gate that clears [`Flags::INTERRUPT_ENABLE`] when the handler is entered.
+    Interrupt = 0xE,
+    /// A gate that leaves [`Flags::INTERRUPT_ENABLE`] unchanged when the handler is entered.
+    Trap = 0xF,
+}
+
+impl GateType {
+    /// Creates a [`GateType`] from the value of the type field of a gate descriptor.
+    ///
+    /// This function returns [`None`] if `val` does not describe a [`GateType`].
+    pub const fn from_u8(val: u8) -> Option<Self> {
+        match val {
+            0xE => Some(Self::Interrupt),
+            0xF => Some(Self::Trap),
+            _ => None,
+        }
+    }
+}
+
 /// A handler function for an interrupt or exception without an error code.
 #[cfg(feature = "abi-x86-interrupt")]
 pub type HandlerFunc = extern "x86-interrupt" fn(_: InterruptStackFrame<Current>);
 /// A handler function for an interrupt or exception with an error code.
 #[cfg(feature = "abi-x86-interrupt")]
 pub type HandlerFuncErrorCode =
-    extern "x86-interrupt" fn(_: InterruptStackFrame<Current>, code: u64) -> !;
+    extern "x86-interrupt" fn(_: InterruptStackFrame<Current>, code: u64);
 /// A handler function for an interrupt or exception without an error code that must not return.
 #[cfg(feature = "abi-x86-interrupt")]
 pub type NoReturnHandlerFunc = extern "x86-interrupt" fn(_: InterruptStackFrame<Current>) -> !;
@@ -25,6 +620,77 @@ pub type NoReturnHandlerFunc = extern "x86-interrupt" fn(_: InterruptStackFrame<
 pub type NoReturnHandlerFuncErrorCode =
     extern "x86-interrupt" fn(_: InterruptStackFrame<Current>, code: u64) -> !;
 
+/// A handler function for an interrupt or exception without an error code.
+///
+/// This is a placeholder type, as the `abi-x86-interrupt` feature is not enabled.
+#[cfg(not(feature = "abi-x86-interrupt"))]
+#[derive(Clone, Copy, Debug)]
+pub struct HandlerFunc(());
+/// A handler function for an interrupt or exception with an error code.
+///
+/// This is a placeholder type, as the `abi-x86-interrupt` feature is not enabled.
+#[cfg(not(feature = "abi-x86-interrupt"))]
+#[derive(Clone, Copy, Debug)]
+pub struct HandlerFuncErrorCode(());
+/// A handler function for an interrupt or exception without an error code that must not return.
+///
+/// This is a placeholder type, as the `abi-x86-interrupt` feature is not enabled.
+#[cfg(not(feature = "abi-x86-interrupt"))]
+#[derive(Clone, Copy, Debug)]
+pub struct NoReturnHandlerFunc(());
+/// A handler function for an interrupt or exception with an error code that must not return.
+///
+/// This is a placeholder type, as the `abi-x86-interrupt` feature is not enabled.
+#[cfg(not(feature = "abi-x86-interrupt"))]
+#[derive(Clone, Copy, Debug)]
+pub struct NoReturnHandlerFuncErrorCode(());
+
+/// Marker trait implemented by the handler function types that can be installed into an
+/// [`Entry`].
+///
+/// This trait is sealed.
+#[cfg(feature = "abi-x86-interrupt")]
+pub trait HandlerFuncType: private::Sealed {
+    /// Returns the address of the handler function.
+    fn to_addr(self) -> usize;
+}
+
+/// Implements [`HandlerFuncType`] for the given handler function types.
+#[cfg(feature = "abi-x86-interrupt")]
+macro_rules! impl_handler_func_type {
+    ($($ty:ty),*) => {
+        $(
+            impl HandlerFuncType for $ty {
+                fn to_addr(self) -> usize {
+                    self as usize
+                }
+            }
+
+            impl private::Sealed for $ty {}
+        )*
+    };
+}
+
+#[cfg(feature = "abi-x86-interrupt")]
+impl_handler_func_type!(
+    HandlerFunc,
+    HandlerFuncErrorCode,
+    NoReturnHandlerFunc,
+    NoReturnHandlerFuncErrorCode
+);
+
+#[cfg(feature = "abi-x86-interrupt")]
+mod private {
+    //! Module used to seal the [`HandlerFuncType`][hft] trait.
+    //!
+    //! [hft]: crate::structures::idt::HandlerFuncType
+
+    /// Trait used to seal [`HandlerFuncType`][hft].
+    ///
+    /// [hft]: crate::structures::idt::HandlerFuncType
+    pub trait Sealed {}
+}
+
 /// The interrupt stack frame pushed by the CPU whenever an exception or interrupt occurs.
 #[repr(C)]
 pub struct InterruptStackFrame<A: ArchitectureExt> {
@@ -83,3 +749,115 @@ impl<A: ArchitectureExt> PartialEq for InterruptStackFrame<A> {
 }
 
 impl<A: ArchitectureExt> Eq for InterruptStackFrame<A> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        registers::segmentation::SegmentSelector,
+        structures::{
+            idt::{
+                Entry, GateDescriptor32, GateDescriptor64, GateOptions, GateType, HandlerFunc,
+                InterruptDescriptorTable,
+            },
+            task_state::InterruptStackIndex,
+        },
+        PrivilegeLevel,
+    };
+
+    #[test]
+    fn table_layout() {
+        #[cfg(target_arch = "x86_64")]
+        let entry_size = 16;
+        #[cfg(target_arch = "x86")]
+        let entry_size = 8;
+
+        assert_eq!(core::mem::size_of::<Entry<HandlerFunc>>(), entry_size);
+        assert_eq!(
+            core::mem::size_of::<InterruptDescriptorTable>(),
+            256 * entry_size
+        );
+    }
+
+    #[test]
+    fn entry_handler_addr() {
+        let selector = SegmentSelector::new(1, false, PrivilegeLevel::Ring0);
+        let mut idt = InterruptDescriptorTable::new();
+        assert!(!idt[32].present());
+
+        // SAFETY:
+        // The handler is never invoked.
+        unsafe { idt[32].set_handler_addr(selector, 0x1234_5678) };
+
+        assert!(idt[32].present());
+        assert_eq!(idt[32].handler_addr(), 0x1234_5678);
+        assert_eq!(idt[32].selector(), selector);
+
+        let mut options = idt[32].options();
+        options.set_privilege_level(PrivilegeLevel::Ring3);
+        idt[32].set_options(options);
+        assert_eq!(idt[32].options().privilege_level(), PrivilegeLevel::Ring3);
+        assert_eq!(idt[32].handler_addr(), 0x1234_5678);
+    }
+
+    #[test]
+    #[should_panic]
+    fn exception_vector_index() {
+        let idt = InterruptDescriptorTable::new();
+        let _ = idt[14];
+    }
+
+    #[test]
+    fn gate_options() {
+        let mut options = GateOptions::new();
+        assert!(!options.present());
+        assert_eq!(options.gate_type(), Some(GateType::Interrupt));
+
+        // SAFETY:
+        // The options are never loaded.
+        unsafe {
+            options
+                .set_present(true)
+                .disable_interrupts(false)
+                .set_privilege_level(PrivilegeLevel::Ring3)
+                .set_stack_index(Some(InterruptStackIndex::Ist7));
+        }
+
+        assert!(options.present());
+        assert_eq!(options.gate_type(), Some(GateType::Trap));
+        assert_eq!(options.privilege_level(), PrivilegeLevel::Ring3);
+        assert_eq!(options.stack_index(), Some(InterruptStackIndex::Ist7));
+    }
+
+    #[test]
+    fn gate_descriptor_layout() {
+        let selector = SegmentSelector::new(1, false, PrivilegeLevel::Ring0);
+        let mut options = GateOptions::new();
+        // SAFETY:
+        // The gate descriptor is never loaded.
+        unsafe {
+            options
+                .set_present(true)
+                .set_stack_index(Some(InterruptStackIndex::Ist1));
+        }
+
+        let gate = GateDescriptor64::missing()
+            .set_offset(0xFFFF_8000_1234_5678)
+            .set_selector(selector)
+            .set_options(options);
+        // SAFETY:
+        // `GateDescriptor64` is 16 bytes of plain data.
+        let raw = unsafe { core::mem::transmute::<GateDescriptor64, u128>(gate) };
+        assert_eq!(raw, 0x0000_0000_FFFF_8000_1234_8E01_0008_5678);
+        assert_eq!(gate.offset(), 0xFFFF_8000_1234_5678);
+
+        let gate = GateDescriptor32::missing()
+            .set_offset(0x1234_5678)
+            .set_selector(selector)
+            .set_options(*GateOptions::new().set_present(true));
+        // SAFETY:
+        // `GateDescriptor32` is 8 bytes of plain data.
+        let raw = unsafe { core::mem::transmute::<GateDescriptor32, u64>(gate) };
+        assert_eq!(raw, 0x1234_8E00_0008_5678);
+        assert_eq!(gate.offset(), 0x1234_5678);
+    }
+}
@@ -1,5 +1,7 @@
 //! Definitions and interfaces to interact with `x86` and `x86_64` specific structures.
 
+pub mod descriptor_table;
 pub mod idt;
 pub mod paging;
+pub mod segment_table;
 pub mod task_state;
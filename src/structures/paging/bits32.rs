@@ -0,0 +1,473 @@
+//! Definitions and interfaces to interact with 32-bit paging.
+//!
+//! 32-bit paging is used when paging is enabled and `CR4.PAE` is clear. It translates 32-bit
+//! linear addresses using a two-level hierarchy mapping 4 KiB pages, and 4 MiB pages if
+//! `CR4.PSE` is set.
+
+use core::marker::PhantomData;
+
+use crate::{
+    addr::PhysAddr,
+    structures::paging::{
+        Branch, Leaf, PageMapEntryPresent, PageMapEntryState, Present, Unclassified,
+    },
+};
+
+/// Representation of a page table.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PageTable<L: PageMapLevel>([PageMapEntry<L, Unclassified>; 1024]);
+
+impl<L: PageMapLevel> PageTable<L> {
+    /// Creates an empty [`PageTable`].
+    pub const fn new() -> Self {
+        Self([PageMapEntry::new(); 1024])
+    }
+
+    /// Gets the [`PageMapEntry`] located at `index`.
+    ///
+    /// Returns [`None`] if `index` is out of bounds.
+    pub const fn get(&self, index: usize) -> Option<PageMapEntry<L, Unclassified>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index < self.0.len()) {
+            return None;
+        }
+
+        Some(self.0[index])
+    }
+
+    /// Sets the [`PageMapEntry`] located at `index` to `entry`.
+    ///
+    /// # Errors
+    /// Return [`Err`] if `index` is out of bounds.
+    pub const fn set(
+        &mut self,
+        index: usize,
+        entry: PageMapEntry<L, Unclassified>,
+    ) -> Result<(), PageMapEntry<L, Unclassified>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index < self.0.len()) {
+            return Err(entry);
+        }
+
+        self.0[index] = entry;
+        Ok(())
+    }
+}
+
+impl<L: PageMapLevel> Default for PageTable<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A 32-bit page table entry.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PageMapEntry<L: PageMapLevel, S: PageMapEntryState> {
+    /// The underlying value of the [`PageMapEntry`].
+    value: u32,
+    /// Phantom data used to ensure type safety.
+    phantom: PhantomData<(L, S)>,
+}
+
+impl<L: PageMapLevel> PageMapEntry<L, Unclassified> {
+    /// Creates a new [`PageMapEntry`] that is unclassified.
+    pub const fn new() -> Self {
+        Self {
+            value: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new [`PageMapEntry`] from its raw value.
+    pub const fn from_raw(value: u32) -> Self {
+        Self {
+            value,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: PageMapLevel, S: PageMapEntryState> PageMapEntry<L, S> {
+    /// Returns the raw value of this [`PageMapEntry`].
+    pub const fn to_raw(self) -> u32 {
+        self.value
+    }
+
+    /// Returns this [`PageMapEntry`] as a [`PageMapEntry<L, Unclassified>`].
+    pub const fn unclassified(self) -> PageMapEntry<L, Unclassified> {
+        PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns [`PageMapEntry<L, Present>`] if the [`PageMapEntry`] is present; otherwise, this
+    /// function return [`None`].
+    pub const fn present(self) -> Option<PageMapEntry<L, Present>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(self.value & 0b1 == 0b1) {
+            return None;
+        }
+
+        Some(PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Sets the present bit in this [`PageMapEntry`].
+    pub const fn set_present(self) -> PageMapEntry<L, Present> {
+        PageMapEntry {
+            value: self.value | 0b1,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Clears the present bit in this [`PageMapEntry`].
+    pub const fn clear_present(self) -> PageMapEntry<L, Unclassified> {
+        PageMapEntry {
+            value: self.value & !0b1,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: PageMapLevel, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] is writable.
+    pub const fn writable(self) -> bool {
+        self.value & (1 << 1) == (1 << 1)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] should be writable.
+    pub const fn set_writable(mut self, writable: bool) -> Self {
+        self.value = (self.value & !(1 << 1)) | ((writable as u32) << 1);
+        self
+    }
+
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] is accessible
+    /// to userspace.
+    pub const fn user(self) -> bool {
+        self.value & (1 << 2) == (1 << 2)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] should be accessible
+    /// to userspace.
+    pub const fn set_user(mut self, accessible: bool) -> Self {
+        self.value = (self.value & !(1 << 2)) | ((accessible as u32) << 2);
+        self
+    }
+
+    /// Returns `true` if the bit is set.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn write_through(self) -> bool {
+        self.value & (1 << 3) == (1 << 3)
+    }
+
+    /// Sets the write through bit.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn set_write_through(mut self, write_through: bool) -> Self {
+        self.value = (self.value & !(1 << 3)) | ((write_through as u32) << 3);
+        self
+    }
+
+    /// Returns `true` if the bit is set.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn cache_disable(self) -> bool {
+        self.value & (1 << 4) == (1 << 4)
+    }
+
+    /// Sets the cache disable bit.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn set_cache_disable(mut self, cache_disable: bool) -> Self {
+        self.value = (self.value & !(1 << 4)) | ((cache_disable as u32) << 4);
+        self
+    }
+
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] has been
+    /// accessed.
+    pub const fn accessed(self) -> bool {
+        self.value & (1 << 5) == (1 << 5)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] should be marked as
+    /// having been accessed.
+    pub const fn set_accessed(mut self, accessed: bool) -> Self {
+        self.value = (self.value & !(1 << 5)) | ((accessed as u32) << 5);
+        self
+    }
+}
+
+impl<S: PageMapEntryPresent> PageMapEntry<Pte, S> {
+    /// Sets the [`PageMapEntry`] to be a leaf entry mapping the 4 KiB frame at `leaf_address`.
+    ///
+    /// Bits 32 and above of `leaf_address` are ignored.
+    pub const fn set_leaf(self, leaf_address: PhysAddr) -> PageMapEntry<Pte, Leaf> {
+        PageMapEntry {
+            value: (self.value & !0xFFFF_F000) | (leaf_address.as_u64() as u32 & 0xFFFF_F000),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: PageMapEntryPresent> PageMapEntry<Pde, S> {
+    /// Sets the [`PageMapEntry`] to be a leaf entry mapping the 4 MiB frame at `leaf_address`.
+    ///
+    /// This requires `CR4.PSE` to be set. Bits 32 to 39 of `leaf_address` are only used if the
+    /// processor supports PSE-36, and only up to its physical address width.
+    pub const fn set_leaf(self, leaf_address: PhysAddr) -> PageMapEntry<Pde, Leaf> {
+        let low = (leaf_address.as_u64() as u32) & 0xFFC0_0000;
+        let high = ((leaf_address.as_u64() >> 32) as u32 & 0xFF) << 13;
+
+        PageMapEntry {
+            value: (self.value & !(0xFFC0_0000 | (0xFF << 13))) | low | high | (1 << 7),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: BranchSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Sets the [`PageMapEntry`] to be a branch entry.
+    ///
+    /// Bits 32 and above of `branch_address` are ignored.
+    pub const fn set_branch(self, branch_address: PhysAddr) -> PageMapEntry<L, Branch> {
+        PageMapEntry {
+            value: (self.value & !(0xFFFF_F000 | (1 << 7)))
+                | (branch_address.as_u64() as u32 & 0xFFFF_F000),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: BranchLeafSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Returns [`PageMapEntry<L, Leaf>`] if this [`PageMapEntry`] is a leaf entry; otherwise,
+    /// this function returns [`None`].
+    pub const fn leaf_opt(self) -> Option<PageMapEntry<L, Leaf>> {
+        if !self.is_leaf() {
+            return None;
+        }
+
+        Some(PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns [`PageMapEntry<L, Branch>`] if this [`PageMapEntry`] is a branch entry; otherwise,
+    /// this function returns [`None`].
+    pub const fn branch_opt(self) -> Option<PageMapEntry<L, Branch>> {
+        if self.is_leaf() {
+            return None;
+        }
+
+        Some(PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns `true` if this [`PageMapEntry`] is a leaf entry.
+    ///
+    /// The page size bit of a [`PageMapEntry<Pde, Present>`] is ignored unless `CR4.PSE` is set.
+    const fn is_leaf(&self) -> bool {
+        self.value & (1 << 7) == (1 << 7)
+    }
+}
+
+impl<L: UnconditionalLeafSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Returns this [`PageMapEntry`] as a [`PageMapEntry<L, Leaf>`].
+    pub const fn leaf(self) -> PageMapEntry<L, Leaf> {
+        PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: LeafSupport> PageMapEntry<L, Leaf> {
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] has been written
+    /// to.
+    pub const fn dirty(self) -> bool {
+        self.value & (1 << 6) == (1 << 6)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] has been written to.
+    pub const fn set_dirty(mut self, dirty: bool) -> Self {
+        self.value = (self.value & !(1 << 6)) | ((dirty as u32) << 6);
+        self
+    }
+
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] should be
+    /// translated globally.
+    pub const fn global(self) -> bool {
+        self.value & (1 << 8) == (1 << 8)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] should be translated
+    /// globally.
+    pub const fn set_global(mut self, global: bool) -> Self {
+        self.value = (self.value & !(1 << 8)) | ((global as u32) << 8);
+        self
+    }
+
+    /// Returns `true` if the bit is set.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn pat(self) -> bool {
+        self.value & (1 << L::PAT_BIT_POS) == (1 << L::PAT_BIT_POS)
+    }
+
+    /// Sets the pat bit.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn set_pat(mut self, pat: bool) -> Self {
+        self.value = (self.value & !(1 << L::PAT_BIT_POS)) | ((pat as u32) << L::PAT_BIT_POS);
+        self
+    }
+}
+
+impl PageMapEntry<Pte, Leaf> {
+    /// Returns the base address of the 4 KiB frame controlled by this [`PageMapEntry`].
+    pub const fn frame(self) -> PhysAddr {
+        PhysAddr::new_truncate((self.value & 0xFFFF_F000) as u64)
+    }
+}
+
+impl PageMapEntry<Pde, Leaf> {
+    /// Returns the base address of the 4 MiB frame controlled by this [`PageMapEntry`].
+    ///
+    /// Bits 32 to 39 of the address are taken from the PSE-36 bits of the [`PageMapEntry`].
+    pub const fn frame(self) -> PhysAddr {
+        PhysAddr::new_truncate(
+            ((self.value & 0xFFC0_0000) as u64) | ((((self.value >> 13) & 0xFF) as u64) << 32),
+        )
+    }
+}
+
+impl PageMapEntry<Pde, Branch> {
+    /// Returns the base address of the [`PageTable<Pte>`] referred to by this [`PageMapEntry`].
+    pub const fn frame(self) -> PhysAddr {
+        PhysAddr::new_truncate((self.value & 0xFFFF_F000) as u64)
+    }
+}
+
+impl<L: PageMapLevel> Default for PageMapEntry<L, Unclassified> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The lowest level of the [`PageTable`] hierarchy, mapping 4 KiB pages.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Pte;
+
+/// The highest level of the [`PageTable`] hierarchy, referred to by `CR3`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Pde;
+
+/// Marker trait indicating that the implementer is a valid page map level.
+pub trait PageMapLevel: Copy + private::PageMapLevelSealed {}
+impl PageMapLevel for Pte {}
+impl PageMapLevel for Pde {}
+
+/// Marker trait indicating that the [`PageMapEntry`]s of that [`PageMapLevel`] could be leaf
+/// entries.
+pub trait LeafSupport: PageMapLevel {
+    /// Position of the PAT bit.
+    const PAT_BIT_POS: u8;
+}
+impl LeafSupport for Pte {
+    const PAT_BIT_POS: u8 = 7;
+}
+impl LeafSupport for Pde {
+    const PAT_BIT_POS: u8 = 12;
+}
+
+/// Marker trait indicating that the [`PageMapEntry`]s of that [`PageMapLevel`] could be branch
+/// entries.
+pub trait BranchSupport: PageMapLevel {}
+impl BranchSupport for Pde {}
+
+/// Marker trait that indicates that the [`PageMapEntry`]s of that [`PageMapLevel`]
+/// could be either branch or leaf entries.
+pub trait BranchLeafSupport: LeafSupport + BranchSupport {}
+impl BranchLeafSupport for Pde {}
+
+/// Marker trait that indicates that the [`PageMapEntry`]s of that [`PageMapLevel`]
+/// are unconditionally leaf entries.
+pub trait UnconditionalLeafSupport: LeafSupport {}
+impl UnconditionalLeafSupport for Pte {}
+
+mod private {
+    //! Module used to seal the levels used to implement the 32-bit paging abstraction.
+
+    use crate::structures::paging::bits32::{Pde, Pte};
+
+    /// Marker trait used to seal [`PageMapLevel`].
+    pub trait PageMapLevelSealed {}
+
+    impl PageMapLevelSealed for Pte {}
+    impl PageMapLevelSealed for Pde {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PageMapEntry, PageTable, Pde, Pte};
+    use crate::addr::PhysAddr;
+
+    #[test]
+    fn pse_36() {
+        let entry = PageMapEntry::<Pde, _>::new()
+            .set_present()
+            .set_writable(true)
+            .set_leaf(PhysAddr::new(0x3_4540_0000).unwrap())
+            .set_pat(true);
+
+        assert_eq!(entry.to_raw(), 0x4540_7083);
+        assert_eq!(entry.frame(), PhysAddr::new(0x3_4540_0000).unwrap());
+
+        let entry = PageMapEntry::<Pde, _>::from_raw(entry.to_raw());
+        let entry = entry.present().unwrap();
+        assert!(entry.branch_opt().is_none());
+        assert!(entry.leaf_opt().unwrap().pat());
+    }
+
+    #[test]
+    fn two_level() {
+        let mut directory = PageTable::<Pde>::new();
+        let entry = directory
+            .get(1)
+            .unwrap()
+            .set_present()
+            .set_user(true)
+            .set_branch(PhysAddr::new(0x5000).unwrap());
+        directory.set(1, entry.unclassified()).unwrap();
+        assert!(directory.set(1024, entry.unclassified()).is_err());
+
+        let entry = directory.get(1).unwrap().present().unwrap();
+        assert!(entry.leaf_opt().is_none());
+        assert_eq!(
+            entry.branch_opt().unwrap().frame(),
+            PhysAddr::new(0x5000).unwrap()
+        );
+
+        let entry = PageMapEntry::<Pte, _>::new()
+            .set_present()
+            .set_leaf(PhysAddr::new(0xABCD_E123).unwrap())
+            .set_pat(true)
+            .set_global(true);
+        assert_eq!(entry.frame(), PhysAddr::new(0xABCD_E000).unwrap());
+        assert_eq!(entry.to_raw(), 0xABCD_E181);
+    }
+}
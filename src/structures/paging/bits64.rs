@@ -4,6 +4,23 @@
 
 use core::marker::PhantomData;
 
+pub use crate::structures::paging::{
+    Branch, Leaf, PageMapEntryPresent, PageMapEntryState, Present, Unclassified,
+};
+use crate::{
+    addr::PhysAddr,
+    structures::paging::pat::{MemoryType, PatConfiguration},
+};
+
+pub mod access;
+mod huge;
+pub mod mapper;
+pub mod page;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod translate;
+pub mod walk;
+
 /// Representation of a page table.
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -60,6 +77,7 @@ impl<L: PageMapLevel> Default for PageTable<L> {
 }
 
 /// A 64-bit page table entry.
+#[repr(transparent)]
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct PageMapEntry<L: PageMapLevel, S: PageMapEntryState> {
     /// The underlying value of the [`PageMapEntry`].
@@ -79,6 +97,14 @@ impl<L: PageMapLevel> PageMapEntry<L, Unclassified> {
 }
 
 impl<L: PageMapLevel, S: PageMapEntryState> PageMapEntry<L, S> {
+    /// Bits of a [`PageMapEntry`] that are ignored by the processor and available to software.
+    ///
+    /// Bit 11 is also used as the HLAT restart bit when HLAT paging is in use.
+    pub const AVAILABLE_MASK: u64 = 0x07F0_0000_0000_0E00;
+
+    /// Bits of a leaf [`PageMapEntry`] that hold its protection key.
+    pub const PROTECTION_KEY_MASK: u64 = 0x7800_0000_0000_0000;
+
     /// Returns this [`PageMapEntry`] as a [`PageMapEntry<L, Unclassified>`].
     pub const fn unclassified(self) -> PageMapEntry<L, Unclassified> {
         PageMapEntry {
@@ -203,6 +229,29 @@ impl<L: PageMapLevel, S: PageMapEntryPresent> PageMapEntry<L, S> {
         self
     }
 
+    /// Returns the software-defined value stored in the available bits of this [`PageMapEntry`].
+    ///
+    /// Bits 9 to 11 of the [`PageMapEntry`] make up bits 0 to 2 of the value, and bits 52 to 58
+    /// make up bits 3 to 9.
+    pub const fn available(self) -> u16 {
+        (((self.value >> 9) & 0b111) | (((self.value >> 52) & 0x7F) << 3)) as u16
+    }
+
+    /// Stores the software-defined `value` in the available bits of this [`PageMapEntry`].
+    ///
+    /// See [`PageMapEntry::available()`] for the layout of `value`.
+    ///
+    /// # Panics
+    /// Panics if `value` does not fit in 10 bits.
+    pub const fn set_available(mut self, value: u16) -> Self {
+        assert!(value < (1 << 10), "`value` must fit in 10 bits");
+
+        let value = value as u64;
+        self.value =
+            (self.value & !Self::AVAILABLE_MASK) | ((value & 0b111) << 9) | ((value >> 3) << 52);
+        self
+    }
+
     /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] cannot be
     /// executed.
     pub const fn no_execute(self) -> bool {
@@ -219,9 +268,11 @@ impl<L: PageMapLevel, S: PageMapEntryPresent> PageMapEntry<L, S> {
 
 impl<L: LeafSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
     /// Sets the [`PageMapEntry`] to be a leaf entry.
-    pub const fn set_leaf(self, leaf_address: u64) -> PageMapEntry<L, Leaf> {
+    pub const fn set_leaf(self, leaf_address: PhysAddr) -> PageMapEntry<L, Leaf> {
         PageMapEntry {
-            value: (self.value & !L::ADDRESS_MASK) | (leaf_address & L::ADDRESS_MASK),
+            value: (self.value & !L::ADDRESS_MASK)
+                | (leaf_address.as_u64() & L::ADDRESS_MASK)
+                | L::PAGE_SIZE_BIT,
             phantom: PhantomData,
         }
     }
@@ -229,9 +280,10 @@ impl<L: LeafSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
 
 impl<L: BranchSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
     /// Sets the [`PageMapEntry`] to be a branch entry.
-    pub const fn set_branch(self, branch_address: u64) -> PageMapEntry<L, Branch> {
+    pub const fn set_branch(self, branch_address: PhysAddr) -> PageMapEntry<L, Branch> {
         PageMapEntry {
-            value: (self.value & !0x000F_FFFF_FFFF_F000) | (branch_address & 0x000F_FFFF_FFFF_F000),
+            value: (self.value & !(0x000F_FFFF_FFFF_F000 | (1 << 7)))
+                | (branch_address.as_u64() & 0x000F_FFFF_FFFF_F000),
             phantom: PhantomData,
         }
     }
@@ -333,16 +385,83 @@ impl<L: LeafSupport> PageMapEntry<L, Leaf> {
         self
     }
 
+    /// Returns the index of the page attribute table slot selected by this [`PageMapEntry`].
+    ///
+    /// The index is made up of the PAT bit (bit 2), the cache disable bit (bit 1), and the write
+    /// through bit (bit 0).
+    pub const fn pat_index(self) -> u8 {
+        ((self.pat() as u8) << 2) | ((self.cache_disable() as u8) << 1) | self.write_through() as u8
+    }
+
+    /// Selects the page attribute table slot at `index` for this [`PageMapEntry`].
+    ///
+    /// See [`PageMapEntry::pat_index()`] for the layout of `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is not less than 8.
+    pub const fn set_pat_index(self, index: u8) -> Self {
+        assert!(index < 8, "`index` must be less than 8");
+
+        self.set_pat(index & 0b100 != 0)
+            .set_cache_disable(index & 0b10 != 0)
+            .set_write_through(index & 0b1 != 0)
+    }
+
+    /// Returns the [`MemoryType`] selected by this [`PageMapEntry`] when the page attribute table
+    /// holds `pat`.
+    ///
+    /// The memory type used to access the region of memory controlled by this [`PageMapEntry`]
+    /// also depends on the MTRRs.
+    pub const fn memory_type(self, pat: PatConfiguration) -> MemoryType {
+        pat.get(self.pat_index())
+    }
+
+    /// Selects `memory_type` for this [`PageMapEntry`] when the page attribute table holds `pat`.
+    ///
+    /// The first slot of `pat` holding `memory_type` is selected.
+    ///
+    /// # Errors
+    /// Returns `memory_type` if no slot of `pat` holds it.
+    pub const fn set_memory_type(
+        self,
+        pat: PatConfiguration,
+        memory_type: MemoryType,
+    ) -> Result<Self, MemoryType> {
+        match pat.index_of(memory_type) {
+            Some(index) => Ok(self.set_pat_index(index)),
+            None => Err(memory_type),
+        }
+    }
+
+    /// Returns the protection key of the region of memory controlled by this [`PageMapEntry`].
+    ///
+    /// The protection key selects the access rights in the `PKRU` register for user pages and in
+    /// the `IA32_PKRS` MSR for supervisor pages. It is ignored unless `CR4.PKE` or `CR4.PKS` is
+    /// set, respectively.
+    pub const fn protection_key(self) -> u8 {
+        ((self.value & Self::PROTECTION_KEY_MASK) >> 59) as u8
+    }
+
+    /// Sets the protection key of the region of memory controlled by this [`PageMapEntry`].
+    ///
+    /// # Panics
+    /// Panics if `key` is not less than 16.
+    pub const fn set_protection_key(mut self, key: u8) -> Self {
+        assert!(key < 16, "`key` must be less than 16");
+        self.value = (self.value & !Self::PROTECTION_KEY_MASK) | ((key as u64) << 59);
+        self
+    }
+
     /// Returns the base address of the region of memory controlled by this [`PageMapEntry`].
-    pub const fn frame(self) -> u64 {
-        self.value & L::ADDRESS_MASK
+    pub const fn frame(self) -> PhysAddr {
+        PhysAddr::new_truncate(self.value & L::ADDRESS_MASK)
     }
 }
 
 impl<L: BranchSupport> PageMapEntry<L, Branch> {
     /// Returns the base address of the next level of the page table hierarchy.
-    pub const fn frame(self) -> u64 {
-        self.value & 0x000F_FFFF_FFFF_F000
+    pub const fn frame(self) -> PhysAddr {
+        PhysAddr::new_truncate(self.value & 0x000F_FFFF_FFFF_F000)
     }
 }
 
@@ -377,12 +496,26 @@ pub struct Pml4e;
 pub struct Pml5e;
 
 /// Marker trait indicating that the implementer is a valid page map level.
-pub trait PageMapLevel: Copy + private::PageMapLevelSealed {}
-impl PageMapLevel for Pml1e {}
-impl PageMapLevel for Pml2e {}
-impl PageMapLevel for Pml3e {}
-impl PageMapLevel for Pml4e {}
-impl PageMapLevel for Pml5e {}
+pub trait PageMapLevel: Copy + private::PageMapLevelSealed {
+    /// Position of the lowest bit of a virtual address used to index a [`PageTable`] of this
+    /// [`PageMapLevel`].
+    const INDEX_SHIFT: u8;
+}
+impl PageMapLevel for Pml1e {
+    const INDEX_SHIFT: u8 = 12;
+}
+impl PageMapLevel for Pml2e {
+    const INDEX_SHIFT: u8 = 21;
+}
+impl PageMapLevel for Pml3e {
+    const INDEX_SHIFT: u8 = 30;
+}
+impl PageMapLevel for Pml4e {
+    const INDEX_SHIFT: u8 = 39;
+}
+impl PageMapLevel for Pml5e {
+    const INDEX_SHIFT: u8 = 48;
+}
 
 /// Marker trait indicating that the [`PageMapEntry`]s of that [`PageMapLevel`] could be leaf
 /// entries.
@@ -392,27 +525,58 @@ pub trait LeafSupport: PageMapLevel {
 
     /// Bitmask to extract the address of the frames this [`PageMapEntry`] controls.
     const ADDRESS_MASK: u64;
+
+    /// Bit that marks a [`PageMapEntry`] of this [`PageMapLevel`] as a leaf entry, or 0 if every
+    /// [`PageMapEntry`] of this [`PageMapLevel`] is a leaf entry.
+    const PAGE_SIZE_BIT: u64;
 }
 impl LeafSupport for Pml1e {
     const PAT_BIT_POS: u8 = 7;
     const ADDRESS_MASK: u64 = 0x000F_FFFF_FFFF_F000;
+    const PAGE_SIZE_BIT: u64 = 0;
 }
 impl LeafSupport for Pml2e {
     const PAT_BIT_POS: u8 = 12;
     const ADDRESS_MASK: u64 = 0x000F_FFFF_FFE0_0000;
+    const PAGE_SIZE_BIT: u64 = 1 << 7;
 }
 impl LeafSupport for Pml3e {
     const PAT_BIT_POS: u8 = 12;
     const ADDRESS_MASK: u64 = 0x000F_FFFF_C000_0000;
+    const PAGE_SIZE_BIT: u64 = 1 << 7;
 }
 
+// The software-defined bits must not overlap with the bits written by `set_leaf` and
+// `set_branch`.
+const _: () = {
+    let reserved = PageMapEntry::<Pml1e, Unclassified>::AVAILABLE_MASK
+        | PageMapEntry::<Pml1e, Unclassified>::PROTECTION_KEY_MASK;
+
+    assert!(reserved & <Pml1e as LeafSupport>::ADDRESS_MASK == 0);
+    assert!(reserved & <Pml2e as LeafSupport>::ADDRESS_MASK == 0);
+    assert!(reserved & <Pml3e as LeafSupport>::ADDRESS_MASK == 0);
+    assert!(reserved & 0x000F_FFFF_FFFF_F000 == 0);
+};
+
 /// Marker trait indicating that the [`PageMapEntry`]s of that [`PageMapLevel`] could be branch
 /// entries.
-pub trait BranchSupport: PageMapLevel {}
-impl BranchSupport for Pml5e {}
-impl BranchSupport for Pml4e {}
-impl BranchSupport for Pml3e {}
-impl BranchSupport for Pml2e {}
+pub trait BranchSupport: PageMapLevel {
+    /// The [`PageMapLevel`] of the [`PageTable`]s referred to by branch entries of this
+    /// [`PageMapLevel`].
+    type Child: PageMapLevel;
+}
+impl BranchSupport for Pml5e {
+    type Child = Pml4e;
+}
+impl BranchSupport for Pml4e {
+    type Child = Pml3e;
+}
+impl BranchSupport for Pml3e {
+    type Child = Pml2e;
+}
+impl BranchSupport for Pml2e {
+    type Child = Pml1e;
+}
 
 /// Marker trait that indicates that the [`PageMapEntry`]s of that [`PageMapLevel`]
 /// are could be either branch or leaf entries.
@@ -431,41 +595,10 @@ pub trait UnconditionalBranchSupport: BranchSupport {}
 impl UnconditionalBranchSupport for Pml5e {}
 impl UnconditionalBranchSupport for Pml4e {}
 
-/// Marker struct that indicates that the [`PageMapEntry`] has not been classified.
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-pub struct Unclassified;
-
-/// Marker struct that indicates that the [`PageMapEntry`] is present.
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-pub struct Present;
-
-/// Marker struct that indicates that the [`PageMapEntry`] is a leaf entry.
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-pub struct Leaf;
-
-/// Marker struct that indicates that the [`PageMapEntry`] is a branch entry.
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-pub struct Branch;
-
-/// Marker trait that indicates that the implementer is a valid state of a [`PageMapEntry`].
-pub trait PageMapEntryState: Copy + private::PageMapEntryStateSealed {}
-impl PageMapEntryState for Unclassified {}
-impl PageMapEntryState for Present {}
-impl PageMapEntryState for Leaf {}
-impl PageMapEntryState for Branch {}
-
-/// Marker trait that indicates that the [`PageMapEntryState`] is a subset of [`Present`].
-pub trait PageMapEntryPresent: PageMapEntryState {}
-impl PageMapEntryPresent for Present {}
-impl PageMapEntryPresent for Leaf {}
-impl PageMapEntryPresent for Branch {}
-
 mod private {
-    //! Module used to seal the various traits used to implement the 64-bit paging abstraction.
+    //! Module used to seal the levels used to implement the 64-bit paging abstraction.
 
-    use crate::structures::paging::bits64::{
-        Branch, Leaf, Pml1e, Pml2e, Pml3e, Pml4e, Pml5e, Present, Unclassified,
-    };
+    use crate::structures::paging::bits64::{Pml1e, Pml2e, Pml3e, Pml4e, Pml5e};
 
     /// Marker trait used to seal [`PageMapLevel`].
     pub trait PageMapLevelSealed {}
@@ -475,21 +608,17 @@ mod private {
     impl PageMapLevelSealed for Pml3e {}
     impl PageMapLevelSealed for Pml4e {}
     impl PageMapLevelSealed for Pml5e {}
-
-    /// Marker trait used to seal [`PageMapEntryState`].
-    pub trait PageMapEntryStateSealed {}
-
-    impl PageMapEntryStateSealed for Unclassified {}
-    impl PageMapEntryStateSealed for Present {}
-    impl PageMapEntryStateSealed for Leaf {}
-    impl PageMapEntryStateSealed for Branch {}
 }
 
 #[cfg(test)]
 mod tests {
     use core::marker::PhantomData;
 
-    use super::{PageMapEntry, PageTable, Pml5e, Unclassified};
+    use super::{PageMapEntry, PageTable, Pml1e, Pml2e, Pml5e, Unclassified};
+    use crate::{
+        addr::PhysAddr,
+        structures::paging::pat::{MemoryType, PatConfiguration},
+    };
 
     #[test]
     fn pml5e() {
@@ -506,7 +635,58 @@ mod tests {
         assert!(entry.accessed());
 
         let entry = entry.branch();
-        assert_eq!(entry.frame(), 0)
+        assert_eq!(entry.frame(), PhysAddr::zero())
+    }
+
+    #[test]
+    fn software_bits() {
+        let entry = PageMapEntry::<Pml5e, Unclassified>::new()
+            .set_present()
+            .set_available(0x3FF)
+            .set_branch(PhysAddr::new(0x000F_FFFF_FFFF_F000).unwrap());
+        assert_eq!(entry.available(), 0x3FF);
+        assert_eq!(entry.frame().as_u64(), 0x000F_FFFF_FFFF_F000);
+
+        let entry = PageMapEntry::<Pml2e, Unclassified>::new()
+            .set_present()
+            .set_available(0b10_1010_1101)
+            .set_leaf(PhysAddr::new(0x000F_FFFF_FFE0_0000).unwrap())
+            .set_protection_key(0xA)
+            .set_no_execute(true)
+            .set_leaf(PhysAddr::new(0x20_0000).unwrap());
+        assert_eq!(entry.available(), 0b10_1010_1101);
+        assert_eq!(entry.protection_key(), 0xA);
+        assert!(entry.no_execute());
+        assert_eq!(entry.frame().as_u64(), 0x20_0000);
+    }
+
+    #[test]
+    fn memory_type() {
+        let pat = PatConfiguration::DEFAULT.set(5, MemoryType::WriteCombining);
+
+        let entry = PageMapEntry::<Pml1e, Unclassified>::new()
+            .set_present()
+            .set_leaf(PhysAddr::zero())
+            .set_memory_type(pat, MemoryType::WriteCombining)
+            .unwrap();
+        assert_eq!(entry.value, 0b1000_1001);
+        assert_eq!(entry.memory_type(pat), MemoryType::WriteCombining);
+        assert_eq!(
+            entry.memory_type(PatConfiguration::DEFAULT),
+            MemoryType::WriteThrough
+        );
+
+        let entry = PageMapEntry::<Pml2e, Unclassified>::new()
+            .set_present()
+            .set_leaf(PhysAddr::zero())
+            .set_memory_type(pat, MemoryType::WriteCombining)
+            .unwrap();
+        assert_eq!(entry.value, 0b1_0000_1000_1001);
+        assert_eq!(entry.pat_index(), 5);
+        assert_eq!(
+            entry.set_memory_type(pat, MemoryType::WriteProtected),
+            Err(MemoryType::WriteProtected)
+        );
     }
 
     #[test]
@@ -523,7 +703,7 @@ mod tests {
             .set_no_execute(true)
             .set_write_through(false)
             .set_cache_disable(false)
-            .set_branch(0xF0_0000);
+            .set_branch(PhysAddr::new(0xF0_0000).unwrap());
 
         table.set(0, entry.unclassified()).unwrap();
     }
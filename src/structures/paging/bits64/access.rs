@@ -0,0 +1,293 @@
+//! Strategies to reach the [`PageTable`]s of the active 4-level or 5-level paging hierarchy
+//! through virtual memory.
+
+use crate::{
+    addr::{PhysAddr, VirtAddr},
+    structures::paging::bits64::{
+        mapper::PageTableAccessMut, translate::PageTableAccess, PageMapLevel, PageTable,
+    },
+};
+
+/// Reaches [`PageTable`]s through a recursive entry of the root [`PageTable`].
+///
+/// The [`PageMapEntry`][pme] at [`RecursiveAccess::index`] of the root [`PageTable`] refers to
+/// the root [`PageTable`] itself, so that walking through it one or more times exposes the
+/// [`PageTable`]s of the paging hierarchy as regular pages.
+///
+/// This type is neither [`Clone`] nor [`Copy`], as every copy could hand out a mutable reference
+/// to the same [`PageTable`].
+///
+/// [pme]: crate::structures::paging::bits64::PageMapEntry
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct RecursiveAccess {
+    /// The index of the recursive entry in the root [`PageTable`].
+    index: u16,
+    /// The number of levels of the paging hierarchy.
+    levels: u8,
+}
+
+impl RecursiveAccess {
+    /// Creates a new [`RecursiveAccess`] for a 4-level paging hierarchy whose root
+    /// [`PageTable`] refers to itself at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is not less than 512.
+    ///
+    /// # Safety
+    /// The active paging hierarchy must use 4-level paging and the entry at `index` of its root
+    /// [`PageTable`] must be a present and writable branch entry referring to the root
+    /// [`PageTable`] itself. No other virtual address may be used to access those
+    /// [`PageTable`]s while this [`RecursiveAccess`] is in use.
+    pub const unsafe fn new(index: u16) -> Self {
+        assert!(index < 512, "recursive index out of range");
+        Self { index, levels: 4 }
+    }
+
+    /// Creates a new [`RecursiveAccess`] for a 5-level paging hierarchy whose root
+    /// [`PageTable`] refers to itself at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is not less than 512.
+    ///
+    /// # Safety
+    /// The active paging hierarchy must use 5-level paging and the entry at `index` of its root
+    /// [`PageTable`] must be a present and writable branch entry referring to the root
+    /// [`PageTable`] itself. No other virtual address may be used to access those
+    /// [`PageTable`]s while this [`RecursiveAccess`] is in use.
+    pub const unsafe fn new_la57(index: u16) -> Self {
+        assert!(index < 512, "recursive index out of range");
+        Self { index, levels: 5 }
+    }
+
+    /// Returns the index of the recursive entry in the root [`PageTable`].
+    pub const fn index(&self) -> u16 {
+        self.index
+    }
+
+    /// Returns the virtual address of the [`PageTable<L>`] used to translate `address`.
+    ///
+    /// Returns [`None`] if the paging hierarchy has no [`PageTable<L>`].
+    pub const fn table_address<L: PageMapLevel>(&self, address: VirtAddr) -> Option<VirtAddr> {
+        let level = (L::INDEX_SHIFT - 12) / 9 + 1;
+        if level > self.levels {
+            return None;
+        }
+
+        let mut value = 0;
+
+        let mut position = 1;
+        while position <= self.levels {
+            let index = if position > self.levels - level {
+                self.index as u64
+            } else {
+                (address.as_u64() >> (12 + 9 * (position + level - 1) as u64)) & 0x1FF
+            };
+            value |= index << (12 + 9 * (position - 1) as u64);
+            position += 1;
+        }
+
+        if self.levels == 4 {
+            Some(VirtAddr::new_truncate(value))
+        } else {
+            Some(VirtAddr::new_truncate_la57(value))
+        }
+    }
+}
+
+impl PageTableAccess for RecursiveAccess {
+    fn table<L: PageMapLevel>(&self, _: PhysAddr, address: VirtAddr) -> Option<&PageTable<L>> {
+        let table = self.table_address::<L>(address)?;
+        // SAFETY:
+        // The invariants of `RecursiveAccess::new()` or `RecursiveAccess::new_la57()` ensure that
+        // the table used to translate `address` is mapped at `table`.
+        Some(unsafe { &*table.as_ptr::<PageTable<L>>() })
+    }
+}
+
+impl PageTableAccessMut for RecursiveAccess {
+    fn table_mut<L: PageMapLevel>(
+        &mut self,
+        _: PhysAddr,
+        address: VirtAddr,
+    ) -> Option<&mut PageTable<L>> {
+        let table = self.table_address::<L>(address)?;
+        // SAFETY:
+        // The invariants of `RecursiveAccess::new()` or `RecursiveAccess::new_la57()` ensure that
+        // the table used to translate `address` is mapped writable at `table` and is not
+        // accessed through any other virtual address.
+        Some(unsafe { &mut *table.as_mut_ptr::<PageTable<L>>() })
+    }
+}
+
+/// Reaches [`PageTable`]s through a direct map of physical memory at a constant offset.
+///
+/// Like [`RecursiveAccess`], this type is neither [`Clone`] nor [`Copy`].
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct OffsetAccess {
+    /// The virtual address at which physical address 0 is mapped.
+    offset: VirtAddr,
+}
+
+impl OffsetAccess {
+    /// Creates a new [`OffsetAccess`] for physical memory mapped starting at `offset`.
+    ///
+    /// # Safety
+    /// Every [`PageTable`] of the paging hierarchy must be mapped writable at `offset` plus its
+    /// physical address. No other virtual address may be used to access those [`PageTable`]s
+    /// while this [`OffsetAccess`] is in use.
+    pub const unsafe fn new(offset: VirtAddr) -> Self {
+        Self { offset }
+    }
+
+    /// Returns the virtual address at which physical address 0 is mapped.
+    pub const fn offset(&self) -> VirtAddr {
+        self.offset
+    }
+
+    /// Returns the virtual address at which the physical address `frame` is mapped.
+    ///
+    /// Returns [`None`] if the resulting virtual address would not be canonical.
+    pub const fn table_address(&self, frame: PhysAddr) -> Option<VirtAddr> {
+        self.offset.checked_add(frame.as_u64())
+    }
+}
+
+impl PageTableAccess for OffsetAccess {
+    fn table<L: PageMapLevel>(&self, frame: PhysAddr, _: VirtAddr) -> Option<&PageTable<L>> {
+        let table = self.table_address(frame)?;
+        // SAFETY:
+        // The invariants of `OffsetAccess::new()` ensure that the table located at `frame` is
+        // mapped at `table`.
+        Some(unsafe { &*table.as_ptr::<PageTable<L>>() })
+    }
+}
+
+impl PageTableAccessMut for OffsetAccess {
+    fn table_mut<L: PageMapLevel>(
+        &mut self,
+        frame: PhysAddr,
+        _: VirtAddr,
+    ) -> Option<&mut PageTable<L>> {
+        let table = self.table_address(frame)?;
+        // SAFETY:
+        // The invariants of `OffsetAccess::new()` ensure that the table located at `frame` is
+        // mapped writable at `table` and is not accessed through any other virtual address.
+        Some(unsafe { &mut *table.as_mut_ptr::<PageTable<L>>() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OffsetAccess, RecursiveAccess};
+    use crate::{
+        addr::VirtAddr,
+        structures::paging::bits64::{
+            mapper::{Mapper, PageTableAccessMut},
+            testing::{phys, virt, Frames, Memory},
+            translate::{MappingFlags, PageSizeKind, PageTableAccess},
+            PageMapEntry, PageTable, Pml1e, Pml2e, Pml3e, Pml4e, Pml5e,
+        },
+    };
+
+    #[test]
+    fn recursive() {
+        let mut memory = Memory::new();
+        let root = memory.allocate();
+        let entry = PageMapEntry::<Pml4e, _>::new()
+            .set_present()
+            .set_writable(true)
+            .set_branch(root);
+        memory.table_mut::<Pml4e>(root, VirtAddr::zero()).unwrap().0[0x1FF] = entry.unclassified();
+
+        let mut mapper = Mapper::new(memory, root);
+        let address = virt(0x0000_7F12_3456_7000);
+        mapper
+            .map_4kib(
+                address,
+                phys(0x4000_0000),
+                MappingFlags::new(),
+                &mut Frames::new(phys(0x10_0000), 3),
+            )
+            .unwrap()
+            .ignore();
+
+        // SAFETY:
+        // Only the table addresses are computed, the tables are never accessed through them.
+        let access = unsafe { RecursiveAccess::new(0x1FF) };
+        let expected = [
+            (access.table_address::<Pml4e>(address), root),
+            (access.table_address::<Pml3e>(address), phys(0x10_0000)),
+            (access.table_address::<Pml2e>(address), phys(0x10_1000)),
+            (access.table_address::<Pml1e>(address), phys(0x10_2000)),
+        ];
+        for (table, frame) in expected {
+            let translation = mapper.translate(table.unwrap()).unwrap();
+            assert_eq!(translation.address, frame);
+            assert_eq!(translation.size, PageSizeKind::Size4KiB);
+        }
+
+        assert_eq!(
+            access.table_address::<Pml4e>(address),
+            Some(virt(0xFFFF_FFFF_FFFF_F000))
+        );
+        assert_eq!(
+            access.table_address::<Pml1e>(address),
+            Some(virt(0xFFFF_FFBF_891A_2000))
+        );
+        assert_eq!(access.table_address::<Pml5e>(address), None);
+
+        // SAFETY:
+        // Only the table addresses are computed, the tables are never accessed through them.
+        let access = unsafe { RecursiveAccess::new_la57(0x100) };
+        assert_eq!(
+            access.table_address::<Pml5e>(address),
+            Some(virt(0xFF00_8040_2010_0000))
+        );
+        assert_eq!(
+            access.table_address::<Pml1e>(address),
+            Some(virt(0xFF00_003F_891A_2000))
+        );
+    }
+
+    #[test]
+    fn offset() {
+        let mut tables = (0..4)
+            .map(|_| PageTable::<Pml1e>::new())
+            .collect::<Vec<_>>();
+        let offset = VirtAddr::from_ptr(tables.as_mut_ptr());
+
+        // SAFETY:
+        // `tables` holds the 4 tables located at physical addresses 0 to 0x3000 and is not
+        // accessed while `access` is in use.
+        let access = unsafe { OffsetAccess::new(offset) };
+        assert_eq!(access.table_address(phys(0x2000)), Some(offset + 0x2000));
+
+        let mut mapper = Mapper::new(access, phys(0));
+        let address = virt(0x0000_7F12_3456_7000);
+        mapper
+            .map_4kib(
+                address,
+                phys(0x4000_0000),
+                MappingFlags::new(),
+                &mut Frames::new(phys(0x1000), 3),
+            )
+            .unwrap()
+            .ignore();
+        let translation = mapper.translate(address + 0x123).unwrap();
+        assert_eq!(translation.address, phys(0x4000_0123));
+
+        assert!(mapper
+            .access()
+            .table::<Pml4e>(phys(0), address)
+            .unwrap()
+            .get(address.index::<Pml4e>())
+            .unwrap()
+            .present()
+            .is_some());
+
+        assert_eq!(
+            tables[3].0[0x167].present().unwrap().leaf().frame(),
+            phys(0x4000_0000)
+        );
+    }
+}
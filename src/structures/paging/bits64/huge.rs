@@ -0,0 +1,240 @@
+//! Definitions to split huge pages into [`PageTable`]s of smaller pages and to merge them back.
+
+use core::marker::PhantomData;
+
+use crate::{
+    addr::PhysAddr,
+    structures::paging::bits64::{
+        Branch, BranchLeafSupport, Leaf, LeafSupport, PageMapEntry, PageMapLevel, PageTable,
+        Unclassified,
+    },
+};
+
+/// Bits of a leaf [`PageMapEntry`] that the processor sets as the page is used.
+const STATUS_MASK: u64 = (1 << 5) | (1 << 6);
+
+/// Bits of a [`PageMapEntry`] that grant access rights to the region it controls.
+const GRANT_MASK: u64 = (1 << 1) | (1 << 2);
+
+/// Bit of a [`PageMapEntry`] that prevents the region it controls from being executed.
+const NO_EXECUTE_BIT: u64 = 1 << 63;
+
+impl<L: BranchLeafSupport> PageMapEntry<L, Leaf>
+where
+    L::Child: LeafSupport,
+{
+    /// Splits the huge page mapped by this [`PageMapEntry`] into `table`, which is located at the
+    /// physical address `frame`, returning the branch [`PageMapEntry`] that replaces this
+    /// [`PageMapEntry`].
+    ///
+    /// Every [`PageMapEntry`] of `table` maps its part of the huge page with the same attributes
+    /// as this [`PageMapEntry`], moving the PAT bit if its position differs between levels. The
+    /// returned [`PageMapEntry`] is present, writable, and accessible to userspace, so that the
+    /// effective access rights of the pages are unchanged.
+    ///
+    /// The TLB entries of the huge page must be invalidated after the returned [`PageMapEntry`]
+    /// has replaced this [`PageMapEntry`].
+    pub const fn split(
+        self,
+        frame: PhysAddr,
+        table: &mut PageTable<L::Child>,
+    ) -> PageMapEntry<L, Branch> {
+        let attributes = self.value & !(L::ADDRESS_MASK | L::PAGE_SIZE_BIT | (1 << L::PAT_BIT_POS));
+        let template = PageMapEntry::<L::Child, Leaf> {
+            value: attributes,
+            phantom: PhantomData,
+        }
+        .set_pat(self.pat());
+
+        let size = 1 << L::Child::INDEX_SHIFT;
+        let mut index = 0;
+        while index < table.0.len() {
+            let base = PhysAddr::new_truncate(self.frame().as_u64() + index as u64 * size);
+            table.0[index] = template.set_leaf(base).unclassified();
+            index += 1;
+        }
+
+        PageMapEntry::<L, _>::new()
+            .set_present()
+            .set_writable(true)
+            .set_user(true)
+            .set_branch(frame)
+    }
+}
+
+impl<L: BranchLeafSupport> PageMapEntry<L, Branch>
+where
+    L::Child: LeafSupport,
+{
+    /// Merges `table`, which is referred to by this [`PageMapEntry`], into a huge page,
+    /// returning the leaf [`PageMapEntry`] that replaces this [`PageMapEntry`] and the physical
+    /// address of `table`, which is no longer used.
+    ///
+    /// `table` can be merged if every [`PageMapEntry`] of it is a present leaf entry, they map
+    /// contiguous physical memory aligned to the size of the huge page, and they have the same
+    /// attributes, ignoring their accessed and dirty bits. The access rights of this
+    /// [`PageMapEntry`] are folded into the returned [`PageMapEntry`], which is accessed or
+    /// dirty if any [`PageMapEntry`] of `table` is.
+    ///
+    /// The TLB entries of the pages of `table` must be invalidated after the returned
+    /// [`PageMapEntry`] has replaced this [`PageMapEntry`].
+    ///
+    /// # Errors
+    /// Returns the index of the first [`PageMapEntry`] of `table` that prevents the merge.
+    pub const fn merge(
+        self,
+        table: &PageTable<L::Child>,
+    ) -> Result<(PageMapEntry<L, Leaf>, PhysAddr), usize> {
+        let first = table.0[0].value;
+        let base = first & L::Child::ADDRESS_MASK;
+        if base & !L::ADDRESS_MASK != 0 {
+            return Err(0);
+        }
+
+        let attributes = first & !(L::Child::ADDRESS_MASK | STATUS_MASK);
+        let size = 1 << L::Child::INDEX_SHIFT;
+        let mut status = 0;
+
+        let mut index = 0;
+        while index < table.0.len() {
+            let value = table.0[index].value;
+            if value & 0b1 == 0
+                || value & L::Child::PAGE_SIZE_BIT != L::Child::PAGE_SIZE_BIT
+                || value & L::Child::ADDRESS_MASK != base + index as u64 * size
+                || value & !(L::Child::ADDRESS_MASK | STATUS_MASK) != attributes
+            {
+                return Err(index);
+            }
+
+            status |= value & STATUS_MASK;
+            index += 1;
+        }
+
+        let rights =
+            (attributes & self.value & GRANT_MASK) | ((attributes | self.value) & NO_EXECUTE_BIT);
+        let entry = PageMapEntry::<L, Unclassified> {
+            value: (attributes & !(GRANT_MASK | NO_EXECUTE_BIT | (1 << L::Child::PAT_BIT_POS)))
+                | rights
+                | status,
+            phantom: PhantomData,
+        }
+        .set_present()
+        .set_leaf(PhysAddr::new_truncate(base))
+        .set_pat(attributes & (1 << L::Child::PAT_BIT_POS) != 0);
+
+        Ok((entry, self.frame()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structures::paging::bits64::{
+        testing::phys, PageMapEntry, PageTable, Pml1e, Pml2e, Pml3e,
+    };
+
+    #[test]
+    fn split_merge_2mib() {
+        let leaf = PageMapEntry::<Pml2e, _>::new()
+            .set_present()
+            .set_writable(true)
+            .set_no_execute(true)
+            .set_accessed(true)
+            .set_available(0x155)
+            .set_leaf(phys(0x4060_0000))
+            .set_pat_index(0b110)
+            .set_global(true)
+            .set_dirty(true)
+            .set_protection_key(3);
+
+        let mut table = PageTable::<Pml1e>::new();
+        let branch = leaf.split(phys(0x7000), &mut table);
+        assert_eq!(branch.frame(), phys(0x7000));
+        assert!(branch.writable() && branch.user() && !branch.no_execute());
+
+        let first = table.get(0).unwrap().present().unwrap().leaf();
+        assert_eq!(first.frame(), phys(0x4060_0000));
+        assert_eq!(first.pat_index(), 0b110);
+        assert_eq!(first.unclassified(), table.0[0]);
+        assert_eq!(table.0[0].value, 0x9AA0_0000_4060_0BF3);
+        assert_eq!(table.0[511].value, 0x9AA0_0000_407F_FBF3);
+
+        let (merged, freed) = branch.merge(&table).unwrap();
+        assert_eq!(merged, leaf);
+        assert_eq!(freed, phys(0x7000));
+
+        // Access rights of the branch entry are folded into the huge page.
+        let (merged, _) = branch
+            .set_writable(false)
+            .set_user(false)
+            .merge(&table)
+            .unwrap();
+        assert_eq!(merged, leaf.set_writable(false));
+
+        // The accessed and dirty bits of the smaller pages are combined.
+        let clean = leaf.set_accessed(false).set_dirty(false);
+        let mut table = PageTable::<Pml1e>::new();
+        let branch = clean.split(phys(0x7000), &mut table);
+        table.0[17] = table.0[17]
+            .present()
+            .unwrap()
+            .leaf()
+            .set_dirty(true)
+            .unclassified();
+        assert_eq!(branch.merge(&table).unwrap().0, clean.set_dirty(true));
+    }
+
+    #[test]
+    fn split_merge_1gib() {
+        let leaf = PageMapEntry::<Pml3e, _>::new()
+            .set_present()
+            .set_user(true)
+            .set_leaf(phys(0x1_4000_0000))
+            .set_pat(true);
+
+        let mut table = PageTable::<Pml2e>::new();
+        let branch = leaf.split(phys(0x3000), &mut table);
+        assert_eq!(table.0[0].value, 0x1_4000_1085);
+        assert_eq!(table.0[511].value, 0x1_7FE0_1085);
+        assert_eq!(branch.merge(&table), Ok((leaf, phys(0x3000))));
+    }
+
+    #[test]
+    fn merge_errors() {
+        let leaf = PageMapEntry::<Pml2e, _>::new()
+            .set_present()
+            .set_writable(true)
+            .set_leaf(phys(0x20_0000));
+        let mut table = PageTable::<Pml1e>::new();
+        let branch = leaf.split(phys(0x7000), &mut table);
+
+        let mut attributes = table;
+        attributes.0[3] = attributes.0[3]
+            .present()
+            .unwrap()
+            .set_writable(false)
+            .unclassified();
+        assert_eq!(branch.merge(&attributes), Err(3));
+
+        let mut missing = table;
+        missing.0[511] = missing.0[511].clear_present();
+        assert_eq!(branch.merge(&missing), Err(511));
+
+        let mut discontiguous = table;
+        discontiguous.0[42] = discontiguous.0[42]
+            .present()
+            .unwrap()
+            .set_leaf(phys(0x1000))
+            .unclassified();
+        assert_eq!(branch.merge(&discontiguous), Err(42));
+
+        let mut misaligned = PageTable::<Pml1e>::new();
+        for (index, entry) in misaligned.0.iter_mut().enumerate() {
+            *entry = table.0[index]
+                .present()
+                .unwrap()
+                .set_leaf(phys(0x1000 + index as u64 * 0x1000))
+                .unclassified();
+        }
+        assert_eq!(branch.merge(&misaligned), Err(0));
+    }
+}
@@ -0,0 +1,759 @@
+//! Definitions and interfaces to map and unmap pages using 4-level paging structures.
+
+use crate::{
+    addr::{PhysAddr, VirtAddr},
+    structures::paging::bits64::{
+        page::{Page, PageSize, PhysFrame},
+        translate::{MappingFlags, PageSizeKind, PageTableAccess, TranslateError, Translation},
+        BranchLeafSupport, BranchSupport, LeafSupport, PageMapEntry, PageMapLevel, PageTable,
+        Pml1e, Pml2e, Pml3e, Pml4e,
+    },
+};
+
+/// Provides mutable access to the [`PageTable`]s that make up a paging hierarchy.
+pub trait PageTableAccessMut: PageTableAccess {
+    /// Returns a mutable reference to the [`PageTable`] located at the physical address `frame`,
+    /// used to translate `address`.
+    ///
+    /// Returns [`None`] if the [`PageTable`] cannot be accessed.
+    fn table_mut<L: PageMapLevel>(
+        &mut self,
+        frame: PhysAddr,
+        address: VirtAddr,
+    ) -> Option<&mut PageTable<L>>;
+}
+
+/// Allocates 4 KiB frames of physical memory.
+pub trait FrameAllocator {
+    /// Allocates a 4 KiB frame of physical memory, returning its physical address.
+    ///
+    /// Returns [`None`] if no frame could be allocated.
+    fn allocate_frame(&mut self) -> Option<PhysAddr>;
+
+    /// Returns the 4 KiB frame of physical memory starting at `frame` to this
+    /// [`FrameAllocator`].
+    ///
+    /// # Safety
+    /// - `frame` must have been allocated by this [`FrameAllocator`].
+    /// - `frame` must no longer be in use.
+    unsafe fn deallocate_frame(&mut self, frame: PhysAddr);
+}
+
+/// Maps and unmaps pages in a 4-level paging hierarchy.
+///
+/// Missing intermediate [`PageTable`]s are allocated when a page is mapped. The
+/// [`PageMapEntry`]s that refer to them are created with the [parent flags][parent], which are
+/// writable and accessible to userspace by default, so that the access rights of each page are
+/// determined by the [`MappingFlags`] of its leaf [`PageMapEntry`] alone. Intermediate
+/// [`PageMapEntry`]s that already exist are never modified.
+///
+/// [parent]: Mapper::set_parent_flags
+#[derive(Debug)]
+pub struct Mapper<A: PageTableAccessMut> {
+    /// Access to the [`PageTable`]s of the paging hierarchy.
+    access: A,
+    /// The physical address of the [`PageTable<Pml4e>`] at the root of the paging hierarchy.
+    root: PhysAddr,
+    /// The [`MappingFlags`] of the [`PageMapEntry`]s referring to new intermediate
+    /// [`PageTable`]s.
+    parent_flags: MappingFlags,
+}
+
+impl<A: PageTableAccessMut> Mapper<A> {
+    /// Creates a new [`Mapper`] for the paging hierarchy rooted at the [`PageTable<Pml4e>`]
+    /// located at the physical address `root`.
+    pub const fn new(access: A, root: PhysAddr) -> Self {
+        Self {
+            access,
+            root,
+            parent_flags: MappingFlags::new().set_writable(true).set_user(true),
+        }
+    }
+
+    /// Returns the [`MappingFlags`] of the [`PageMapEntry`]s referring to intermediate
+    /// [`PageTable`]s created by this [`Mapper`].
+    pub const fn parent_flags(&self) -> MappingFlags {
+        self.parent_flags
+    }
+
+    /// Sets the [`MappingFlags`] of the [`PageMapEntry`]s referring to intermediate
+    /// [`PageTable`]s created by this [`Mapper`].
+    ///
+    /// Only [`MappingFlags::writable()`], [`MappingFlags::user()`],
+    /// [`MappingFlags::write_through()`], [`MappingFlags::cache_disable()`], and
+    /// [`MappingFlags::no_execute()`] apply to intermediate [`PageMapEntry`]s, and restrict
+    /// every page mapped through them.
+    pub const fn set_parent_flags(mut self, flags: MappingFlags) -> Self {
+        self.parent_flags = flags;
+        self
+    }
+
+    /// Returns the physical address of the [`PageTable<Pml4e>`] at the root of the paging
+    /// hierarchy.
+    pub const fn root(&self) -> PhysAddr {
+        self.root
+    }
+
+    /// Returns a reference to the [`PageTableAccessMut`] used by this [`Mapper`].
+    pub const fn access(&self) -> &A {
+        &self.access
+    }
+
+    /// Returns a mutable reference to the [`PageTableAccessMut`] used by this [`Mapper`].
+    pub const fn access_mut(&mut self) -> &mut A {
+        &mut self.access
+    }
+
+    /// Translates `address` using the paging hierarchy of this [`Mapper`].
+    ///
+    /// # Errors
+    /// See [`PageTable<Pml4e>::translate()`].
+    pub fn translate(&self, address: VirtAddr) -> Result<Translation, TranslateError> {
+        self.access
+            .table::<Pml4e>(self.root, address)
+            .ok_or(TranslateError::InaccessibleTable(self.root))?
+            .translate(&self.access, address)
+    }
+
+    /// Maps `page` to `frame`, returning the [`MapperFlush`] of the page.
+    ///
+    /// # Errors
+    /// See [`Mapper::map_4kib()`], [`Mapper::map_2mib()`], and [`Mapper::map_1gib()`].
+    pub fn map<S: PageSize, F: FrameAllocator>(
+        &mut self,
+        page: Page<S>,
+        frame: PhysFrame<S>,
+        flags: MappingFlags,
+        allocator: &mut F,
+    ) -> Result<MapperFlush, MapError> {
+        let (address, frame) = (page.start_address(), frame.start_address());
+        match S::KIND {
+            PageSizeKind::Size4KiB => self.map_4kib(address, frame, flags, allocator),
+            PageSizeKind::Size2MiB => self.map_2mib(address, frame, flags, allocator),
+            PageSizeKind::Size1GiB => self.map_1gib(address, frame, flags, allocator),
+        }
+    }
+
+    /// Maps the 4 KiB page starting at `address` to the 4 KiB frame starting at `frame`, returning
+    /// the [`MapperFlush`] of the page.
+    ///
+    /// Missing intermediate [`PageTable`]s are allocated using `allocator`, and referred to with
+    /// the [parent flags](Mapper::set_parent_flags) of this [`Mapper`].
+    ///
+    /// # Errors
+    /// - [`MapError::NonCanonical`]: `address` is not a canonical 48-bit address.
+    /// - [`MapError::Misaligned`]: `address` or `frame` is not 4 KiB aligned.
+    /// - [`MapError::AlreadyMapped`]: the page is already mapped.
+    /// - [`MapError::ParentHugePage`]: the page is part of a 2 MiB or 1 GiB page.
+    /// - [`MapError::AllocationFailed`]: an intermediate [`PageTable`] could not be allocated.
+    /// - [`MapError::InaccessibleTable`]: a [`PageTable`] could not be accessed.
+    pub fn map_4kib<F: FrameAllocator>(
+        &mut self,
+        address: VirtAddr,
+        frame: PhysAddr,
+        flags: MappingFlags,
+        allocator: &mut F,
+    ) -> Result<MapperFlush, MapError> {
+        check_mapping(address, frame, PageSizeKind::Size4KiB)?;
+
+        let pml3 = self.next_table_pml4(address, allocator)?;
+        let pml2 = self.next_table::<Pml3e, _>(pml3, address, allocator)?;
+        let pml1 = self.next_table::<Pml2e, _>(pml2, address, allocator)?;
+        self.install::<Pml1e>(pml1, address, frame, flags)?;
+
+        Ok(MapperFlush::new(address, PageSizeKind::Size4KiB))
+    }
+
+    /// Maps the 2 MiB page starting at `address` to the 2 MiB frame starting at `frame`, returning
+    /// the [`MapperFlush`] of the page.
+    ///
+    /// Missing intermediate [`PageTable`]s are allocated using `allocator`, and referred to with
+    /// the [parent flags](Mapper::set_parent_flags) of this [`Mapper`].
+    ///
+    /// # Errors
+    /// - [`MapError::NonCanonical`]: `address` is not a canonical 48-bit address.
+    /// - [`MapError::Misaligned`]: `address` or `frame` is not 2 MiB aligned.
+    /// - [`MapError::AlreadyMapped`]: the page, or part of it, is already mapped.
+    /// - [`MapError::ParentHugePage`]: the page is part of a 1 GiB page.
+    /// - [`MapError::AllocationFailed`]: an intermediate [`PageTable`] could not be allocated.
+    /// - [`MapError::InaccessibleTable`]: a [`PageTable`] could not be accessed.
+    pub fn map_2mib<F: FrameAllocator>(
+        &mut self,
+        address: VirtAddr,
+        frame: PhysAddr,
+        flags: MappingFlags,
+        allocator: &mut F,
+    ) -> Result<MapperFlush, MapError> {
+        check_mapping(address, frame, PageSizeKind::Size2MiB)?;
+
+        let pml3 = self.next_table_pml4(address, allocator)?;
+        let pml2 = self.next_table::<Pml3e, _>(pml3, address, allocator)?;
+        self.install::<Pml2e>(pml2, address, frame, flags)?;
+
+        Ok(MapperFlush::new(address, PageSizeKind::Size2MiB))
+    }
+
+    /// Maps the 1 GiB page starting at `address` to the 1 GiB frame starting at `frame`, returning
+    /// the [`MapperFlush`] of the page.
+    ///
+    /// Missing intermediate [`PageTable`]s are allocated using `allocator`, and referred to with
+    /// the [parent flags](Mapper::set_parent_flags) of this [`Mapper`].
+    ///
+    /// # Errors
+    /// - [`MapError::NonCanonical`]: `address` is not a canonical 48-bit address.
+    /// - [`MapError::Misaligned`]: `address` or `frame` is not 1 GiB aligned.
+    /// - [`MapError::AlreadyMapped`]: the page, or part of it, is already mapped.
+    /// - [`MapError::AllocationFailed`]: an intermediate [`PageTable`] could not be allocated.
+    /// - [`MapError::InaccessibleTable`]: a [`PageTable`] could not be accessed.
+    pub fn map_1gib<F: FrameAllocator>(
+        &mut self,
+        address: VirtAddr,
+        frame: PhysAddr,
+        flags: MappingFlags,
+        allocator: &mut F,
+    ) -> Result<MapperFlush, MapError> {
+        check_mapping(address, frame, PageSizeKind::Size1GiB)?;
+
+        let pml3 = self.next_table_pml4(address, allocator)?;
+        self.install::<Pml3e>(pml3, address, frame, flags)?;
+
+        Ok(MapperFlush::new(address, PageSizeKind::Size1GiB))
+    }
+
+    /// Unmaps the page containing `address`, returning the physical address of the frame it was
+    /// mapped to and the [`MapperFlush`] of the page.
+    ///
+    /// Intermediate [`PageTable`]s are not freed, even if they become empty.
+    ///
+    /// # Errors
+    /// - [`TranslateError::NonCanonical`]: `address` is not a canonical 48-bit address.
+    /// - [`TranslateError::NotMapped`]: `address` is not mapped.
+    /// - [`TranslateError::InaccessibleTable`]: a [`PageTable`] could not be accessed.
+    pub fn unmap(&mut self, address: VirtAddr) -> Result<(PhysAddr, MapperFlush), TranslateError> {
+        if !address.is_canonical_48() {
+            return Err(TranslateError::NonCanonical);
+        }
+
+        let pml4_table = self
+            .table_mut::<Pml4e>(self.root, address)
+            .map_err(TranslateError::InaccessibleTable)?;
+        let pml3 = pml4_table.0[address.index::<Pml4e>()]
+            .present()
+            .ok_or(TranslateError::NotMapped)?
+            .branch()
+            .frame();
+
+        let pml3_table = self
+            .table_mut::<Pml3e>(pml3, address)
+            .map_err(TranslateError::InaccessibleTable)?;
+        let entry = &mut pml3_table.0[address.index::<Pml3e>()];
+        let present = entry.present().ok_or(TranslateError::NotMapped)?;
+        if let Some(leaf) = present.leaf_opt() {
+            *entry = PageMapEntry::new();
+            return Ok((
+                leaf.frame(),
+                MapperFlush::new(address, PageSizeKind::Size1GiB),
+            ));
+        }
+        let pml2 = present
+            .branch_opt()
+            .ok_or(TranslateError::NotMapped)?
+            .frame();
+
+        let pml2_table = self
+            .table_mut::<Pml2e>(pml2, address)
+            .map_err(TranslateError::InaccessibleTable)?;
+        let entry = &mut pml2_table.0[address.index::<Pml2e>()];
+        let present = entry.present().ok_or(TranslateError::NotMapped)?;
+        if let Some(leaf) = present.leaf_opt() {
+            *entry = PageMapEntry::new();
+            return Ok((
+                leaf.frame(),
+                MapperFlush::new(address, PageSizeKind::Size2MiB),
+            ));
+        }
+        let pml1 = present
+            .branch_opt()
+            .ok_or(TranslateError::NotMapped)?
+            .frame();
+
+        let pml1_table = self
+            .table_mut::<Pml1e>(pml1, address)
+            .map_err(TranslateError::InaccessibleTable)?;
+        let entry = &mut pml1_table.0[address.index::<Pml1e>()];
+        let leaf = entry.present().ok_or(TranslateError::NotMapped)?.leaf();
+        *entry = PageMapEntry::new();
+        Ok((
+            leaf.frame(),
+            MapperFlush::new(address, PageSizeKind::Size4KiB),
+        ))
+    }
+
+    /// Returns a mutable reference to the [`PageTable`] located at `frame`, used to translate
+    /// `address`.
+    fn table_mut<L: PageMapLevel>(
+        &mut self,
+        frame: PhysAddr,
+        address: VirtAddr,
+    ) -> Result<&mut PageTable<L>, PhysAddr> {
+        self.access.table_mut(frame, address).ok_or(frame)
+    }
+
+    /// Returns the physical address of the [`PageTable<Pml3e>`] used to translate `address`,
+    /// allocating it if it is missing.
+    fn next_table_pml4<F: FrameAllocator>(
+        &mut self,
+        address: VirtAddr,
+        allocator: &mut F,
+    ) -> Result<PhysAddr, MapError> {
+        let entry = self
+            .table_mut::<Pml4e>(self.root, address)
+            .map_err(MapError::InaccessibleTable)?
+            .0[address.index::<Pml4e>()];
+        match entry.present() {
+            Some(entry) => Ok(entry.branch().frame()),
+            None => self.create_table::<Pml4e, _>(self.root, address, allocator),
+        }
+    }
+
+    /// Returns the physical address of the [`PageTable`] referred to by the [`PageMapEntry`] of
+    /// the [`PageTable<L>`] located at `frame` used to translate `address`, allocating it if it
+    /// is missing.
+    fn next_table<L: BranchLeafSupport, F: FrameAllocator>(
+        &mut self,
+        frame: PhysAddr,
+        address: VirtAddr,
+        allocator: &mut F,
+    ) -> Result<PhysAddr, MapError> {
+        let entry = self
+            .table_mut::<L>(frame, address)
+            .map_err(MapError::InaccessibleTable)?
+            .0[address.index::<L>()];
+        match entry.present() {
+            Some(entry) => entry
+                .branch_opt()
+                .map(|entry| entry.frame())
+                .ok_or(MapError::ParentHugePage),
+            None => self.create_table::<L, _>(frame, address, allocator),
+        }
+    }
+
+    /// Allocates a new [`PageTable`] and installs it in the [`PageTable<L>`] located at `frame`
+    /// as the [`PageMapEntry`] used to translate `address`, returning the physical address of the
+    /// new [`PageTable`].
+    ///
+    /// The new [`PageTable`] is cleared after it is installed, so that it can be reached through
+    /// a recursive [`PageTableAccessMut`]. If it cannot be cleared, it is uninstalled again and
+    /// its frame is returned to `allocator`.
+    fn create_table<L: BranchSupport, F: FrameAllocator>(
+        &mut self,
+        frame: PhysAddr,
+        address: VirtAddr,
+        allocator: &mut F,
+    ) -> Result<PhysAddr, MapError> {
+        let table = allocator
+            .allocate_frame()
+            .ok_or(MapError::AllocationFailed)?;
+        let index = address.index::<L>();
+
+        let flags = self.parent_flags;
+        let entry = PageMapEntry::<L, _>::new()
+            .set_present()
+            .set_writable(flags.writable())
+            .set_user(flags.user())
+            .set_write_through(flags.write_through())
+            .set_cache_disable(flags.cache_disable())
+            .set_no_execute(flags.no_execute())
+            .set_branch(table);
+
+        let Ok(parent) = self.table_mut::<L>(frame, address) else {
+            // SAFETY:
+            // `table` was allocated by `allocator` and was never installed.
+            unsafe { allocator.deallocate_frame(table) };
+            return Err(MapError::InaccessibleTable(frame));
+        };
+        parent.0[index] = entry.unclassified();
+
+        if let Ok(child) = self.table_mut::<L::Child>(table, address) {
+            *child = PageTable::new();
+            return Ok(table);
+        }
+
+        // The frame can only be reused once no entry refers to it anymore.
+        if let Ok(parent) = self.table_mut::<L>(frame, address) {
+            parent.0[index] = PageMapEntry::new();
+            // SAFETY:
+            // `table` was allocated by `allocator` and no entry refers to it anymore.
+            unsafe { allocator.deallocate_frame(table) };
+        }
+
+        Err(MapError::InaccessibleTable(table))
+    }
+
+    /// Installs a leaf [`PageMapEntry`] mapping `frame` with `flags` in the [`PageTable<L>`]
+    /// located at `table` as the [`PageMapEntry`] used to translate `address`.
+    fn install<L: LeafSupport>(
+        &mut self,
+        table: PhysAddr,
+        address: VirtAddr,
+        frame: PhysAddr,
+        flags: MappingFlags,
+    ) -> Result<(), MapError> {
+        let entry = &mut self
+            .table_mut::<L>(table, address)
+            .map_err(MapError::InaccessibleTable)?
+            .0[address.index::<L>()];
+        if entry.present().is_some() {
+            return Err(MapError::AlreadyMapped);
+        }
+
+        *entry = PageMapEntry::new()
+            .set_present()
+            .set_writable(flags.writable())
+            .set_user(flags.user())
+            .set_write_through(flags.write_through())
+            .set_cache_disable(flags.cache_disable())
+            .set_no_execute(flags.no_execute())
+            .set_leaf(frame)
+            .set_global(flags.global())
+            .set_pat(flags.pat())
+            .unclassified();
+
+        Ok(())
+    }
+}
+
+/// A page whose TLB entries must be invalidated after its mapping has been modified.
+///
+/// This is returned by the functions of [`Mapper`] that modify a mapping and must be consumed,
+/// either by invalidating the TLB entries of the page or by explicitly ignoring them, for
+/// example when the paging hierarchy is not active.
+#[must_use = "the TLB entries of the page must be invalidated or explicitly ignored"]
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct MapperFlush {
+    /// The virtual address of the start of the page.
+    page: VirtAddr,
+    /// The size of the page.
+    size: PageSizeKind,
+}
+
+impl MapperFlush {
+    /// Creates a new [`MapperFlush`] for the page of `size` containing `address`.
+    pub const fn new(address: VirtAddr, size: PageSizeKind) -> Self {
+        Self {
+            page: address.align_down(size.size()),
+            size,
+        }
+    }
+
+    /// Returns the virtual address of the start of the page.
+    pub const fn page(&self) -> VirtAddr {
+        self.page
+    }
+
+    /// Returns the size of the page.
+    pub const fn size(&self) -> PageSizeKind {
+        self.size
+    }
+
+    /// Invalidates the TLB entries of the page in the current address space using `strategy`.
+    #[cfg(feature = "instructions")]
+    pub fn flush(self, strategy: crate::instructions::paging::FlushStrategy) {
+        crate::instructions::paging::TlbFlush::from(self).flush(strategy);
+    }
+
+    /// Ignores the TLB entries of the page.
+    ///
+    /// This is correct if the paging hierarchy is not active on any processor, or if the TLB
+    /// entries of the page are invalidated by other means.
+    pub fn ignore(self) {}
+}
+
+/// Checks that `address` can be mapped to `frame` with a page of `size`.
+const fn check_mapping(
+    address: VirtAddr,
+    frame: PhysAddr,
+    size: PageSizeKind,
+) -> Result<(), MapError> {
+    if !address.is_canonical_48() {
+        return Err(MapError::NonCanonical);
+    }
+
+    if !address.is_aligned(size.size()) || !frame.is_aligned(size.size()) {
+        return Err(MapError::Misaligned);
+    }
+
+    Ok(())
+}
+
+/// Errors that can occur when mapping a page.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum MapError {
+    /// The virtual address is not canonical.
+    NonCanonical,
+    /// The virtual address or physical address is not aligned to the size of the page.
+    Misaligned,
+    /// The page, or part of it, is already mapped.
+    AlreadyMapped,
+    /// The page is part of a larger page that is already mapped.
+    ParentHugePage,
+    /// An intermediate [`PageTable`] could not be allocated.
+    AllocationFailed,
+    /// The [`PageTable`] located at the given physical address could not be accessed.
+    InaccessibleTable(PhysAddr),
+}
+
+impl core::fmt::Display for MapError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NonCanonical => write!(f, "virtual address is not canonical"),
+            Self::Misaligned => write!(f, "address is not aligned to the page size"),
+            Self::AlreadyMapped => write!(f, "page is already mapped"),
+            Self::ParentHugePage => write!(f, "page is part of a huge page"),
+            Self::AllocationFailed => write!(f, "page table allocation failed"),
+            Self::InaccessibleTable(frame) => {
+                write!(f, "page table at {frame:#x} could not be accessed")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        addr::{PhysAddr, VirtAddr},
+        structures::paging::bits64::{
+            mapper::{MapError, Mapper, MapperFlush, PageTableAccessMut},
+            page::{Page, PhysFrame, Size2MiB},
+            testing::{phys, virt, Frames, Memory},
+            translate::{MappingFlags, PageSizeKind, PageTableAccess, TranslateError},
+            PageMapLevel, PageTable, Pml3e,
+        },
+    };
+
+    /// A [`Memory`] that refuses mutable access to the [`PageTable`] located at a given frame.
+    struct Refusing(Memory, PhysAddr);
+
+    impl PageTableAccess for Refusing {
+        fn table<L: PageMapLevel>(
+            &self,
+            frame: PhysAddr,
+            address: VirtAddr,
+        ) -> Option<&PageTable<L>> {
+            self.0.table(frame, address)
+        }
+    }
+
+    impl PageTableAccessMut for Refusing {
+        fn table_mut<L: PageMapLevel>(
+            &mut self,
+            frame: PhysAddr,
+            address: VirtAddr,
+        ) -> Option<&mut PageTable<L>> {
+            if frame == self.1 {
+                return None;
+            }
+
+            self.0.table_mut(frame, address)
+        }
+    }
+
+    /// Creates a [`Mapper`] with an empty root table.
+    fn mapper() -> Mapper<Memory> {
+        let mut memory = Memory::new();
+        let root = memory.allocate();
+
+        Mapper::new(memory, root)
+    }
+
+    #[test]
+    fn map_unmap() {
+        let mut mapper = mapper();
+        let mut frames = Frames::new(phys(0x10_0000), 16);
+        let flags = MappingFlags::new()
+            .set_writable(true)
+            .set_no_execute(true)
+            .set_global(true);
+
+        mapper
+            .map_4kib(
+                virt(0xFFFF_8000_0000_1000),
+                phys(0x5000),
+                flags,
+                &mut frames,
+            )
+            .unwrap()
+            .ignore();
+        mapper
+            .map_2mib(
+                virt(0xFFFF_8000_0020_0000),
+                phys(0x40_0000),
+                flags,
+                &mut frames,
+            )
+            .unwrap()
+            .ignore();
+        mapper
+            .map_1gib(
+                virt(0x4000_0000),
+                phys(0x8000_0000),
+                MappingFlags::new(),
+                &mut frames,
+            )
+            .unwrap()
+            .ignore();
+
+        let translation = mapper.translate(virt(0xFFFF_8000_0000_1234)).unwrap();
+        assert_eq!(translation.address, phys(0x5234));
+        assert_eq!(translation.size, PageSizeKind::Size4KiB);
+        assert_eq!(translation.flags, flags);
+
+        let translation = mapper.translate(virt(0xFFFF_8000_002F_FFFF)).unwrap();
+        assert_eq!(translation.address, phys(0x4F_FFFF));
+        assert_eq!(translation.size, PageSizeKind::Size2MiB);
+
+        let translation = mapper.translate(virt(0x4000_0000)).unwrap();
+        assert_eq!(translation.address, phys(0x8000_0000));
+        assert_eq!(translation.size, PageSizeKind::Size1GiB);
+        assert!(!translation.flags.writable());
+
+        assert_eq!(
+            mapper.unmap(virt(0xFFFF_8000_0000_1FFF)),
+            Ok((
+                phys(0x5000),
+                MapperFlush::new(virt(0xFFFF_8000_0000_1000), PageSizeKind::Size4KiB)
+            ))
+        );
+        assert_eq!(
+            mapper.unmap(virt(0xFFFF_8000_0000_1000)),
+            Err(TranslateError::NotMapped)
+        );
+        assert_eq!(
+            mapper.unmap(virt(0xFFFF_8000_0020_0000)),
+            Ok((
+                phys(0x40_0000),
+                MapperFlush::new(virt(0xFFFF_8000_0020_0000), PageSizeKind::Size2MiB)
+            ))
+        );
+        assert_eq!(
+            mapper.unmap(virt(0x4123_4567)),
+            Ok((
+                phys(0x8000_0000),
+                MapperFlush::new(virt(0x4000_0000), PageSizeKind::Size1GiB)
+            ))
+        );
+        assert_eq!(
+            mapper.translate(virt(0x4000_0000)),
+            Err(TranslateError::NotMapped)
+        );
+    }
+
+    #[test]
+    fn parent_flags() {
+        let mut mapper = mapper().set_parent_flags(MappingFlags::new().set_no_execute(true));
+        let mut frames = Frames::new(phys(0x10_0000), 16);
+        let flags = MappingFlags::new().set_writable(true).set_user(true);
+
+        mapper
+            .map_4kib(virt(0x1000), phys(0x5000), flags, &mut frames)
+            .unwrap()
+            .ignore();
+
+        let translation = mapper.translate(virt(0x1000)).unwrap();
+        assert!(!translation.flags.writable() && !translation.flags.user());
+        assert!(translation.flags.no_execute());
+    }
+
+    #[test]
+    fn map_range() {
+        let mut mapper = mapper();
+        let mut frames = Frames::new(phys(0x10_0000), 16);
+        let flags = MappingFlags::new().set_writable(true);
+
+        let start = Page::<Size2MiB>::containing_address(virt(0xFFFF_8000_0000_0000));
+        let frame = PhysFrame::<Size2MiB>::containing_address(phys(0x4000_0000));
+        for (page, frame) in Page::range(start, start + 4).zip(PhysFrame::range(frame, frame + 4)) {
+            mapper
+                .map(page, frame, flags, &mut frames)
+                .unwrap()
+                .ignore();
+        }
+
+        let translation = mapper.translate(virt(0xFFFF_8000_0071_2345)).unwrap();
+        assert_eq!(translation.address, phys(0x4071_2345));
+        assert_eq!(translation.size, PageSizeKind::Size2MiB);
+        assert!(mapper.translate(virt(0xFFFF_8000_0080_0000)).is_err());
+    }
+
+    #[test]
+    fn map_errors() {
+        let mut mapper = mapper();
+        let mut frames = Frames::new(phys(0x10_0000), 16);
+        let flags = MappingFlags::new();
+
+        assert_eq!(
+            mapper.map_4kib(virt(0x8000_0000_0000), phys(0x1000), flags, &mut frames),
+            Err(MapError::NonCanonical)
+        );
+        assert_eq!(
+            mapper.map_2mib(virt(0x20_1000), phys(0x20_0000), flags, &mut frames),
+            Err(MapError::Misaligned)
+        );
+        assert_eq!(
+            mapper.map_1gib(virt(0x4000_0000), phys(0x20_0000), flags, &mut frames),
+            Err(MapError::Misaligned)
+        );
+
+        mapper
+            .map_2mib(virt(0x20_0000), phys(0x20_0000), flags, &mut frames)
+            .unwrap()
+            .ignore();
+        assert_eq!(
+            mapper.map_2mib(virt(0x20_0000), phys(0x40_0000), flags, &mut frames),
+            Err(MapError::AlreadyMapped)
+        );
+        assert_eq!(
+            mapper.map_4kib(virt(0x20_1000), phys(0x1000), flags, &mut frames),
+            Err(MapError::ParentHugePage)
+        );
+        assert_eq!(
+            mapper.map_1gib(virt(0), phys(0), flags, &mut frames),
+            Err(MapError::AlreadyMapped)
+        );
+
+        let mut frames = Frames::new(phys(0x10_0000), 2);
+        assert_eq!(
+            mapper.map_4kib(
+                virt(0xFFFF_8000_0000_0000),
+                phys(0x1000),
+                flags,
+                &mut frames
+            ),
+            Err(MapError::AllocationFailed)
+        );
+    }
+
+    #[test]
+    fn inaccessible_child() {
+        let mut memory = Memory::new();
+        let root = memory.allocate();
+        let mut mapper = Mapper::new(Refusing(memory, phys(0x10_1000)), root);
+        let mut frames = Frames::new(phys(0x10_0000), 16);
+
+        assert_eq!(
+            mapper.map_4kib(virt(0x1000), phys(0x5000), MappingFlags::new(), &mut frames),
+            Err(MapError::InaccessibleTable(phys(0x10_1000)))
+        );
+        assert_eq!(frames.free(), [phys(0x10_1000)]);
+
+        let pml3_table = mapper
+            .access()
+            .table::<Pml3e>(phys(0x10_0000), virt(0x1000))
+            .unwrap();
+        assert!(pml3_table.0[0].present().is_none());
+        assert_eq!(
+            mapper.translate(virt(0x1000)),
+            Err(TranslateError::NotMapped)
+        );
+    }
+}
@@ -0,0 +1,590 @@
+//! Definitions of pages and frames of the sizes supported by 4-level and 5-level paging.
+
+use core::{marker::PhantomData, ops};
+
+use crate::{
+    addr::{PhysAddr, VirtAddr},
+    structures::paging::bits64::{translate::PageSizeKind, LeafSupport, Pml1e, Pml2e, Pml3e},
+};
+
+/// A 4 KiB page, mapped by a [`PageMapEntry<Pml1e, Leaf>`][leaf].
+///
+/// [leaf]: crate::structures::paging::bits64::PageMapEntry
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Size4KiB {}
+
+/// A 2 MiB page, mapped by a [`PageMapEntry<Pml2e, Leaf>`][leaf].
+///
+/// [leaf]: crate::structures::paging::bits64::PageMapEntry
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Size2MiB {}
+
+/// A 1 GiB page, mapped by a [`PageMapEntry<Pml3e, Leaf>`][leaf].
+///
+/// [leaf]: crate::structures::paging::bits64::PageMapEntry
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Size1GiB {}
+
+/// Marker trait indicating that the implementer is a page size supported by 4-level and 5-level
+/// paging.
+pub trait PageSize:
+    Copy + Eq + Ord + core::hash::Hash + core::fmt::Debug + private::Sealed
+{
+    /// The size of the page in bytes.
+    const SIZE: u64;
+
+    /// The [`PageSizeKind`] corresponding to this [`PageSize`].
+    const KIND: PageSizeKind;
+
+    /// The [`LeafSupport`] level whose leaf entries map pages of this [`PageSize`].
+    type Level: LeafSupport;
+}
+
+impl PageSize for Size4KiB {
+    const SIZE: u64 = 0x1000;
+    const KIND: PageSizeKind = PageSizeKind::Size4KiB;
+    type Level = Pml1e;
+}
+
+impl PageSize for Size2MiB {
+    const SIZE: u64 = 0x20_0000;
+    const KIND: PageSizeKind = PageSizeKind::Size2MiB;
+    type Level = Pml2e;
+}
+
+impl PageSize for Size1GiB {
+    const SIZE: u64 = 0x4000_0000;
+    const KIND: PageSizeKind = PageSizeKind::Size1GiB;
+    type Level = Pml3e;
+}
+
+/// A page of virtual memory of [`PageSize`] `S`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Page<S: PageSize = Size4KiB> {
+    /// The first address of the [`Page`].
+    start: VirtAddr,
+    /// Phantom data used to ensure type safety.
+    phantom: PhantomData<S>,
+}
+
+impl<S: PageSize> Page<S> {
+    /// The size of the [`Page`] in bytes.
+    pub const SIZE: u64 = S::SIZE;
+
+    /// Returns the [`Page`] starting at `address`.
+    ///
+    /// # Errors
+    /// Returns `address` if it is not aligned to the size of the [`Page`].
+    pub const fn from_start_address(address: VirtAddr) -> Result<Self, VirtAddr> {
+        if !address.is_aligned(S::SIZE) {
+            return Err(address);
+        }
+
+        Ok(Self {
+            start: address,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns the [`Page`] that contains `address`.
+    pub const fn containing_address(address: VirtAddr) -> Self {
+        Self {
+            start: address.align_down(S::SIZE),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the first address of this [`Page`].
+    pub const fn start_address(self) -> VirtAddr {
+        self.start
+    }
+
+    /// Returns the size of this [`Page`] in bytes.
+    pub const fn size(self) -> u64 {
+        S::SIZE
+    }
+
+    /// Returns a [`PageRange`] of the pages from `start` up to, but excluding, `end`.
+    pub const fn range(start: Self, end: Self) -> PageRange<S> {
+        PageRange { start, end }
+    }
+
+    /// Returns a [`PageRangeInclusive`] of the pages from `start` up to, and including, `end`.
+    pub const fn range_inclusive(start: Self, end: Self) -> PageRangeInclusive<S> {
+        PageRangeInclusive {
+            start,
+            end,
+            exhausted: false,
+        }
+    }
+
+    /// Returns the [`Page`] `count` pages after this [`Page`].
+    ///
+    /// Returns [`None`] if the resulting [`Page`] does not start at a canonical address.
+    pub const fn checked_add(self, count: u64) -> Option<Self> {
+        let Some(offset) = count.checked_mul(S::SIZE) else {
+            return None;
+        };
+
+        match self.start.checked_add(offset) {
+            Some(start) => Some(Self {
+                start,
+                phantom: PhantomData,
+            }),
+            None => None,
+        }
+    }
+
+    /// Returns the [`Page`] `count` pages before this [`Page`].
+    ///
+    /// Returns [`None`] if the resulting [`Page`] does not start at a canonical address.
+    pub const fn checked_sub(self, count: u64) -> Option<Self> {
+        let Some(offset) = count.checked_mul(S::SIZE) else {
+            return None;
+        };
+
+        match self.start.checked_sub(offset) {
+            Some(start) => Some(Self {
+                start,
+                phantom: PhantomData,
+            }),
+            None => None,
+        }
+    }
+}
+
+impl<S: PageSize> ops::Add<u64> for Page<S> {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the resulting [`Page`] does not start at a canonical address.
+    fn add(self, rhs: u64) -> Self::Output {
+        self.checked_add(rhs).expect("page overflow")
+    }
+}
+
+impl<S: PageSize> ops::AddAssign<u64> for Page<S> {
+    fn add_assign(&mut self, rhs: u64) {
+        *self = *self + rhs;
+    }
+}
+
+impl<S: PageSize> ops::Sub<u64> for Page<S> {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the resulting [`Page`] does not start at a canonical address.
+    fn sub(self, rhs: u64) -> Self::Output {
+        self.checked_sub(rhs).expect("page underflow")
+    }
+}
+
+impl<S: PageSize> ops::SubAssign<u64> for Page<S> {
+    fn sub_assign(&mut self, rhs: u64) {
+        *self = *self - rhs;
+    }
+}
+
+impl<S: PageSize> ops::Sub<Page<S>> for Page<S> {
+    type Output = u64;
+
+    /// Returns the number of pages between `rhs` and `self`.
+    ///
+    /// # Panics
+    /// Panics if `rhs` is greater than `self`.
+    fn sub(self, rhs: Page<S>) -> Self::Output {
+        (self.start - rhs.start) / S::SIZE
+    }
+}
+
+/// A range of [`Page`]s, excluding the [`Page`] at the end.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PageRange<S: PageSize = Size4KiB> {
+    /// The first [`Page`] of the range.
+    pub start: Page<S>,
+    /// The [`Page`] following the last [`Page`] of the range.
+    pub end: Page<S>,
+}
+
+impl<S: PageSize> PageRange<S> {
+    /// Returns `true` if this [`PageRange`] contains no [`Page`]s.
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// Returns the number of [`Page`]s in this [`PageRange`].
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        self.end - self.start
+    }
+}
+
+impl<S: PageSize> Iterator for PageRange<S> {
+    type Item = Page<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let page = self.start;
+        self.start = page + 1;
+        Some(page)
+    }
+}
+
+/// A range of [`Page`]s, including the [`Page`] at the end.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PageRangeInclusive<S: PageSize = Size4KiB> {
+    /// The first [`Page`] of the range.
+    start: Page<S>,
+    /// The last [`Page`] of the range.
+    end: Page<S>,
+    /// Whether the last [`Page`] of the range has been yielded.
+    exhausted: bool,
+}
+
+impl<S: PageSize> PageRangeInclusive<S> {
+    /// Returns the first [`Page`] that has not been yielded yet.
+    pub const fn start(&self) -> Page<S> {
+        self.start
+    }
+
+    /// Returns the last [`Page`] of this [`PageRangeInclusive`].
+    pub const fn end(&self) -> Page<S> {
+        self.end
+    }
+
+    /// Returns `true` if this [`PageRangeInclusive`] contains no [`Page`]s.
+    pub fn is_empty(&self) -> bool {
+        self.exhausted || self.start > self.end
+    }
+
+    /// Returns the number of [`Page`]s in this [`PageRangeInclusive`].
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        self.end - self.start + 1
+    }
+}
+
+impl<S: PageSize> Iterator for PageRangeInclusive<S> {
+    type Item = Page<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let page = self.start;
+        match page.checked_add(1) {
+            Some(next) if page < self.end => self.start = next,
+            _ => self.exhausted = true,
+        }
+        Some(page)
+    }
+}
+
+/// A frame of physical memory of [`PageSize`] `S`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PhysFrame<S: PageSize = Size4KiB> {
+    /// The first address of the [`PhysFrame`].
+    start: PhysAddr,
+    /// Phantom data used to ensure type safety.
+    phantom: PhantomData<S>,
+}
+
+impl<S: PageSize> PhysFrame<S> {
+    /// The size of the [`PhysFrame`] in bytes.
+    pub const SIZE: u64 = S::SIZE;
+
+    /// Returns the [`PhysFrame`] starting at `address`.
+    ///
+    /// # Errors
+    /// Returns `address` if it is not aligned to the size of the [`PhysFrame`].
+    pub const fn from_start_address(address: PhysAddr) -> Result<Self, PhysAddr> {
+        if !address.is_aligned(S::SIZE) {
+            return Err(address);
+        }
+
+        Ok(Self {
+            start: address,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns the [`PhysFrame`] that contains `address`.
+    pub const fn containing_address(address: PhysAddr) -> Self {
+        Self {
+            start: address.align_down(S::SIZE),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the first address of this [`PhysFrame`].
+    pub const fn start_address(self) -> PhysAddr {
+        self.start
+    }
+
+    /// Returns the size of this [`PhysFrame`] in bytes.
+    pub const fn size(self) -> u64 {
+        S::SIZE
+    }
+
+    /// Returns a [`PhysFrameRange`] of the frames from `start` up to, but excluding, `end`.
+    pub const fn range(start: Self, end: Self) -> PhysFrameRange<S> {
+        PhysFrameRange { start, end }
+    }
+
+    /// Returns a [`PhysFrameRangeInclusive`] of the frames from `start` up to, and including,
+    /// `end`.
+    pub const fn range_inclusive(start: Self, end: Self) -> PhysFrameRangeInclusive<S> {
+        PhysFrameRangeInclusive {
+            start,
+            end,
+            exhausted: false,
+        }
+    }
+
+    /// Returns the [`PhysFrame`] `count` frames after this [`PhysFrame`].
+    ///
+    /// Returns [`None`] if the resulting [`PhysFrame`] does not start below 2<sup>52</sup>.
+    pub const fn checked_add(self, count: u64) -> Option<Self> {
+        let Some(offset) = count.checked_mul(S::SIZE) else {
+            return None;
+        };
+
+        match self.start.checked_add(offset) {
+            Some(start) => Some(Self {
+                start,
+                phantom: PhantomData,
+            }),
+            None => None,
+        }
+    }
+
+    /// Returns the [`PhysFrame`] `count` frames before this [`PhysFrame`].
+    ///
+    /// Returns [`None`] if the result underflows.
+    pub const fn checked_sub(self, count: u64) -> Option<Self> {
+        let Some(offset) = count.checked_mul(S::SIZE) else {
+            return None;
+        };
+
+        match self.start.checked_sub(offset) {
+            Some(start) => Some(Self {
+                start,
+                phantom: PhantomData,
+            }),
+            None => None,
+        }
+    }
+}
+
+impl<S: PageSize> ops::Add<u64> for PhysFrame<S> {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the resulting [`PhysFrame`] does not start below 2<sup>52</sup>.
+    fn add(self, rhs: u64) -> Self::Output {
+        self.checked_add(rhs).expect("frame overflow")
+    }
+}
+
+impl<S: PageSize> ops::AddAssign<u64> for PhysFrame<S> {
+    fn add_assign(&mut self, rhs: u64) {
+        *self = *self + rhs;
+    }
+}
+
+impl<S: PageSize> ops::Sub<u64> for PhysFrame<S> {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the result underflows.
+    fn sub(self, rhs: u64) -> Self::Output {
+        self.checked_sub(rhs).expect("frame underflow")
+    }
+}
+
+impl<S: PageSize> ops::SubAssign<u64> for PhysFrame<S> {
+    fn sub_assign(&mut self, rhs: u64) {
+        *self = *self - rhs;
+    }
+}
+
+impl<S: PageSize> ops::Sub<PhysFrame<S>> for PhysFrame<S> {
+    type Output = u64;
+
+    /// Returns the number of frames between `rhs` and `self`.
+    ///
+    /// # Panics
+    /// Panics if `rhs` is greater than `self`.
+    fn sub(self, rhs: PhysFrame<S>) -> Self::Output {
+        (self.start - rhs.start) / S::SIZE
+    }
+}
+
+/// A range of [`PhysFrame`]s, excluding the [`PhysFrame`] at the end.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PhysFrameRange<S: PageSize = Size4KiB> {
+    /// The first [`PhysFrame`] of the range.
+    pub start: PhysFrame<S>,
+    /// The [`PhysFrame`] following the last [`PhysFrame`] of the range.
+    pub end: PhysFrame<S>,
+}
+
+impl<S: PageSize> PhysFrameRange<S> {
+    /// Returns `true` if this [`PhysFrameRange`] contains no [`PhysFrame`]s.
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// Returns the number of [`PhysFrame`]s in this [`PhysFrameRange`].
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        self.end - self.start
+    }
+}
+
+impl<S: PageSize> Iterator for PhysFrameRange<S> {
+    type Item = PhysFrame<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let frame = self.start;
+        self.start = frame + 1;
+        Some(frame)
+    }
+}
+
+/// A range of [`PhysFrame`]s, including the [`PhysFrame`] at the end.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PhysFrameRangeInclusive<S: PageSize = Size4KiB> {
+    /// The first [`PhysFrame`] of the range.
+    start: PhysFrame<S>,
+    /// The last [`PhysFrame`] of the range.
+    end: PhysFrame<S>,
+    /// Whether the last [`PhysFrame`] of the range has been yielded.
+    exhausted: bool,
+}
+
+impl<S: PageSize> PhysFrameRangeInclusive<S> {
+    /// Returns the first [`PhysFrame`] that has not been yielded yet.
+    pub const fn start(&self) -> PhysFrame<S> {
+        self.start
+    }
+
+    /// Returns the last [`PhysFrame`] of this [`PhysFrameRangeInclusive`].
+    pub const fn end(&self) -> PhysFrame<S> {
+        self.end
+    }
+
+    /// Returns `true` if this [`PhysFrameRangeInclusive`] contains no [`PhysFrame`]s.
+    pub fn is_empty(&self) -> bool {
+        self.exhausted || self.start > self.end
+    }
+
+    /// Returns the number of [`PhysFrame`]s in this [`PhysFrameRangeInclusive`].
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        self.end - self.start + 1
+    }
+}
+
+impl<S: PageSize> Iterator for PhysFrameRangeInclusive<S> {
+    type Item = PhysFrame<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let frame = self.start;
+        match frame.checked_add(1) {
+            Some(next) if frame < self.end => self.start = next,
+            _ => self.exhausted = true,
+        }
+        Some(frame)
+    }
+}
+
+mod private {
+    //! Module used to seal [`PageSize`].
+
+    use crate::structures::paging::bits64::page::{Size1GiB, Size2MiB, Size4KiB};
+
+    /// Marker trait used to seal [`PageSize`].
+    pub trait Sealed {}
+
+    impl Sealed for Size4KiB {}
+    impl Sealed for Size2MiB {}
+    impl Sealed for Size1GiB {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Page, PhysFrame, Size1GiB, Size2MiB, Size4KiB};
+    use crate::addr::{PhysAddr, VirtAddr};
+
+    #[test]
+    fn page() {
+        let address = VirtAddr::new(0xFFFF_8000_0034_5678).unwrap();
+
+        let page = Page::<Size2MiB>::containing_address(address);
+        assert_eq!(page.start_address().as_u64(), 0xFFFF_8000_0020_0000);
+        assert_eq!(Page::<Size2MiB>::from_start_address(address), Err(address));
+        assert_eq!(
+            Page::<Size2MiB>::from_start_address(page.start_address()),
+            Ok(page)
+        );
+
+        let next = page + 3;
+        assert_eq!(next.start_address().as_u64(), 0xFFFF_8000_0080_0000);
+        assert_eq!(next - page, 3);
+        assert_eq!(next - 3, page);
+
+        let page = Page::<Size1GiB>::containing_address(VirtAddr::new_truncate_la57(u64::MAX));
+        assert!(page.checked_add(1).is_none());
+    }
+
+    #[test]
+    fn ranges() {
+        let start = Page::<Size4KiB>::containing_address(VirtAddr::new(0x1000).unwrap());
+        let range = Page::range(start, start + 4);
+        assert_eq!(range.len(), 4);
+        assert_eq!(range.last(), Some(start + 3));
+
+        let range = Page::range_inclusive(start, start + 4);
+        assert_eq!(range.len(), 5);
+        assert_eq!(range.count(), 5);
+        assert!(Page::range(start + 1, start).is_empty());
+
+        let last = Page::<Size4KiB>::containing_address(VirtAddr::new_truncate_la57(u64::MAX));
+        let mut range = Page::range_inclusive(last - 1, last);
+        assert_eq!(range.next(), Some(last - 1));
+        assert_eq!(range.next(), Some(last));
+        assert_eq!(range.next(), None);
+
+        let start = PhysFrame::<Size2MiB>::containing_address(PhysAddr::new(0x1F_FFFF).unwrap());
+        let frames = PhysFrame::range(start, start + 2)
+            .map(|frame| frame.start_address().as_u64())
+            .collect::<Vec<_>>();
+        assert_eq!(frames, [0, 0x20_0000]);
+        assert_eq!(PhysFrame::range_inclusive(start + 2, start + 2).len(), 1);
+    }
+}
@@ -0,0 +1,111 @@
+//! Utilities used to test the 4-level and 5-level paging abstractions on the host.
+
+use crate::{
+    addr::{PhysAddr, VirtAddr},
+    structures::paging::bits64::{
+        mapper::{FrameAllocator, PageTableAccessMut},
+        translate::PageTableAccess,
+        PageMapLevel, PageTable, Pml1e,
+    },
+};
+
+/// Creates a [`VirtAddr`] from a 57-bit canonical `address`.
+pub fn virt(address: u64) -> VirtAddr {
+    VirtAddr::new_la57(address).unwrap()
+}
+
+/// Creates a [`PhysAddr`] from `address`.
+pub fn phys(address: u64) -> PhysAddr {
+    PhysAddr::new(address).unwrap()
+}
+
+/// An in-memory image of physical memory made up of [`PageTable`]s.
+///
+/// The [`PageTable`] at index `n` is located at the physical address `n * 0x1000`. Memory grows
+/// as [`PageTable`]s are accessed mutably.
+pub struct Memory(Vec<PageTable<Pml1e>>);
+
+impl Memory {
+    /// Creates an empty [`Memory`].
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Allocates a new zeroed [`PageTable`], returning its physical address.
+    pub fn allocate(&mut self) -> PhysAddr {
+        self.0.push(PageTable::new());
+        PhysAddr::new(((self.0.len() - 1) * 0x1000) as u64).unwrap()
+    }
+}
+
+impl PageTableAccess for Memory {
+    fn table<L: PageMapLevel>(&self, frame: PhysAddr, _: VirtAddr) -> Option<&PageTable<L>> {
+        let table = self.0.get((frame.as_u64() / 0x1000) as usize)?;
+        // SAFETY:
+        // `PageTable` has the same layout regardless of its level.
+        Some(unsafe { &*core::ptr::from_ref(table).cast::<PageTable<L>>() })
+    }
+}
+
+impl PageTableAccessMut for Memory {
+    fn table_mut<L: PageMapLevel>(
+        &mut self,
+        frame: PhysAddr,
+        _: VirtAddr,
+    ) -> Option<&mut PageTable<L>> {
+        let index = (frame.as_u64() / 0x1000) as usize;
+        if index >= self.0.len() {
+            self.0.resize_with(index + 1, PageTable::new);
+        }
+
+        let table = &mut self.0[index];
+        // SAFETY:
+        // `PageTable` has the same layout regardless of its level.
+        Some(unsafe { &mut *core::ptr::from_mut(table).cast::<PageTable<L>>() })
+    }
+}
+
+/// A [`FrameAllocator`] handing out a fixed number of consecutive frames.
+///
+/// Deallocated frames are handed out again before any new frame.
+pub struct Frames {
+    /// The physical address of the next frame.
+    next: PhysAddr,
+    /// The number of frames left.
+    remaining: usize,
+    /// The frames that have been deallocated.
+    free: Vec<PhysAddr>,
+}
+
+impl Frames {
+    /// Creates a new [`Frames`] handing out `count` frames starting at the physical address
+    /// `start`.
+    pub fn new(start: PhysAddr, count: usize) -> Self {
+        Self {
+            next: start,
+            remaining: count,
+            free: Vec::new(),
+        }
+    }
+
+    /// Returns the frames that have been deallocated and not handed out again.
+    pub fn free(&self) -> &[PhysAddr] {
+        &self.free
+    }
+}
+
+impl FrameAllocator for Frames {
+    fn allocate_frame(&mut self) -> Option<PhysAddr> {
+        if let Some(frame) = self.free.pop() {
+            return Some(frame);
+        }
+
+        self.remaining = self.remaining.checked_sub(1)?;
+        self.next += 0x1000;
+        Some(self.next - 0x1000)
+    }
+
+    unsafe fn deallocate_frame(&mut self, frame: PhysAddr) {
+        self.free.push(frame);
+    }
+}
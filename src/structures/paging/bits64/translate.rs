@@ -0,0 +1,489 @@
+//! Definitions and interfaces to translate virtual addresses using 4-level and 5-level paging
+//! structures.
+
+use crate::{
+    addr::{PhysAddr, VirtAddr},
+    structures::paging::bits64::{
+        Branch, BranchSupport, LeafSupport, PageMapEntry, PageMapLevel, PageTable, Pml1e, Pml2e,
+        Pml3e, Pml4e, Pml5e,
+    },
+};
+
+/// Provides access to the [`PageTable`]s that make up a paging hierarchy.
+///
+/// This allows the paging hierarchy to be walked regardless of how physical memory is reachable,
+/// whether through an identity map, a direct map, or an in-memory image of physical memory.
+pub trait PageTableAccess {
+    /// Returns a reference to the [`PageTable`] located at the physical address `frame`, used to
+    /// translate `address`.
+    ///
+    /// `address` identifies the [`PageTable`] for strategies that reach [`PageTable`]s through
+    /// virtual addresses derived from the address being translated, such as recursive mappings.
+    ///
+    /// Returns [`None`] if the [`PageTable`] cannot be accessed.
+    fn table<L: PageMapLevel>(&self, frame: PhysAddr, address: VirtAddr) -> Option<&PageTable<L>>;
+}
+
+/// The result of successfully translating a virtual address.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Translation {
+    /// The physical address that the virtual address translates to.
+    pub address: PhysAddr,
+    /// The size of the page that maps the virtual address.
+    pub size: PageSizeKind,
+    /// The effective [`MappingFlags`] of the page that maps the virtual address.
+    pub flags: MappingFlags,
+}
+
+/// The sizes of pages supported by 4-level and 5-level paging.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PageSizeKind {
+    /// A 4 KiB page mapped by a [`PageMapEntry<Pml1e, Leaf>`][leaf].
+    ///
+    /// [leaf]: crate::structures::paging::bits64::Leaf
+    Size4KiB,
+    /// A 2 MiB page mapped by a [`PageMapEntry<Pml2e, Leaf>`][leaf].
+    ///
+    /// [leaf]: crate::structures::paging::bits64::Leaf
+    Size2MiB,
+    /// A 1 GiB page mapped by a [`PageMapEntry<Pml3e, Leaf>`][leaf].
+    ///
+    /// [leaf]: crate::structures::paging::bits64::Leaf
+    Size1GiB,
+}
+
+impl PageSizeKind {
+    /// Returns the size of the page in bytes.
+    pub const fn size(self) -> u64 {
+        match self {
+            Self::Size4KiB => 0x1000,
+            Self::Size2MiB => 0x20_0000,
+            Self::Size1GiB => 0x4000_0000,
+        }
+    }
+}
+
+/// The effective attributes of a mapped page.
+///
+/// The access rights of a page are determined by every [`PageMapEntry`] used to translate it:
+/// a page is only writable or accessible to userspace if every [`PageMapEntry`] allows it, and
+/// a page is not executable if any [`PageMapEntry`] disallows it.
+///
+/// [`MappingFlags`] are also used to request the attributes of a page when mapping it.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct MappingFlags(u64);
+
+#[allow(clippy::missing_docs_in_private_items)]
+impl MappingFlags {
+    const WRITABLE_BIT: u64 = 1 << 1;
+    const USER_BIT: u64 = 1 << 2;
+    const WRITE_THROUGH_BIT: u64 = 1 << 3;
+    const CACHE_DISABLE_BIT: u64 = 1 << 4;
+    const ACCESSED_BIT: u64 = 1 << 5;
+    const DIRTY_BIT: u64 = 1 << 6;
+    const PAT_BIT: u64 = 1 << 7;
+    const GLOBAL_BIT: u64 = 1 << 8;
+    const NO_EXECUTE_BIT: u64 = 1 << 63;
+
+    const LEAF_MASK: u64 = Self::WRITE_THROUGH_BIT
+        | Self::CACHE_DISABLE_BIT
+        | Self::ACCESSED_BIT
+        | Self::DIRTY_BIT
+        | Self::GLOBAL_BIT;
+}
+
+impl MappingFlags {
+    /// The [`MappingFlags`] before any [`PageMapEntry`] has restricted access.
+    pub(super) const UNRESTRICTED: Self = Self(Self::WRITABLE_BIT | Self::USER_BIT);
+
+    /// Restricts these [`MappingFlags`] by the access rights of `entry`.
+    pub(super) const fn restrict<L: PageMapLevel, S: super::PageMapEntryPresent>(
+        self,
+        entry: PageMapEntry<L, S>,
+    ) -> Self {
+        let value = entry.value;
+        let rights = Self::WRITABLE_BIT | Self::USER_BIT;
+
+        Self((self.0 & (value | !rights)) | (value & Self::NO_EXECUTE_BIT))
+    }
+
+    /// Returns the effective [`MappingFlags`] of the page mapped by `leaf`.
+    pub(super) const fn leaf<L: LeafSupport>(self, leaf: PageMapEntry<L, super::Leaf>) -> Self {
+        let restricted = self.restrict(leaf);
+        let pat = (leaf.pat() as u64) << 7;
+
+        Self(restricted.0 | (leaf.value & Self::LEAF_MASK) | pat)
+    }
+
+    /// Returns these [`MappingFlags`] without the accessed and dirty status of the page.
+    pub(super) const fn attributes(self) -> Self {
+        Self(self.0 & !(Self::ACCESSED_BIT | Self::DIRTY_BIT))
+    }
+
+    /// Creates a new [`MappingFlags`] describing a read-only, supervisor-only, executable page.
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Returns `true` if the page is writable.
+    pub const fn writable(self) -> bool {
+        self.0 & Self::WRITABLE_BIT == Self::WRITABLE_BIT
+    }
+
+    /// Sets whether the page is writable.
+    pub const fn set_writable(mut self, writable: bool) -> Self {
+        self.0 = (self.0 & !Self::WRITABLE_BIT) | ((writable as u64) << 1);
+        self
+    }
+
+    /// Returns `true` if the page is accessible to userspace.
+    pub const fn user(self) -> bool {
+        self.0 & Self::USER_BIT == Self::USER_BIT
+    }
+
+    /// Sets whether the page is accessible to userspace.
+    pub const fn set_user(mut self, user: bool) -> Self {
+        self.0 = (self.0 & !Self::USER_BIT) | ((user as u64) << 2);
+        self
+    }
+
+    /// Returns `true` if the write through bit of the leaf [`PageMapEntry`] is set.
+    pub const fn write_through(self) -> bool {
+        self.0 & Self::WRITE_THROUGH_BIT == Self::WRITE_THROUGH_BIT
+    }
+
+    /// Sets the write through bit of the leaf [`PageMapEntry`].
+    pub const fn set_write_through(mut self, write_through: bool) -> Self {
+        self.0 = (self.0 & !Self::WRITE_THROUGH_BIT) | ((write_through as u64) << 3);
+        self
+    }
+
+    /// Returns `true` if the cache disable bit of the leaf [`PageMapEntry`] is set.
+    pub const fn cache_disable(self) -> bool {
+        self.0 & Self::CACHE_DISABLE_BIT == Self::CACHE_DISABLE_BIT
+    }
+
+    /// Sets the cache disable bit of the leaf [`PageMapEntry`].
+    pub const fn set_cache_disable(mut self, cache_disable: bool) -> Self {
+        self.0 = (self.0 & !Self::CACHE_DISABLE_BIT) | ((cache_disable as u64) << 4);
+        self
+    }
+
+    /// Returns `true` if the page has been accessed.
+    pub const fn accessed(self) -> bool {
+        self.0 & Self::ACCESSED_BIT == Self::ACCESSED_BIT
+    }
+
+    /// Returns `true` if the page has been written to.
+    pub const fn dirty(self) -> bool {
+        self.0 & Self::DIRTY_BIT == Self::DIRTY_BIT
+    }
+
+    /// Returns `true` if the PAT bit of the leaf [`PageMapEntry`] is set.
+    pub const fn pat(self) -> bool {
+        self.0 & Self::PAT_BIT == Self::PAT_BIT
+    }
+
+    /// Sets the PAT bit of the leaf [`PageMapEntry`].
+    pub const fn set_pat(mut self, pat: bool) -> Self {
+        self.0 = (self.0 & !Self::PAT_BIT) | ((pat as u64) << 7);
+        self
+    }
+
+    /// Returns `true` if the page is translated globally.
+    pub const fn global(self) -> bool {
+        self.0 & Self::GLOBAL_BIT == Self::GLOBAL_BIT
+    }
+
+    /// Sets whether the page is translated globally.
+    pub const fn set_global(mut self, global: bool) -> Self {
+        self.0 = (self.0 & !Self::GLOBAL_BIT) | ((global as u64) << 8);
+        self
+    }
+
+    /// Returns `true` if the page cannot be executed.
+    pub const fn no_execute(self) -> bool {
+        self.0 & Self::NO_EXECUTE_BIT == Self::NO_EXECUTE_BIT
+    }
+
+    /// Sets whether the page cannot be executed.
+    pub const fn set_no_execute(mut self, no_execute: bool) -> Self {
+        self.0 = (self.0 & !Self::NO_EXECUTE_BIT) | ((no_execute as u64) << 63);
+        self
+    }
+}
+
+impl Default for MappingFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Errors that can occur when translating a virtual address.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TranslateError {
+    /// The virtual address is not canonical with respect to the paging mode in use.
+    NonCanonical,
+    /// The virtual address is not mapped.
+    NotMapped,
+    /// The [`PageTable`] located at the given physical address could not be accessed.
+    InaccessibleTable(PhysAddr),
+}
+
+impl core::fmt::Display for TranslateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NonCanonical => write!(f, "virtual address is not canonical"),
+            Self::NotMapped => write!(f, "virtual address is not mapped"),
+            Self::InaccessibleTable(frame) => {
+                write!(f, "page table at {frame:#x} could not be accessed")
+            }
+        }
+    }
+}
+
+impl PageTable<Pml5e> {
+    /// Translates `address` using 5-level paging with this [`PageTable`] as the root.
+    ///
+    /// # Errors
+    /// - [`TranslateError::NotMapped`]: `address` is not mapped.
+    /// - [`TranslateError::InaccessibleTable`]: `access` could not provide a [`PageTable`].
+    pub fn translate<A: PageTableAccess>(
+        &self,
+        access: &A,
+        address: VirtAddr,
+    ) -> Result<Translation, TranslateError> {
+        let entry = self.0[address.index::<Pml5e>()]
+            .present()
+            .ok_or(TranslateError::NotMapped)?
+            .branch();
+
+        next_table(access, entry, address)?.walk(
+            access,
+            address,
+            MappingFlags::UNRESTRICTED.restrict(entry),
+        )
+    }
+}
+
+impl PageTable<Pml4e> {
+    /// Translates `address` using 4-level paging with this [`PageTable`] as the root.
+    ///
+    /// # Errors
+    /// - [`TranslateError::NonCanonical`]: `address` is not a canonical 48-bit address.
+    /// - [`TranslateError::NotMapped`]: `address` is not mapped.
+    /// - [`TranslateError::InaccessibleTable`]: `access` could not provide a [`PageTable`].
+    pub fn translate<A: PageTableAccess>(
+        &self,
+        access: &A,
+        address: VirtAddr,
+    ) -> Result<Translation, TranslateError> {
+        if !address.is_canonical_48() {
+            return Err(TranslateError::NonCanonical);
+        }
+
+        self.walk(access, address, MappingFlags::UNRESTRICTED)
+    }
+
+    /// Translates `address`, starting at this [`PageTable`] with the accumulated `flags`.
+    fn walk<A: PageTableAccess>(
+        &self,
+        access: &A,
+        address: VirtAddr,
+        flags: MappingFlags,
+    ) -> Result<Translation, TranslateError> {
+        let pml4e = self.0[address.index::<Pml4e>()]
+            .present()
+            .ok_or(TranslateError::NotMapped)?
+            .branch();
+        let flags = flags.restrict(pml4e);
+
+        let pml3e = next_table(access, pml4e, address)?.0[address.index::<Pml3e>()]
+            .present()
+            .ok_or(TranslateError::NotMapped)?;
+        if let Some(leaf) = pml3e.leaf_opt() {
+            return Ok(Translation {
+                address: leaf.frame() + (address.as_u64() & (PageSizeKind::Size1GiB.size() - 1)),
+                size: PageSizeKind::Size1GiB,
+                flags: flags.leaf(leaf),
+            });
+        }
+        let pml3e = pml3e.branch_opt().ok_or(TranslateError::NotMapped)?;
+        let flags = flags.restrict(pml3e);
+
+        let pml2e = next_table(access, pml3e, address)?.0[address.index::<Pml2e>()]
+            .present()
+            .ok_or(TranslateError::NotMapped)?;
+        if let Some(leaf) = pml2e.leaf_opt() {
+            return Ok(Translation {
+                address: leaf.frame() + (address.as_u64() & (PageSizeKind::Size2MiB.size() - 1)),
+                size: PageSizeKind::Size2MiB,
+                flags: flags.leaf(leaf),
+            });
+        }
+        let pml2e = pml2e.branch_opt().ok_or(TranslateError::NotMapped)?;
+        let flags = flags.restrict(pml2e);
+
+        let leaf = next_table(access, pml2e, address)?.0[address.index::<Pml1e>()]
+            .present()
+            .ok_or(TranslateError::NotMapped)?
+            .leaf();
+
+        Ok(Translation {
+            address: leaf.frame() + (address.as_u64() & (PageSizeKind::Size4KiB.size() - 1)),
+            size: PageSizeKind::Size4KiB,
+            flags: flags.leaf(leaf),
+        })
+    }
+}
+
+/// Returns the [`PageTable`] referenced by `entry`, used to translate `address`.
+fn next_table<L: BranchSupport, A: PageTableAccess>(
+    access: &A,
+    entry: PageMapEntry<L, Branch>,
+    address: VirtAddr,
+) -> Result<&PageTable<L::Child>, TranslateError> {
+    access
+        .table(entry.frame(), address)
+        .ok_or(TranslateError::InaccessibleTable(entry.frame()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        addr::{PhysAddr, VirtAddr},
+        structures::paging::bits64::{
+            mapper::PageTableAccessMut,
+            testing::{phys, virt, Memory},
+            translate::{PageSizeKind, PageTableAccess, TranslateError},
+            Pml1e, Pml2e, Pml3e, Pml4e, Pml5e,
+        },
+    };
+
+    /// Builds a hierarchy mapping a 4 KiB, a 2 MiB, and a 1 GiB page, returning the memory and
+    /// the physical address of the [`PageTable<Pml4e>`].
+    fn hierarchy() -> (Memory, PhysAddr) {
+        let mut memory = Memory::new();
+        let pml4 = memory.allocate();
+        let pml3 = memory.allocate();
+        let pml2 = memory.allocate();
+        let pml1 = memory.allocate();
+
+        let pml4_table = memory.table_mut::<Pml4e>(pml4, VirtAddr::zero()).unwrap();
+        let entry = pml4_table
+            .get(0)
+            .unwrap()
+            .set_present()
+            .set_writable(true)
+            .set_user(true)
+            .set_branch(pml3);
+        pml4_table.set(0, entry.unclassified()).unwrap();
+
+        let pml3_table = memory.table_mut::<Pml3e>(pml3, VirtAddr::zero()).unwrap();
+        let entry = pml3_table
+            .get(0)
+            .unwrap()
+            .set_present()
+            .set_writable(true)
+            .set_branch(pml2);
+        pml3_table.set(0, entry.unclassified()).unwrap();
+        let entry = pml3_table
+            .get(1)
+            .unwrap()
+            .set_present()
+            .set_user(true)
+            .set_leaf(phys(0x1_4000_0000))
+            .set_global(true);
+        pml3_table.set(1, entry.unclassified()).unwrap();
+
+        let pml2_table = memory.table_mut::<Pml2e>(pml2, VirtAddr::zero()).unwrap();
+        let entry = pml2_table
+            .get(0)
+            .unwrap()
+            .set_present()
+            .set_writable(true)
+            .set_branch(pml1);
+        pml2_table.set(0, entry.unclassified()).unwrap();
+        let entry = pml2_table
+            .get(1)
+            .unwrap()
+            .set_present()
+            .set_no_execute(true)
+            .set_leaf(phys(0x80_0000))
+            .set_pat(true);
+        pml2_table.set(1, entry.unclassified()).unwrap();
+
+        let pml1_table = memory.table_mut::<Pml1e>(pml1, VirtAddr::zero()).unwrap();
+        let entry = pml1_table
+            .get(5)
+            .unwrap()
+            .set_present()
+            .set_writable(true)
+            .set_leaf(phys(0xABC_D000))
+            .set_dirty(true);
+        pml1_table.set(5, entry.unclassified()).unwrap();
+
+        (memory, pml4)
+    }
+
+    #[test]
+    fn translate_4level() {
+        let (memory, pml4) = hierarchy();
+        let root = memory.table::<Pml4e>(pml4, VirtAddr::zero()).unwrap();
+
+        let translation = root.translate(&memory, virt(0x5123)).unwrap();
+        assert_eq!(translation.address, phys(0xABC_D123));
+        assert_eq!(translation.size, PageSizeKind::Size4KiB);
+        assert!(translation.flags.writable());
+        assert!(!translation.flags.user());
+        assert!(translation.flags.dirty());
+
+        let translation = root.translate(&memory, virt(0x2F_FFFF)).unwrap();
+        assert_eq!(translation.address, phys(0x8F_FFFF));
+        assert_eq!(translation.size, PageSizeKind::Size2MiB);
+        assert!(!translation.flags.writable());
+        assert!(translation.flags.no_execute());
+        assert!(translation.flags.pat());
+
+        let translation = root.translate(&memory, virt(0x4765_4321)).unwrap();
+        assert_eq!(translation.address, phys(0x1_4765_4321));
+        assert_eq!(translation.size, PageSizeKind::Size1GiB);
+        assert!(translation.flags.user());
+        assert!(translation.flags.global());
+
+        assert_eq!(
+            root.translate(&memory, virt(0x6000)),
+            Err(TranslateError::NotMapped)
+        );
+        assert_eq!(
+            root.translate(&memory, virt(0x0000_8000_0000_0000)),
+            Err(TranslateError::NonCanonical)
+        );
+    }
+
+    #[test]
+    fn translate_5level() {
+        let (mut memory, pml4) = hierarchy();
+        let pml5 = memory.allocate();
+
+        let pml5_table = memory.table_mut::<Pml5e>(pml5, VirtAddr::zero()).unwrap();
+        let entry = pml5_table
+            .get(0)
+            .unwrap()
+            .set_present()
+            .set_writable(true)
+            .set_branch(pml4);
+        pml5_table.set(0, entry.unclassified()).unwrap();
+
+        let root = memory.table::<Pml5e>(pml5, VirtAddr::zero()).unwrap();
+        let translation = root.translate(&memory, virt(0x5123)).unwrap();
+        assert_eq!(translation.address, phys(0xABC_D123));
+        assert!(!translation.flags.user());
+
+        assert_eq!(
+            root.translate(&memory, virt(0x0001_0000_0000_0000)),
+            Err(TranslateError::NotMapped)
+        );
+    }
+}
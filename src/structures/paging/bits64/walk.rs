@@ -0,0 +1,838 @@
+//! Definitions and interfaces to walk every mapping of 4-level and 5-level paging structures.
+
+use core::fmt;
+
+use crate::{
+    addr::{PhysAddr, VirtAddr},
+    structures::paging::bits64::{
+        translate::{MappingFlags, PageSizeKind, PageTableAccess},
+        Branch, BranchSupport, Leaf, LeafSupport, PageMapEntry, PageMapLevel, PageTable, Pml1e,
+        Pml2e, Pml3e, Pml4e, Pml5e, Present,
+    },
+};
+
+/// Receives the [`PageMapEntry`]s of a paging hierarchy as it is walked in ascending order of
+/// virtual addresses.
+///
+/// Every method has a default implementation that does nothing, so that a [`Visitor`] only needs
+/// to implement the hooks it is interested in. `L` identifies the level of the visited
+/// [`PageMapEntry`].
+pub trait Visitor {
+    /// Called with a branch `entry` mapping the region starting at `address`, before the
+    /// [`PageTable`] it refers to is walked.
+    ///
+    /// Returns `false` to skip the [`PageTable`] referred to by `entry`.
+    fn enter<L: BranchSupport>(
+        &mut self,
+        _address: VirtAddr,
+        _entry: PageMapEntry<L, Branch>,
+    ) -> bool {
+        true
+    }
+
+    /// Called with a branch `entry` mapping the region starting at `address`, after the
+    /// [`PageTable`] it refers to has been walked.
+    ///
+    /// This is not called if [`Visitor::enter`] returned `false` for `entry`.
+    fn leave<L: BranchSupport>(&mut self, _address: VirtAddr, _entry: PageMapEntry<L, Branch>) {}
+
+    /// Called with a leaf `entry` mapping the page starting at `address` with the effective
+    /// `flags`.
+    fn leaf<L: LeafSupport>(
+        &mut self,
+        _address: VirtAddr,
+        _entry: PageMapEntry<L, Leaf>,
+        _flags: MappingFlags,
+    ) {
+    }
+}
+
+/// A region of virtual memory mapped to contiguous physical memory by pages of the same size
+/// and with the same attributes.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Region {
+    /// The virtual address of the start of the region.
+    pub start: VirtAddr,
+    /// The length of the region in bytes.
+    pub len: u64,
+    /// The physical address that the start of the region is mapped to.
+    pub frame: PhysAddr,
+    /// The size of the pages that map the region.
+    pub size: PageSizeKind,
+    /// The effective [`MappingFlags`] of the pages that map the region.
+    ///
+    /// The accessed and dirty status of the pages is not reported.
+    pub flags: MappingFlags,
+}
+
+impl Region {
+    /// Returns `true` if the page starting at `start` described by `mapping` directly follows
+    /// this [`Region`] and can be merged into it.
+    fn continued_by(&self, start: VirtAddr, mapping: &Mapping) -> bool {
+        self.size == mapping.size
+            && self.flags == mapping.flags.attributes()
+            && self.start.as_u64().wrapping_add(self.len) == start.as_u64()
+            && self.frame.as_u64() + self.len == mapping.frame.as_u64()
+    }
+}
+
+impl fmt::Display for Region {
+    /// Formats this [`Region`] as a single line, similar to Linux's `ptdump`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Formats `len` bytes using the largest unit that divides it.
+        fn human(f: &mut fmt::Formatter<'_>, mut len: u64) -> fmt::Result {
+            let mut units = ["K", "M", "G", "T", "P"].iter();
+            let mut unit = "B";
+            while len != 0 && len.is_multiple_of(1024) {
+                match units.next() {
+                    Some(next) => unit = next,
+                    None => break,
+                }
+                len /= 1024;
+            }
+            write!(f, "{len:>5}{unit}")
+        }
+
+        /// Returns `set` if `flag` is set, or padding of the same width otherwise.
+        const fn flag(flag: bool, set: &'static str, unset: &'static str) -> &'static str {
+            if flag {
+                set
+            } else {
+                unset
+            }
+        }
+
+        let flags = self.flags;
+        write!(
+            f,
+            "{:#018x}-{:#018x} ",
+            self.start,
+            self.start.as_u64().wrapping_add(self.len)
+        )?;
+        human(f, self.len)?;
+        write!(
+            f,
+            " -> {:#015x} {} {} {} {} {} {} {} ",
+            self.frame,
+            flag(flags.writable(), "RW ", "ro "),
+            flag(flags.user(), "USR", "   "),
+            flag(flags.write_through(), "PWT", "   "),
+            flag(flags.cache_disable(), "PCD", "   "),
+            flag(flags.pat(), "PAT", "   "),
+            flag(flags.global(), "GLB", "   "),
+            flag(flags.no_execute(), "NX", "x "),
+        )?;
+
+        match self.size {
+            PageSizeKind::Size4KiB => f.write_str("4K"),
+            PageSizeKind::Size2MiB => f.write_str("2M"),
+            PageSizeKind::Size1GiB => f.write_str("1G"),
+        }
+    }
+}
+
+/// An [`Iterator`] over the [`Region`]s mapped by a paging hierarchy, in ascending order of
+/// virtual addresses.
+///
+/// Yields `Err(frame)` and stops if the [`PageTable`] located at `frame` could not be accessed.
+pub struct Regions<'a, A: PageTableAccess> {
+    /// The root [`PageTable`] of the paging hierarchy.
+    root: Root<'a>,
+    /// Access to the [`PageTable`]s of the paging hierarchy.
+    access: &'a A,
+    /// The lowest virtual address that has not been walked yet, before sign extension.
+    ///
+    /// [`None`] if the whole virtual address space has been walked.
+    cursor: Option<u64>,
+}
+
+impl<A: PageTableAccess> Regions<'_, A> {
+    /// Moves the cursor past the region of `size` bytes containing `cursor`.
+    fn advance(&mut self, cursor: u64, size: u64) {
+        let next = (cursor & !(size - 1)) + size;
+        self.cursor = (next < self.root.limit()).then_some(next);
+    }
+}
+
+impl<A: PageTableAccess> Clone for Regions<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root,
+            access: self.access,
+            cursor: self.cursor,
+        }
+    }
+}
+
+impl<A: PageTableAccess> Iterator for Regions<'_, A> {
+    type Item = Result<Region, PhysAddr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut region: Option<Region> = None;
+
+        while let Some(cursor) = self.cursor {
+            let mapping = match self.root.probe(self.access, cursor) {
+                Ok(Probe::Mapped(mapping)) => mapping,
+                Ok(Probe::Unmapped(size)) => {
+                    self.advance(cursor, size);
+                    match region {
+                        Some(_) => break,
+                        None => continue,
+                    }
+                }
+                // Report the error on the next call, once the pending region has been yielded.
+                Err(_) if region.is_some() => break,
+                Err(frame) => {
+                    self.cursor = None;
+                    return Some(Err(frame));
+                }
+            };
+
+            let size = mapping.size.size();
+            let start = self.root.address(cursor & !(size - 1));
+            match &mut region {
+                Some(region) if region.continued_by(start, &mapping) => region.len += size,
+                Some(_) => break,
+                None => {
+                    region = Some(Region {
+                        start,
+                        len: size,
+                        frame: mapping.frame,
+                        size: mapping.size,
+                        flags: mapping.flags.attributes(),
+                    });
+                }
+            }
+            self.advance(cursor, size);
+        }
+
+        region.map(Ok)
+    }
+}
+
+/// Formats the [`Region`]s mapped by a paging hierarchy, one per line, similar to Linux's
+/// `ptdump`.
+pub struct Dump<'a, A: PageTableAccess> {
+    /// The [`Region`]s to format.
+    regions: Regions<'a, A>,
+}
+
+impl<A: PageTableAccess> fmt::Display for Dump<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for region in self.regions.clone() {
+            match region {
+                Ok(region) => writeln!(f, "{region}")?,
+                Err(frame) => writeln!(f, "page table at {frame:#x} could not be accessed")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PageTable<Pml5e> {
+    /// Returns an [`Iterator`] over the [`Region`]s mapped using 5-level paging with this
+    /// [`PageTable`] as the root.
+    pub fn regions<'a, A: PageTableAccess>(&'a self, access: &'a A) -> Regions<'a, A> {
+        Regions {
+            root: Root::Pml5(self),
+            access,
+            cursor: Some(0),
+        }
+    }
+
+    /// Returns a [`Dump`] of the [`Region`]s mapped using 5-level paging with this
+    /// [`PageTable`] as the root.
+    pub fn dump<'a, A: PageTableAccess>(&'a self, access: &'a A) -> Dump<'a, A> {
+        Dump {
+            regions: self.regions(access),
+        }
+    }
+
+    /// Walks every [`PageMapEntry`] used by 5-level paging with this [`PageTable`] as the root,
+    /// passing them to `visitor`.
+    ///
+    /// # Errors
+    /// Returns the physical address of a [`PageTable`] that `access` could not provide.
+    pub fn visit<A: PageTableAccess, V: Visitor>(
+        &self,
+        access: &A,
+        visitor: &mut V,
+    ) -> Result<(), PhysAddr> {
+        visit_table(
+            self,
+            &Context { access, la57: true },
+            visitor,
+            0,
+            MappingFlags::UNRESTRICTED,
+        )
+    }
+}
+
+impl PageTable<Pml4e> {
+    /// Returns an [`Iterator`] over the [`Region`]s mapped using 4-level paging with this
+    /// [`PageTable`] as the root.
+    pub fn regions<'a, A: PageTableAccess>(&'a self, access: &'a A) -> Regions<'a, A> {
+        Regions {
+            root: Root::Pml4(self),
+            access,
+            cursor: Some(0),
+        }
+    }
+
+    /// Returns a [`Dump`] of the [`Region`]s mapped using 4-level paging with this
+    /// [`PageTable`] as the root.
+    pub fn dump<'a, A: PageTableAccess>(&'a self, access: &'a A) -> Dump<'a, A> {
+        Dump {
+            regions: self.regions(access),
+        }
+    }
+
+    /// Walks every [`PageMapEntry`] used by 4-level paging with this [`PageTable`] as the root,
+    /// passing them to `visitor`.
+    ///
+    /// # Errors
+    /// Returns the physical address of a [`PageTable`] that `access` could not provide.
+    pub fn visit<A: PageTableAccess, V: Visitor>(
+        &self,
+        access: &A,
+        visitor: &mut V,
+    ) -> Result<(), PhysAddr> {
+        visit_table(
+            self,
+            &Context {
+                access,
+                la57: false,
+            },
+            visitor,
+            0,
+            MappingFlags::UNRESTRICTED,
+        )
+    }
+}
+
+/// The root [`PageTable`] of a paging hierarchy walked by [`Regions`].
+#[derive(Clone, Copy)]
+enum Root<'a> {
+    /// The root of a 4-level paging hierarchy.
+    Pml4(&'a PageTable<Pml4e>),
+    /// The root of a 5-level paging hierarchy.
+    Pml5(&'a PageTable<Pml5e>),
+}
+
+impl Root<'_> {
+    /// Returns the size of the virtual address space, before sign extension.
+    const fn limit(self) -> u64 {
+        match self {
+            Self::Pml4(_) => 1 << 48,
+            Self::Pml5(_) => 1 << 57,
+        }
+    }
+
+    /// Returns the [`VirtAddr`] of `address` before sign extension.
+    const fn address(self, address: u64) -> VirtAddr {
+        match self {
+            Self::Pml4(_) => VirtAddr::new_truncate(address),
+            Self::Pml5(_) => VirtAddr::new_truncate_la57(address),
+        }
+    }
+
+    /// Looks up the mapping of `address`, before sign extension.
+    fn probe<A: PageTableAccess>(self, access: &A, address: u64) -> Result<Probe, PhysAddr> {
+        match self {
+            Self::Pml4(table) => probe_table(
+                table,
+                &Context {
+                    access,
+                    la57: false,
+                },
+                address,
+                MappingFlags::UNRESTRICTED,
+            ),
+            Self::Pml5(table) => probe_table(
+                table,
+                &Context { access, la57: true },
+                address,
+                MappingFlags::UNRESTRICTED,
+            ),
+        }
+    }
+}
+
+/// The state shared by every step of a walk.
+struct Context<'a, A: PageTableAccess> {
+    /// Access to the [`PageTable`]s of the paging hierarchy.
+    access: &'a A,
+    /// Whether the paging hierarchy uses 5-level paging.
+    la57: bool,
+}
+
+impl<A: PageTableAccess> Context<'_, A> {
+    /// Returns the [`VirtAddr`] of `address` before sign extension.
+    const fn address(&self, address: u64) -> VirtAddr {
+        if self.la57 {
+            VirtAddr::new_truncate_la57(address)
+        } else {
+            VirtAddr::new_truncate(address)
+        }
+    }
+
+    /// Returns the [`PageTable`] referred to by `entry`, used to translate `address`.
+    fn next_table<L: BranchSupport>(
+        &self,
+        entry: PageMapEntry<L, Branch>,
+        address: VirtAddr,
+    ) -> Result<&PageTable<L::Child>, PhysAddr> {
+        self.access
+            .table(entry.frame(), address)
+            .ok_or(entry.frame())
+    }
+}
+
+/// A page mapped by a leaf [`PageMapEntry`].
+struct Mapping {
+    /// The physical address that the start of the page is mapped to.
+    frame: PhysAddr,
+    /// The size of the page.
+    size: PageSizeKind,
+    /// The effective [`MappingFlags`] of the page.
+    flags: MappingFlags,
+}
+
+/// The result of looking up the mapping of a virtual address.
+enum Probe {
+    /// The virtual address is mapped by a page.
+    Mapped(Mapping),
+    /// The virtual address is not mapped, nor is the rest of the aligned region of the given
+    /// size containing it.
+    Unmapped(u64),
+}
+
+/// A [`PageMapLevel`] whose present [`PageMapEntry`]s can be walked.
+trait Walk: PageMapLevel {
+    /// Walks the present `entry` mapping the region starting at `address`, before sign extension.
+    fn visit_entry<A: PageTableAccess, V: Visitor>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        visitor: &mut V,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<(), PhysAddr>;
+
+    /// Looks up the mapping of `address`, before sign extension, through the present `entry`.
+    fn probe_entry<A: PageTableAccess>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<Probe, PhysAddr>;
+}
+
+impl Walk for Pml5e {
+    fn visit_entry<A: PageTableAccess, V: Visitor>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        visitor: &mut V,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<(), PhysAddr> {
+        visit_branch(entry.branch(), context, visitor, address, flags)
+    }
+
+    fn probe_entry<A: PageTableAccess>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<Probe, PhysAddr> {
+        probe_branch(entry.branch(), context, address, flags)
+    }
+}
+
+impl Walk for Pml4e {
+    fn visit_entry<A: PageTableAccess, V: Visitor>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        visitor: &mut V,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<(), PhysAddr> {
+        visit_branch(entry.branch(), context, visitor, address, flags)
+    }
+
+    fn probe_entry<A: PageTableAccess>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<Probe, PhysAddr> {
+        probe_branch(entry.branch(), context, address, flags)
+    }
+}
+
+impl Walk for Pml3e {
+    fn visit_entry<A: PageTableAccess, V: Visitor>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        visitor: &mut V,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<(), PhysAddr> {
+        match (entry.leaf_opt(), entry.branch_opt()) {
+            (Some(leaf), _) => {
+                visitor.leaf(context.address(address), leaf, flags.leaf(leaf));
+                Ok(())
+            }
+            (None, Some(branch)) => visit_branch(branch, context, visitor, address, flags),
+            (None, None) => Ok(()),
+        }
+    }
+
+    fn probe_entry<A: PageTableAccess>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<Probe, PhysAddr> {
+        match (entry.leaf_opt(), entry.branch_opt()) {
+            (Some(leaf), _) => Ok(probe_leaf(leaf, PageSizeKind::Size1GiB, flags)),
+            (None, Some(branch)) => probe_branch(branch, context, address, flags),
+            (None, None) => Ok(Probe::Unmapped(PageSizeKind::Size1GiB.size())),
+        }
+    }
+}
+
+impl Walk for Pml2e {
+    fn visit_entry<A: PageTableAccess, V: Visitor>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        visitor: &mut V,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<(), PhysAddr> {
+        match (entry.leaf_opt(), entry.branch_opt()) {
+            (Some(leaf), _) => {
+                visitor.leaf(context.address(address), leaf, flags.leaf(leaf));
+                Ok(())
+            }
+            (None, Some(branch)) => visit_branch(branch, context, visitor, address, flags),
+            (None, None) => Ok(()),
+        }
+    }
+
+    fn probe_entry<A: PageTableAccess>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<Probe, PhysAddr> {
+        match (entry.leaf_opt(), entry.branch_opt()) {
+            (Some(leaf), _) => Ok(probe_leaf(leaf, PageSizeKind::Size2MiB, flags)),
+            (None, Some(branch)) => probe_branch(branch, context, address, flags),
+            (None, None) => Ok(Probe::Unmapped(PageSizeKind::Size2MiB.size())),
+        }
+    }
+}
+
+impl Walk for Pml1e {
+    fn visit_entry<A: PageTableAccess, V: Visitor>(
+        entry: PageMapEntry<Self, Present>,
+        context: &Context<'_, A>,
+        visitor: &mut V,
+        address: u64,
+        flags: MappingFlags,
+    ) -> Result<(), PhysAddr> {
+        let leaf = entry.leaf();
+        visitor.leaf(context.address(address), leaf, flags.leaf(leaf));
+        Ok(())
+    }
+
+    fn probe_entry<A: PageTableAccess>(
+        entry: PageMapEntry<Self, Present>,
+        _: &Context<'_, A>,
+        _: u64,
+        flags: MappingFlags,
+    ) -> Result<Probe, PhysAddr> {
+        Ok(probe_leaf(entry.leaf(), PageSizeKind::Size4KiB, flags))
+    }
+}
+
+/// Walks the present [`PageMapEntry`]s of `table`, which maps the region starting at `base`,
+/// before sign extension.
+fn visit_table<L: Walk, A: PageTableAccess, V: Visitor>(
+    table: &PageTable<L>,
+    context: &Context<'_, A>,
+    visitor: &mut V,
+    base: u64,
+    flags: MappingFlags,
+) -> Result<(), PhysAddr> {
+    for (index, entry) in table.0.iter().enumerate() {
+        if let Some(entry) = entry.present() {
+            let address = base | ((index as u64) << L::INDEX_SHIFT);
+            L::visit_entry(entry, context, visitor, address, flags)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Walks the [`PageTable`] referred to by the branch `entry`, which maps the region starting at
+/// `address`, before sign extension.
+fn visit_branch<L: BranchSupport, A: PageTableAccess, V: Visitor>(
+    entry: PageMapEntry<L, Branch>,
+    context: &Context<'_, A>,
+    visitor: &mut V,
+    address: u64,
+    flags: MappingFlags,
+) -> Result<(), PhysAddr>
+where
+    L::Child: Walk,
+{
+    let virt = context.address(address);
+    if visitor.enter(virt, entry) {
+        let table = context.next_table(entry, virt)?;
+        visit_table(table, context, visitor, address, flags.restrict(entry))?;
+        visitor.leave(virt, entry);
+    }
+
+    Ok(())
+}
+
+/// Looks up the mapping of `address`, before sign extension, in `table`.
+fn probe_table<L: Walk, A: PageTableAccess>(
+    table: &PageTable<L>,
+    context: &Context<'_, A>,
+    address: u64,
+    flags: MappingFlags,
+) -> Result<Probe, PhysAddr> {
+    match table.0[((address >> L::INDEX_SHIFT) & 0x1FF) as usize].present() {
+        Some(entry) => L::probe_entry(entry, context, address, flags),
+        None => Ok(Probe::Unmapped(1 << L::INDEX_SHIFT)),
+    }
+}
+
+/// Looks up the mapping of `address`, before sign extension, in the [`PageTable`] referred to
+/// by the branch `entry`.
+fn probe_branch<L: BranchSupport, A: PageTableAccess>(
+    entry: PageMapEntry<L, Branch>,
+    context: &Context<'_, A>,
+    address: u64,
+    flags: MappingFlags,
+) -> Result<Probe, PhysAddr>
+where
+    L::Child: Walk,
+{
+    let table = context.next_table(entry, context.address(address))?;
+    probe_table(table, context, address, flags.restrict(entry))
+}
+
+/// Returns the [`Probe`] of a page of `size` mapped by `leaf`.
+const fn probe_leaf<L: LeafSupport>(
+    leaf: PageMapEntry<L, Leaf>,
+    size: PageSizeKind,
+    flags: MappingFlags,
+) -> Probe {
+    Probe::Mapped(Mapping {
+        frame: leaf.frame(),
+        size,
+        flags: flags.leaf(leaf),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Region, Visitor};
+    use crate::{
+        addr::VirtAddr,
+        structures::paging::bits64::{
+            mapper::Mapper,
+            testing::{phys, virt, Frames, Memory},
+            translate::{MappingFlags, PageSizeKind, PageTableAccess},
+            Branch, BranchSupport, Leaf, LeafSupport, PageMapEntry, PageMapLevel, Pml4e,
+        },
+    };
+
+    /// Builds a [`Mapper`] for a hierarchy with a few mapped regions.
+    fn mapper() -> Mapper<Memory> {
+        let mut memory = Memory::new();
+        let root = memory.allocate();
+        let mut mapper = Mapper::new(memory, root);
+        let mut frames = Frames::new(phys(0x10_0000), 16);
+
+        let data = MappingFlags::new()
+            .set_writable(true)
+            .set_user(true)
+            .set_no_execute(true);
+        for page in 0..3 {
+            mapper
+                .map_4kib(
+                    virt(0x40_0000 + page * 0x1000),
+                    phys(0x80_0000 + page * 0x1000),
+                    data,
+                    &mut frames,
+                )
+                .unwrap()
+                .ignore();
+        }
+        mapper
+            .map_4kib(virt(0x40_3000), phys(0x90_0000), data, &mut frames)
+            .unwrap()
+            .ignore();
+        mapper
+            .map_2mib(
+                virt(0x4000_0000),
+                phys(0x20_0000),
+                MappingFlags::new(),
+                &mut frames,
+            )
+            .unwrap()
+            .ignore();
+        mapper
+            .map_4kib(
+                virt(0xFFFF_8000_0000_0000),
+                phys(0x1000),
+                MappingFlags::new().set_writable(true).set_global(true),
+                &mut frames,
+            )
+            .unwrap()
+            .ignore();
+
+        mapper
+    }
+
+    #[test]
+    fn regions() {
+        let mapper = mapper();
+        let root = mapper
+            .access()
+            .table::<Pml4e>(mapper.root(), VirtAddr::zero())
+            .unwrap();
+        let regions = root
+            .regions(mapper.access())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let data = MappingFlags::new()
+            .set_writable(true)
+            .set_user(true)
+            .set_no_execute(true);
+        assert_eq!(
+            regions,
+            [
+                Region {
+                    start: virt(0x40_0000),
+                    len: 0x3000,
+                    frame: phys(0x80_0000),
+                    size: PageSizeKind::Size4KiB,
+                    flags: data,
+                },
+                Region {
+                    start: virt(0x40_3000),
+                    len: 0x1000,
+                    frame: phys(0x90_0000),
+                    size: PageSizeKind::Size4KiB,
+                    flags: data,
+                },
+                Region {
+                    start: virt(0x4000_0000),
+                    len: 0x20_0000,
+                    frame: phys(0x20_0000),
+                    size: PageSizeKind::Size2MiB,
+                    flags: MappingFlags::new(),
+                },
+                Region {
+                    start: virt(0xFFFF_8000_0000_0000),
+                    len: 0x1000,
+                    frame: phys(0x1000),
+                    size: PageSizeKind::Size4KiB,
+                    flags: MappingFlags::new().set_writable(true).set_global(true),
+                },
+            ]
+        );
+
+        // No page is both writable and executable, except the global one.
+        let audit = regions
+            .iter()
+            .filter(|region| region.flags.writable() && !region.flags.no_execute())
+            .map(|region| region.start)
+            .collect::<Vec<_>>();
+        assert_eq!(audit, [virt(0xFFFF_8000_0000_0000)]);
+
+        assert_eq!(
+            root.dump(mapper.access()).to_string(),
+            format!(
+                "{}\n{}\n{}\n{}\n",
+                "0x0000000000400000-0x0000000000403000    12K -> 0x0000000800000 RW  USR                 NX 4K",
+                "0x0000000000403000-0x0000000000404000     4K -> 0x0000000900000 RW  USR                 NX 4K",
+                "0x0000000040000000-0x0000000040200000     2M -> 0x0000000200000 ro                      x  2M",
+                "0xffff800000000000-0xffff800000001000     4K -> 0x0000000001000 RW                  GLB x  4K",
+            )
+        );
+    }
+
+    #[test]
+    fn visitor() {
+        /// Records the hooks called during a walk.
+        #[derive(Default)]
+        struct Recorder(Vec<(char, u8, VirtAddr)>);
+
+        /// Returns the level number of `L`.
+        fn level<L: PageMapLevel>() -> u8 {
+            (L::INDEX_SHIFT - 12) / 9 + 1
+        }
+
+        impl Visitor for Recorder {
+            fn enter<L: BranchSupport>(
+                &mut self,
+                address: VirtAddr,
+                _: PageMapEntry<L, Branch>,
+            ) -> bool {
+                self.0.push(('>', level::<L>(), address));
+                // Skip the upper half of the address space.
+                address.as_u64() >> 63 == 0
+            }
+
+            fn leave<L: BranchSupport>(&mut self, address: VirtAddr, _: PageMapEntry<L, Branch>) {
+                self.0.push(('<', level::<L>(), address));
+            }
+
+            fn leaf<L: LeafSupport>(
+                &mut self,
+                address: VirtAddr,
+                _: PageMapEntry<L, Leaf>,
+                _: MappingFlags,
+            ) {
+                self.0.push(('.', level::<L>(), address));
+            }
+        }
+
+        let mapper = mapper();
+        let mut recorder = Recorder::default();
+        mapper
+            .access()
+            .table::<Pml4e>(mapper.root(), VirtAddr::zero())
+            .unwrap()
+            .visit(mapper.access(), &mut recorder)
+            .unwrap();
+
+        assert_eq!(
+            recorder.0,
+            [
+                ('>', 4, virt(0)),
+                ('>', 3, virt(0)),
+                ('>', 2, virt(0x40_0000)),
+                ('.', 1, virt(0x40_0000)),
+                ('.', 1, virt(0x40_1000)),
+                ('.', 1, virt(0x40_2000)),
+                ('.', 1, virt(0x40_3000)),
+                ('<', 2, virt(0x40_0000)),
+                ('<', 3, virt(0)),
+                ('>', 3, virt(0x4000_0000)),
+                ('.', 2, virt(0x4000_0000)),
+                ('<', 3, virt(0x4000_0000)),
+                ('<', 4, virt(0)),
+                ('>', 4, virt(0xFFFF_8000_0000_0000)),
+            ]
+        );
+    }
+}
@@ -1,3 +1,53 @@
 //! Definitions and interfaces to interact with `x86` and `x86_64` paging structures.
+//!
+//! The paging structures of every paging mode share the same typestate design: entries are
+//! parameterized by their level in the hierarchy and by a state describing what is known about
+//! them. The states are shared between paging modes.
 
+pub mod bits32;
 pub mod bits64;
+pub mod pae;
+pub mod pat;
+
+/// Marker struct that indicates that a page table entry has not been classified.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Unclassified;
+
+/// Marker struct that indicates that a page table entry is present.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Present;
+
+/// Marker struct that indicates that a page table entry is a leaf entry.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Leaf;
+
+/// Marker struct that indicates that a page table entry is a branch entry.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Branch;
+
+/// Marker trait that indicates that the implementer is a valid state of a page table entry.
+pub trait PageMapEntryState: Copy + private::PageMapEntryStateSealed {}
+impl PageMapEntryState for Unclassified {}
+impl PageMapEntryState for Present {}
+impl PageMapEntryState for Leaf {}
+impl PageMapEntryState for Branch {}
+
+/// Marker trait that indicates that the [`PageMapEntryState`] is a subset of [`Present`].
+pub trait PageMapEntryPresent: PageMapEntryState {}
+impl PageMapEntryPresent for Present {}
+impl PageMapEntryPresent for Leaf {}
+impl PageMapEntryPresent for Branch {}
+
+mod private {
+    //! Module used to seal the page table entry states shared by every paging mode.
+
+    use crate::structures::paging::{Branch, Leaf, Present, Unclassified};
+
+    /// Marker trait used to seal [`PageMapEntryState`].
+    pub trait PageMapEntryStateSealed {}
+
+    impl PageMapEntryStateSealed for Unclassified {}
+    impl PageMapEntryStateSealed for Present {}
+    impl PageMapEntryStateSealed for Leaf {}
+    impl PageMapEntryStateSealed for Branch {}
+}
@@ -0,0 +1,565 @@
+//! Definitions and interfaces to interact with PAE paging.
+//!
+//! PAE paging is used when paging is enabled, `CR4.PAE` is set, and long mode is not active. It
+//! translates 32-bit linear addresses using a [`PageDirectoryPointerTable`] of 4 entries followed
+//! by two levels of [`PageTable`]s mapping 4 KiB and 2 MiB pages.
+
+use core::marker::PhantomData;
+
+use crate::{
+    addr::PhysAddr,
+    structures::paging::{
+        Branch, Leaf, PageMapEntryPresent, PageMapEntryState, Present, Unclassified,
+    },
+};
+
+/// Representation of the page-directory-pointer table referred to by `CR3`.
+///
+/// The [`PageDirectoryPointerTable`] is loaded into the processor when `CR3` is written, so later
+/// modifications only take effect once `CR3` is reloaded.
+#[repr(C, align(32))]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PageDirectoryPointerTable([PageMapEntry<Pdpte, Unclassified>; 4]);
+
+impl PageDirectoryPointerTable {
+    /// Creates an empty [`PageDirectoryPointerTable`].
+    pub const fn new() -> Self {
+        Self([PageMapEntry::new(); 4])
+    }
+
+    /// Gets the [`PageMapEntry`] located at `index`.
+    ///
+    /// Returns [`None`] if `index` is out of bounds.
+    pub const fn get(&self, index: usize) -> Option<PageMapEntry<Pdpte, Unclassified>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index < self.0.len()) {
+            return None;
+        }
+
+        Some(self.0[index])
+    }
+
+    /// Sets the [`PageMapEntry`] located at `index` to `entry`.
+    ///
+    /// # Errors
+    /// Return [`Err`] if `index` is out of bounds.
+    pub const fn set(
+        &mut self,
+        index: usize,
+        entry: PageMapEntry<Pdpte, Unclassified>,
+    ) -> Result<(), PageMapEntry<Pdpte, Unclassified>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index < self.0.len()) {
+            return Err(entry);
+        }
+
+        self.0[index] = entry;
+        Ok(())
+    }
+}
+
+impl Default for PageDirectoryPointerTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Representation of a page directory or page table.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PageTable<L: PageTableLevel>([PageMapEntry<L, Unclassified>; 512]);
+
+impl<L: PageTableLevel> PageTable<L> {
+    /// Creates an empty [`PageTable`].
+    pub const fn new() -> Self {
+        Self([PageMapEntry::new(); 512])
+    }
+
+    /// Gets the [`PageMapEntry`] located at `index`.
+    ///
+    /// Returns [`None`] if `index` is out of bounds.
+    pub const fn get(&self, index: usize) -> Option<PageMapEntry<L, Unclassified>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index < self.0.len()) {
+            return None;
+        }
+
+        Some(self.0[index])
+    }
+
+    /// Sets the [`PageMapEntry`] located at `index` to `entry`.
+    ///
+    /// # Errors
+    /// Return [`Err`] if `index` is out of bounds.
+    pub const fn set(
+        &mut self,
+        index: usize,
+        entry: PageMapEntry<L, Unclassified>,
+    ) -> Result<(), PageMapEntry<L, Unclassified>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index < self.0.len()) {
+            return Err(entry);
+        }
+
+        self.0[index] = entry;
+        Ok(())
+    }
+}
+
+impl<L: PageTableLevel> Default for PageTable<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A 64-bit PAE page table entry.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PageMapEntry<L: PageMapLevel, S: PageMapEntryState> {
+    /// The underlying value of the [`PageMapEntry`].
+    value: u64,
+    /// Phantom data used to ensure type safety.
+    phantom: PhantomData<(L, S)>,
+}
+
+impl<L: PageMapLevel> PageMapEntry<L, Unclassified> {
+    /// Creates a new [`PageMapEntry`] that is unclassified.
+    pub const fn new() -> Self {
+        Self {
+            value: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new [`PageMapEntry`] from its raw value.
+    pub const fn from_raw(value: u64) -> Self {
+        Self {
+            value,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: PageMapLevel, S: PageMapEntryState> PageMapEntry<L, S> {
+    /// Returns the raw value of this [`PageMapEntry`].
+    pub const fn to_raw(self) -> u64 {
+        self.value
+    }
+
+    /// Returns this [`PageMapEntry`] as a [`PageMapEntry<L, Unclassified>`].
+    pub const fn unclassified(self) -> PageMapEntry<L, Unclassified> {
+        PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns [`PageMapEntry<L, Present>`] if the [`PageMapEntry`] is present; otherwise, this
+    /// function return [`None`].
+    pub const fn present(self) -> Option<PageMapEntry<L, Present>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(self.value & 0b1 == 0b1) {
+            return None;
+        }
+
+        Some(PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Sets the present bit in this [`PageMapEntry`].
+    pub const fn set_present(self) -> PageMapEntry<L, Present> {
+        PageMapEntry {
+            value: self.value | 0b1,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Clears the present bit in this [`PageMapEntry`].
+    pub const fn clear_present(self) -> PageMapEntry<L, Unclassified> {
+        PageMapEntry {
+            value: self.value & !0b1,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: PageMapLevel, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Returns `true` if the bit is set.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn write_through(self) -> bool {
+        self.value & (1 << 3) == (1 << 3)
+    }
+
+    /// Sets the write through bit.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn set_write_through(mut self, write_through: bool) -> Self {
+        self.value = (self.value & !(1 << 3)) | ((write_through as u64) << 3);
+        self
+    }
+
+    /// Returns `true` if the bit is set.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn cache_disable(self) -> bool {
+        self.value & (1 << 4) == (1 << 4)
+    }
+
+    /// Sets the cache disable bit.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn set_cache_disable(mut self, cache_disable: bool) -> Self {
+        self.value = (self.value & !(1 << 4)) | ((cache_disable as u64) << 4);
+        self
+    }
+}
+
+impl<L: PageTableLevel, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] is writable.
+    pub const fn writable(self) -> bool {
+        self.value & (1 << 1) == (1 << 1)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] should be writable.
+    pub const fn set_writable(mut self, writable: bool) -> Self {
+        self.value = (self.value & !(1 << 1)) | ((writable as u64) << 1);
+        self
+    }
+
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] is accessible
+    /// to userspace.
+    pub const fn user(self) -> bool {
+        self.value & (1 << 2) == (1 << 2)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] should be accessible
+    /// to userspace.
+    pub const fn set_user(mut self, accessible: bool) -> Self {
+        self.value = (self.value & !(1 << 2)) | ((accessible as u64) << 2);
+        self
+    }
+
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] has been
+    /// accessed.
+    pub const fn accessed(self) -> bool {
+        self.value & (1 << 5) == (1 << 5)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] should be marked as
+    /// having been accessed.
+    pub const fn set_accessed(mut self, accessed: bool) -> Self {
+        self.value = (self.value & !(1 << 5)) | ((accessed as u64) << 5);
+        self
+    }
+
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] cannot be
+    /// executed.
+    ///
+    /// This bit is reserved unless `IA32_EFER.NXE` is set.
+    pub const fn no_execute(self) -> bool {
+        self.value & (1 << 63) == (1 << 63)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] should be not
+    /// executable.
+    ///
+    /// This bit is reserved unless `IA32_EFER.NXE` is set.
+    pub const fn set_no_execute(mut self, no_execute: bool) -> Self {
+        self.value = (self.value & !(1 << 63)) | ((no_execute as u64) << 63);
+        self
+    }
+}
+
+impl<L: LeafSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Sets the [`PageMapEntry`] to be a leaf entry.
+    pub const fn set_leaf(self, leaf_address: PhysAddr) -> PageMapEntry<L, Leaf> {
+        PageMapEntry {
+            value: (self.value & !L::ADDRESS_MASK)
+                | (leaf_address.as_u64() & L::ADDRESS_MASK)
+                | L::PAGE_SIZE_BIT,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: BranchSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Sets the [`PageMapEntry`] to be a branch entry.
+    pub const fn set_branch(self, branch_address: PhysAddr) -> PageMapEntry<L, Branch> {
+        PageMapEntry {
+            value: (self.value & !(0x000F_FFFF_FFFF_F000 | L::PAGE_SIZE_BIT))
+                | (branch_address.as_u64() & 0x000F_FFFF_FFFF_F000),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: UnconditionalLeafSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Returns this [`PageMapEntry`] as a [`PageMapEntry<L, Leaf>`].
+    pub const fn leaf(self) -> PageMapEntry<L, Leaf> {
+        PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: UnconditionalBranchSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Returns this [`PageMapEntry`] as a [`PageMapEntry<L, Branch>`].
+    pub const fn branch(self) -> PageMapEntry<L, Branch> {
+        PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L: BranchLeafSupport, S: PageMapEntryPresent> PageMapEntry<L, S> {
+    /// Returns [`PageMapEntry<L, Leaf>`] if this [`PageMapEntry`] is a leaf entry; otherwise,
+    /// this function returns [`None`].
+    pub const fn leaf_opt(self) -> Option<PageMapEntry<L, Leaf>> {
+        if !self.is_leaf() {
+            return None;
+        }
+
+        Some(PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns [`PageMapEntry<L, Branch>`] if this [`PageMapEntry`] is a branch entry; otherwise,
+    /// this function returns [`None`].
+    pub const fn branch_opt(self) -> Option<PageMapEntry<L, Branch>> {
+        if self.is_leaf() {
+            return None;
+        }
+
+        Some(PageMapEntry {
+            value: self.value,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns `true` if this [`PageMapEntry`] is a leaf entry.
+    const fn is_leaf(&self) -> bool {
+        self.value & (1 << 7) == (1 << 7)
+    }
+}
+
+impl<L: LeafSupport> PageMapEntry<L, Leaf> {
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] has been written
+    /// to.
+    pub const fn dirty(self) -> bool {
+        self.value & (1 << 6) == (1 << 6)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] has been written to.
+    pub const fn set_dirty(mut self, dirty: bool) -> Self {
+        self.value = (self.value & !(1 << 6)) | ((dirty as u64) << 6);
+        self
+    }
+
+    /// Returns `true` if the region of memory controlled by this [`PageMapEntry`] should be
+    /// translated globally.
+    pub const fn global(self) -> bool {
+        self.value & (1 << 8) == (1 << 8)
+    }
+
+    /// Sets whether the region of memory controlled by this [`PageMapEntry`] should be translated
+    /// globally.
+    pub const fn set_global(mut self, global: bool) -> Self {
+        self.value = (self.value & !(1 << 8)) | ((global as u64) << 8);
+        self
+    }
+
+    /// Returns `true` if the bit is set.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn pat(self) -> bool {
+        self.value & (1 << L::PAT_BIT_POS) == (1 << L::PAT_BIT_POS)
+    }
+
+    /// Sets the pat bit.
+    ///
+    /// This bit helps determine the memory type used to access the item pointed to by this
+    /// [`PageMapEntry`].
+    pub const fn set_pat(mut self, pat: bool) -> Self {
+        self.value = (self.value & !(1 << L::PAT_BIT_POS)) | ((pat as u64) << L::PAT_BIT_POS);
+        self
+    }
+
+    /// Returns the base address of the region of memory controlled by this [`PageMapEntry`].
+    pub const fn frame(self) -> PhysAddr {
+        PhysAddr::new_truncate(self.value & L::ADDRESS_MASK)
+    }
+}
+
+impl<L: BranchSupport> PageMapEntry<L, Branch> {
+    /// Returns the base address of the next level of the page table hierarchy.
+    pub const fn frame(self) -> PhysAddr {
+        PhysAddr::new_truncate(self.value & 0x000F_FFFF_FFFF_F000)
+    }
+}
+
+impl<L: PageMapLevel> Default for PageMapEntry<L, Unclassified> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The lowest level of the hierarchy, mapping 4 KiB pages.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Pte;
+
+/// The second level of the hierarchy, mapping 2 MiB pages or referring to a [`PageTable<Pte>`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Pde;
+
+/// The highest level of the hierarchy, stored in the [`PageDirectoryPointerTable`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Pdpte;
+
+/// Marker trait indicating that the implementer is a valid page map level.
+pub trait PageMapLevel: Copy + private::PageMapLevelSealed {}
+impl PageMapLevel for Pte {}
+impl PageMapLevel for Pde {}
+impl PageMapLevel for Pdpte {}
+
+/// Marker trait indicating that the [`PageMapEntry`]s of that [`PageMapLevel`] are stored in a
+/// [`PageTable`] and control the access rights of the pages they map.
+pub trait PageTableLevel: PageMapLevel {}
+impl PageTableLevel for Pte {}
+impl PageTableLevel for Pde {}
+
+/// Marker trait indicating that the [`PageMapEntry`]s of that [`PageMapLevel`] could be leaf
+/// entries.
+pub trait LeafSupport: PageTableLevel {
+    /// Position of the PAT bit.
+    const PAT_BIT_POS: u8;
+
+    /// Bitmask to extract the address of the frames this [`PageMapEntry`] controls.
+    const ADDRESS_MASK: u64;
+
+    /// Bit that marks a [`PageMapEntry`] of this [`PageMapLevel`] as a leaf entry, or 0 if every
+    /// [`PageMapEntry`] of this [`PageMapLevel`] is a leaf entry.
+    const PAGE_SIZE_BIT: u64;
+}
+impl LeafSupport for Pte {
+    const PAT_BIT_POS: u8 = 7;
+    const ADDRESS_MASK: u64 = 0x000F_FFFF_FFFF_F000;
+    const PAGE_SIZE_BIT: u64 = 0;
+}
+impl LeafSupport for Pde {
+    const PAT_BIT_POS: u8 = 12;
+    const ADDRESS_MASK: u64 = 0x000F_FFFF_FFE0_0000;
+    const PAGE_SIZE_BIT: u64 = 1 << 7;
+}
+
+/// Marker trait indicating that the [`PageMapEntry`]s of that [`PageMapLevel`] could be branch
+/// entries.
+pub trait BranchSupport: PageMapLevel {
+    /// Bit that marks a [`PageMapEntry`] of this [`PageMapLevel`] as a leaf entry, or 0 if every
+    /// [`PageMapEntry`] of this [`PageMapLevel`] is a branch entry.
+    const PAGE_SIZE_BIT: u64;
+}
+impl BranchSupport for Pde {
+    const PAGE_SIZE_BIT: u64 = 1 << 7;
+}
+impl BranchSupport for Pdpte {
+    const PAGE_SIZE_BIT: u64 = 0;
+}
+
+/// Marker trait that indicates that the [`PageMapEntry`]s of that [`PageMapLevel`]
+/// could be either branch or leaf entries.
+pub trait BranchLeafSupport: LeafSupport + BranchSupport {}
+impl BranchLeafSupport for Pde {}
+
+/// Marker trait that indicates that the [`PageMapEntry`]s of that [`PageMapLevel`]
+/// are unconditionally leaf entries.
+pub trait UnconditionalLeafSupport: LeafSupport {}
+impl UnconditionalLeafSupport for Pte {}
+
+/// Marker trait that indicates that the [`PageMapEntry`]s of that [`PageMapLevel`]
+/// are unconditionally branch entries.
+pub trait UnconditionalBranchSupport: BranchSupport {}
+impl UnconditionalBranchSupport for Pdpte {}
+
+mod private {
+    //! Module used to seal the levels used to implement the PAE paging abstraction.
+
+    use crate::structures::paging::pae::{Pde, Pdpte, Pte};
+
+    /// Marker trait used to seal [`PageMapLevel`].
+    pub trait PageMapLevelSealed {}
+
+    impl PageMapLevelSealed for Pte {}
+    impl PageMapLevelSealed for Pde {}
+    impl PageMapLevelSealed for Pdpte {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PageDirectoryPointerTable, PageMapEntry, PageTable, Pde, Pte};
+    use crate::addr::PhysAddr;
+
+    #[test]
+    fn pdpt() {
+        let mut pdpt = PageDirectoryPointerTable::new();
+        let entry = pdpt
+            .get(3)
+            .unwrap()
+            .set_present()
+            .set_write_through(true)
+            .set_branch(PhysAddr::new(0x1_2345_6000).unwrap());
+        pdpt.set(3, entry.unclassified()).unwrap();
+        assert!(pdpt.set(4, entry.unclassified()).is_err());
+
+        assert_eq!(entry.to_raw(), 0x1_2345_6009);
+        assert_eq!(
+            pdpt.get(3).unwrap().present().unwrap().branch().frame(),
+            PhysAddr::new(0x1_2345_6000).unwrap()
+        );
+        assert_eq!(core::mem::size_of::<PageDirectoryPointerTable>(), 32);
+        assert_eq!(core::mem::align_of::<PageDirectoryPointerTable>(), 32);
+    }
+
+    #[test]
+    fn leaves() {
+        let mut directory = PageTable::<Pde>::new();
+        let entry = directory
+            .get(0)
+            .unwrap()
+            .set_present()
+            .set_writable(true)
+            .set_no_execute(true)
+            .set_leaf(PhysAddr::new(0x4_0020_0000).unwrap())
+            .set_pat(true);
+        directory.set(0, entry.unclassified()).unwrap();
+
+        assert_eq!(entry.to_raw(), 0x8000_0004_0020_1083);
+        let entry = directory.get(0).unwrap().present().unwrap();
+        assert!(entry.branch_opt().is_none());
+        let entry = entry.leaf_opt().unwrap();
+        assert_eq!(entry.frame(), PhysAddr::new(0x4_0020_0000).unwrap());
+        assert!(entry.no_execute() && entry.pat());
+
+        let entry = PageMapEntry::<Pde, _>::from_raw(entry.to_raw())
+            .set_present()
+            .set_branch(PhysAddr::new(0x3000).unwrap());
+        assert_eq!(entry.to_raw(), 0x8000_0000_0000_3003);
+
+        let entry = PageMapEntry::<Pte, _>::new()
+            .set_present()
+            .set_leaf(PhysAddr::new(0x7000).unwrap())
+            .set_pat(true);
+        assert_eq!(entry.to_raw(), 0x7081);
+    }
+}
@@ -0,0 +1,186 @@
+//! Definitions and interfaces to interact with the page attribute table.
+//!
+//! The page attribute table (PAT) is configured through the `IA32_PAT` MSR and holds 8 memory
+//! types. The PAT, cache disable, and write through bits of a leaf page table entry select one of
+//! those slots.
+
+use core::fmt;
+
+/// A memory type that can be selected through the page attribute table.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum MemoryType {
+    /// Uncacheable.
+    ///
+    /// Memory accesses are not cached and are not speculative.
+    Uncacheable = 0,
+    /// Write combining.
+    ///
+    /// Memory accesses are not cached, but writes may be combined and delayed.
+    WriteCombining = 1,
+    /// Write through.
+    ///
+    /// Reads are cached and writes are propagated to memory.
+    WriteThrough = 4,
+    /// Write protected.
+    ///
+    /// Reads are cached and writes invalidate the corresponding cache lines.
+    WriteProtected = 5,
+    /// Write back.
+    ///
+    /// Reads and writes are cached.
+    WriteBack = 6,
+    /// Uncached.
+    ///
+    /// Like [`MemoryType::Uncacheable`], but can be overridden by a write combining MTRR.
+    Uncached = 7,
+}
+
+impl MemoryType {
+    /// Creates a [`MemoryType`] from its encoding in the `IA32_PAT` MSR.
+    ///
+    /// Returns [`None`] if `val` is a reserved encoding.
+    pub const fn from_u8(val: u8) -> Option<Self> {
+        match val {
+            0 => Some(Self::Uncacheable),
+            1 => Some(Self::WriteCombining),
+            4 => Some(Self::WriteThrough),
+            5 => Some(Self::WriteProtected),
+            6 => Some(Self::WriteBack),
+            7 => Some(Self::Uncached),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MemoryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Uncacheable => "UC",
+            Self::WriteCombining => "WC",
+            Self::WriteThrough => "WT",
+            Self::WriteProtected => "WP",
+            Self::WriteBack => "WB",
+            Self::Uncached => "UC-",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// The 8 memory types configured in the page attribute table.
+///
+/// A slot is selected by a 3-bit index made up of the PAT bit (bit 2), the cache disable bit
+/// (bit 1), and the write through bit (bit 0) of a leaf page table entry.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PatConfiguration([MemoryType; 8]);
+
+impl PatConfiguration {
+    /// The [`PatConfiguration`] after power-up or reset.
+    ///
+    /// This configuration is compatible with processors that do not support the PAT.
+    pub const DEFAULT: Self = Self([
+        MemoryType::WriteBack,
+        MemoryType::WriteThrough,
+        MemoryType::Uncached,
+        MemoryType::Uncacheable,
+        MemoryType::WriteBack,
+        MemoryType::WriteThrough,
+        MemoryType::Uncached,
+        MemoryType::Uncacheable,
+    ]);
+
+    /// Creates a new [`PatConfiguration`] from the memory types of its 8 slots.
+    pub const fn new(slots: [MemoryType; 8]) -> Self {
+        Self(slots)
+    }
+
+    /// Creates a new [`PatConfiguration`] from the value of the `IA32_PAT` MSR.
+    ///
+    /// Returns [`None`] if any slot holds a reserved encoding.
+    pub const fn from_raw(value: u64) -> Option<Self> {
+        let mut slots = [MemoryType::Uncacheable; 8];
+
+        let mut index = 0;
+        while index < slots.len() {
+            slots[index] = match MemoryType::from_u8(((value >> (index * 8)) & 0xFF) as u8) {
+                Some(memory_type) => memory_type,
+                None => return None,
+            };
+            index += 1;
+        }
+
+        Some(Self(slots))
+    }
+
+    /// Returns the value of the `IA32_PAT` MSR that holds this [`PatConfiguration`].
+    pub const fn to_raw(self) -> u64 {
+        let mut value = 0;
+
+        let mut index = 0;
+        while index < self.0.len() {
+            value |= (self.0[index] as u64) << (index * 8);
+            index += 1;
+        }
+
+        value
+    }
+
+    /// Returns the [`MemoryType`] of the slot at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is not less than 8.
+    pub const fn get(self, index: u8) -> MemoryType {
+        self.0[index as usize]
+    }
+
+    /// Sets the [`MemoryType`] of the slot at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is not less than 8.
+    pub const fn set(mut self, index: u8, memory_type: MemoryType) -> Self {
+        self.0[index as usize] = memory_type;
+        self
+    }
+
+    /// Returns the index of the first slot holding `memory_type`.
+    ///
+    /// Returns [`None`] if no slot holds `memory_type`.
+    pub const fn index_of(self, memory_type: MemoryType) -> Option<u8> {
+        let mut index = 0;
+        while index < self.0.len() {
+            if self.0[index] as u8 == memory_type as u8 {
+                return Some(index as u8);
+            }
+            index += 1;
+        }
+
+        None
+    }
+}
+
+impl Default for PatConfiguration {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MemoryType, PatConfiguration};
+
+    #[test]
+    fn raw() {
+        assert_eq!(PatConfiguration::DEFAULT.to_raw(), 0x0007_0406_0007_0406);
+        assert_eq!(
+            PatConfiguration::from_raw(0x0007_0406_0007_0406),
+            Some(PatConfiguration::DEFAULT)
+        );
+        assert_eq!(PatConfiguration::from_raw(0x0007_0406_0007_0402), None);
+
+        let pat = PatConfiguration::DEFAULT.set(5, MemoryType::WriteCombining);
+        assert_eq!(pat.to_raw(), 0x0007_0106_0007_0406);
+        assert_eq!(pat.index_of(MemoryType::WriteCombining), Some(5));
+        assert_eq!(pat.index_of(MemoryType::WriteThrough), Some(1));
+        assert_eq!(pat.index_of(MemoryType::WriteProtected), None);
+    }
+}
@@ -0,0 +1,1028 @@
+//! Definitions and interfaces to interact with `x86` and `x86_64` segment descriptor tables.
+//!
+//! The same [`SegmentDescriptor`] format is used by both the global descriptor table (GDT) and
+//! local descriptor tables (LDTs).
+
+use core::marker::PhantomData;
+
+use crate::{
+    registers::segmentation::SegmentSelector,
+    structures::{descriptor_table::DescriptorTablePointer, task_state::TaskStateSegment64},
+    Current, PrivilegeLevel,
+};
+
+/// A table of [`SegmentDescriptor`]s, suitable for use as a GDT or an LDT.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct SegmentTable<const N: usize> {
+    /// The [`SegmentDescriptor`]s that make up this [`SegmentTable`].
+    descriptors: [SegmentDescriptor<Unclassified>; N],
+    /// The number of [`SegmentDescriptor`]s that are in use.
+    len: usize,
+    /// Whether this [`SegmentTable`] is an LDT.
+    local: bool,
+}
+
+impl<const N: usize> SegmentTable<N> {
+    /// Creates an empty [`SegmentTable`] intended to be used as a GDT.
+    ///
+    /// The first entry of a GDT is the null descriptor, so the first [`SegmentDescriptor`] pushed
+    /// onto this [`SegmentTable`] has an index of 1.
+    ///
+    /// # Panics
+    /// Panics if `N` is 0 or greater than 8192.
+    pub const fn new() -> Self {
+        assert!(N != 0 && N <= 8192);
+
+        Self {
+            descriptors: [SegmentDescriptor::NULL; N],
+            len: 1,
+            local: false,
+        }
+    }
+
+    /// Creates an empty [`SegmentTable`] intended to be used as an LDT.
+    ///
+    /// # Panics
+    /// Panics if `N` is greater than 8192.
+    pub const fn new_local() -> Self {
+        assert!(N <= 8192);
+
+        Self {
+            descriptors: [SegmentDescriptor::NULL; N],
+            len: 0,
+            local: true,
+        }
+    }
+
+    /// Appends `descriptor` to this [`SegmentTable`], returning a [`SegmentSelector`] which refers
+    /// to it.
+    ///
+    /// The requested [`PrivilegeLevel`] of the returned [`SegmentSelector`] is the descriptor
+    /// privilege level of `descriptor`.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if this [`SegmentTable`] is full.
+    pub const fn push<S: SegmentDescriptorKind>(
+        &mut self,
+        descriptor: SegmentDescriptor<S>,
+    ) -> Result<SegmentSelector, SegmentDescriptor<S>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(self.len < N) {
+            return Err(descriptor);
+        }
+
+        let index = self.len;
+        self.descriptors[index] = descriptor.unclassified();
+        self.len += 1;
+
+        Ok(SegmentSelector::new(
+            index as u16,
+            self.local,
+            descriptor.dpl(),
+        ))
+    }
+
+    /// Appends `descriptor` to this [`SegmentTable`] as two consecutive entries, returning a
+    /// [`SegmentSelector`] which refers to it.
+    ///
+    /// The requested [`PrivilegeLevel`] of the returned [`SegmentSelector`] is the descriptor
+    /// privilege level of `descriptor`.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if this [`SegmentTable`] does not have room for two more entries.
+    pub const fn push_system(
+        &mut self,
+        descriptor: SystemDescriptor64,
+    ) -> Result<SegmentSelector, SystemDescriptor64> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(self.len + 1 < N) {
+            return Err(descriptor);
+        }
+
+        let index = self.len;
+        self.descriptors[index] = descriptor.low.unclassified();
+        self.descriptors[index + 1] = SegmentDescriptor::from_raw(descriptor.high);
+        self.len += 2;
+
+        Ok(SegmentSelector::new(
+            index as u16,
+            self.local,
+            descriptor.low.dpl(),
+        ))
+    }
+
+    /// Gets the [`SystemDescriptor64`] occupying the two entries starting at `index`.
+    ///
+    /// Returns [`None`] if `index` is out of bounds or if the entries do not hold a valid
+    /// [`SystemDescriptor64`].
+    pub const fn get_system(&self, index: usize) -> Option<SystemDescriptor64> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index + 1 < self.len) {
+            return None;
+        }
+
+        SystemDescriptor64::from_raw(
+            self.descriptors[index].to_raw(),
+            self.descriptors[index + 1].to_raw(),
+        )
+    }
+
+    /// Sets the two entries starting at `index` to `descriptor`.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if `index` is out of bounds.
+    pub const fn set_system(
+        &mut self,
+        index: usize,
+        descriptor: SystemDescriptor64,
+    ) -> Result<(), SystemDescriptor64> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index + 1 < self.len) {
+            return Err(descriptor);
+        }
+
+        self.descriptors[index] = descriptor.low.unclassified();
+        self.descriptors[index + 1] = SegmentDescriptor::from_raw(descriptor.high);
+        Ok(())
+    }
+
+    /// Gets the [`SegmentDescriptor`] located at `index`.
+    ///
+    /// Returns [`None`] if `index` is out of bounds.
+    pub const fn get(&self, index: usize) -> Option<SegmentDescriptor<Unclassified>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index < self.len) {
+            return None;
+        }
+
+        Some(self.descriptors[index])
+    }
+
+    /// Sets the [`SegmentDescriptor`] located at `index` to `descriptor`.
+    ///
+    /// # Errors
+    /// Returns [`Err`] if `index` is out of bounds.
+    pub const fn set<S: SegmentDescriptorKind>(
+        &mut self,
+        index: usize,
+        descriptor: SegmentDescriptor<S>,
+    ) -> Result<(), SegmentDescriptor<S>> {
+        #[allow(clippy::nonminimal_bool)]
+        if !(index < self.len) {
+            return Err(descriptor);
+        }
+
+        self.descriptors[index] = descriptor.unclassified();
+        Ok(())
+    }
+
+    /// Returns the number of [`SegmentDescriptor`]s in use, including the null descriptor of a
+    /// GDT.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no [`SegmentDescriptor`]s have been pushed onto this [`SegmentTable`].
+    pub const fn is_empty(&self) -> bool {
+        if self.local {
+            self.len == 0
+        } else {
+            self.len == 1
+        }
+    }
+
+    /// Returns `true` if this [`SegmentTable`] is intended to be used as an LDT.
+    pub const fn local(&self) -> bool {
+        self.local
+    }
+
+    /// Returns the [`SegmentDescriptor`]s in use.
+    pub const fn as_slice(&self) -> &[SegmentDescriptor<Unclassified>] {
+        self.descriptors.split_at(self.len).0
+    }
+
+    /// Returns a [`DescriptorTablePointer`] which refers to the [`SegmentDescriptor`]s in use.
+    ///
+    /// The limit is zero if this [`SegmentTable`] is an empty LDT, so that no selector refers to a
+    /// valid descriptor.
+    pub fn pointer(&self) -> DescriptorTablePointer<Current> {
+        let limit =
+            (self.len * core::mem::size_of::<SegmentDescriptor<Unclassified>>()).saturating_sub(1);
+
+        DescriptorTablePointer::new(limit as u16, self.descriptors.as_ptr() as _)
+    }
+
+    /// Loads this [`SegmentTable`] into the global descriptor table register.
+    ///
+    /// This does not reload any segment registers.
+    ///
+    /// # Panics
+    /// Panics if this [`SegmentTable`] is intended to be used as an LDT.
+    #[cfg(feature = "instructions")]
+    pub fn load(&'static self) {
+        assert!(!self.local);
+
+        // SAFETY:
+        // - The pointer refers to a valid global descriptor table.
+        // - `self` is valid for the `'static` lifetime.
+        unsafe { crate::instructions::tables::lgdt(&self.pointer()) }
+    }
+}
+
+impl<const N: usize> Default for SegmentTable<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An 8-byte segment descriptor.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct SegmentDescriptor<S: SegmentDescriptorKind> {
+    /// The underlying value of the [`SegmentDescriptor`].
+    value: u64,
+    /// Phantom data used to ensure type safety.
+    phantom: PhantomData<S>,
+}
+
+#[allow(clippy::missing_docs_in_private_items)]
+impl<S: SegmentDescriptorKind> SegmentDescriptor<S> {
+    const ACCESSED_BIT: u64 = 1 << 40;
+    const READ_WRITE_BIT: u64 = 1 << 41;
+    const DIRECTION_CONFORMING_BIT: u64 = 1 << 42;
+    const EXECUTABLE_BIT: u64 = 1 << 43;
+    const CODE_DATA_BIT: u64 = 1 << 44;
+    const DPL_SHIFT: u64 = 45;
+    const PRESENT_BIT: u64 = 1 << 47;
+    const AVAILABLE_BIT: u64 = 1 << 52;
+    const LONG_MODE_BIT: u64 = 1 << 53;
+    const SIZE_BIT: u64 = 1 << 54;
+    const GRANULARITY_BIT: u64 = 1 << 55;
+
+    const TYPE_MASK: u64 = 0xF << 40;
+    const LIMIT_MASK: u64 = 0x000F_0000_0000_FFFF;
+    const BASE_MASK: u64 = 0xFF00_00FF_FFFF_0000;
+}
+
+impl SegmentDescriptor<Unclassified> {
+    /// The null [`SegmentDescriptor`].
+    pub const NULL: Self = Self::from_raw(0);
+
+    /// Creates a new [`SegmentDescriptor`] from its raw representation.
+    pub const fn from_raw(raw: u64) -> Self {
+        Self {
+            value: raw,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns [`SegmentDescriptor<Code>`] if this [`SegmentDescriptor`] describes a code
+    /// segment; otherwise, this function returns [`None`].
+    pub const fn code(self) -> Option<SegmentDescriptor<Code>> {
+        let mask = Self::CODE_DATA_BIT | Self::EXECUTABLE_BIT;
+        #[allow(clippy::nonminimal_bool)]
+        if !(self.value & mask == mask) {
+            return None;
+        }
+
+        Some(SegmentDescriptor {
+            value: self.value,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns [`SegmentDescriptor<Data>`] if this [`SegmentDescriptor`] describes a data
+    /// segment; otherwise, this function returns [`None`].
+    pub const fn data(self) -> Option<SegmentDescriptor<Data>> {
+        let mask = Self::CODE_DATA_BIT | Self::EXECUTABLE_BIT;
+        #[allow(clippy::nonminimal_bool)]
+        if !(self.value & mask == Self::CODE_DATA_BIT) {
+            return None;
+        }
+
+        Some(SegmentDescriptor {
+            value: self.value,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns [`SegmentDescriptor<System>`] if this [`SegmentDescriptor`] describes a system
+    /// segment; otherwise, this function returns [`None`].
+    pub const fn system(self) -> Option<SegmentDescriptor<System>> {
+        if self.value & Self::CODE_DATA_BIT == Self::CODE_DATA_BIT {
+            return None;
+        }
+
+        Some(SegmentDescriptor {
+            value: self.value,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<S: SegmentDescriptorKind> SegmentDescriptor<S> {
+    /// Returns this [`SegmentDescriptor`] as a [`SegmentDescriptor<Unclassified>`].
+    pub const fn unclassified(self) -> SegmentDescriptor<Unclassified> {
+        SegmentDescriptor {
+            value: self.value,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the raw representation of this [`SegmentDescriptor`].
+    pub const fn to_raw(self) -> u64 {
+        self.value
+    }
+
+    /// Returns the linear address of the first byte of the segment.
+    pub const fn base(self) -> u32 {
+        (((self.value >> 16) & 0xFF_FFFF) | ((self.value >> 32) & 0xFF00_0000)) as u32
+    }
+
+    /// Sets the linear address of the first byte of the segment.
+    pub const fn set_base(mut self, base: u32) -> Self {
+        let base = base as u64;
+        let base = ((base & 0xFF_FFFF) << 16) | ((base & 0xFF00_0000) << 32);
+
+        self.value = (self.value & !Self::BASE_MASK) | base;
+        self
+    }
+
+    /// Returns the 20-bit segment limit.
+    ///
+    /// The limit is measured in 4 KiB units if [`SegmentDescriptor::granularity()`] is `true`
+    /// and in bytes otherwise.
+    pub const fn limit(self) -> u32 {
+        ((self.value & 0xFFFF) | ((self.value >> 32) & 0xF_0000)) as u32
+    }
+
+    /// Sets the 20-bit segment limit.
+    ///
+    /// The limit is measured in 4 KiB units if [`SegmentDescriptor::granularity()`] is `true`
+    /// and in bytes otherwise.
+    ///
+    /// # Panics
+    /// Panics if `limit` does not fit in 20 bits.
+    pub const fn set_limit(mut self, limit: u32) -> Self {
+        assert!(limit <= 0xF_FFFF);
+
+        let limit = limit as u64;
+        let limit = (limit & 0xFFFF) | ((limit & 0xF_0000) << 32);
+
+        self.value = (self.value & !Self::LIMIT_MASK) | limit;
+        self
+    }
+
+    /// Returns `true` if the segment limit is measured in 4 KiB units.
+    pub const fn granularity(self) -> bool {
+        self.value & Self::GRANULARITY_BIT == Self::GRANULARITY_BIT
+    }
+
+    /// Sets whether the segment limit is measured in 4 KiB units.
+    pub const fn set_granularity(mut self, granularity: bool) -> Self {
+        self.value = (self.value & !Self::GRANULARITY_BIT) | ((granularity as u64) << 55);
+        self
+    }
+
+    /// Returns the descriptor [`PrivilegeLevel`] of the segment.
+    pub const fn dpl(self) -> PrivilegeLevel {
+        match (self.value >> Self::DPL_SHIFT) & 0b11 {
+            0 => PrivilegeLevel::Ring0,
+            1 => PrivilegeLevel::Ring1,
+            2 => PrivilegeLevel::Ring2,
+            3 => PrivilegeLevel::Ring3,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sets the descriptor [`PrivilegeLevel`] of the segment.
+    pub const fn set_dpl(mut self, dpl: PrivilegeLevel) -> Self {
+        self.value = (self.value & !(0b11 << Self::DPL_SHIFT)) | ((dpl as u64) << Self::DPL_SHIFT);
+        self
+    }
+
+    /// Returns `true` if the segment is present in memory.
+    pub const fn present(self) -> bool {
+        self.value & Self::PRESENT_BIT == Self::PRESENT_BIT
+    }
+
+    /// Sets whether the segment is present in memory.
+    pub const fn set_present(mut self, present: bool) -> Self {
+        self.value = (self.value & !Self::PRESENT_BIT) | ((present as u64) << 47);
+        self
+    }
+
+    /// Returns `true` if the bit available for use by system software is set.
+    pub const fn available(self) -> bool {
+        self.value & Self::AVAILABLE_BIT == Self::AVAILABLE_BIT
+    }
+
+    /// Sets the bit available for use by system software.
+    pub const fn set_available(mut self, available: bool) -> Self {
+        self.value = (self.value & !Self::AVAILABLE_BIT) | ((available as u64) << 52);
+        self
+    }
+}
+
+impl<S: CodeDataKind> SegmentDescriptor<S> {
+    /// Returns `true` if the segment has been accessed since this bit was last cleared.
+    pub const fn accessed(self) -> bool {
+        self.value & Self::ACCESSED_BIT == Self::ACCESSED_BIT
+    }
+
+    /// Sets whether the segment should be marked as having been accessed.
+    pub const fn set_accessed(mut self, accessed: bool) -> Self {
+        self.value = (self.value & !Self::ACCESSED_BIT) | ((accessed as u64) << 40);
+        self
+    }
+}
+
+impl SegmentDescriptor<Code> {
+    /// A flat 64-bit code segment for [`PrivilegeLevel::Ring0`].
+    pub const KERNEL_CODE64: Self = Self::new()
+        .set_limit(0xF_FFFF)
+        .set_granularity(true)
+        .set_long_mode(true);
+    /// A flat 64-bit code segment for [`PrivilegeLevel::Ring3`].
+    pub const USER_CODE64: Self = Self::KERNEL_CODE64.set_dpl(PrivilegeLevel::Ring3);
+    /// A flat 32-bit code segment for [`PrivilegeLevel::Ring0`].
+    pub const KERNEL_CODE32: Self = Self::new()
+        .set_limit(0xF_FFFF)
+        .set_granularity(true)
+        .set_default_size(true);
+    /// A flat 32-bit code segment for [`PrivilegeLevel::Ring3`].
+    pub const USER_CODE32: Self = Self::KERNEL_CODE32.set_dpl(PrivilegeLevel::Ring3);
+
+    /// Creates a new present, readable, non-conforming code [`SegmentDescriptor`] with a base and
+    /// limit of 0.
+    pub const fn new() -> Self {
+        Self {
+            value: Self::PRESENT_BIT
+                | Self::CODE_DATA_BIT
+                | Self::EXECUTABLE_BIT
+                | Self::READ_WRITE_BIT,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the segment may be read from.
+    pub const fn readable(self) -> bool {
+        self.value & Self::READ_WRITE_BIT == Self::READ_WRITE_BIT
+    }
+
+    /// Sets whether the segment may be read from.
+    pub const fn set_readable(mut self, readable: bool) -> Self {
+        self.value = (self.value & !Self::READ_WRITE_BIT) | ((readable as u64) << 41);
+        self
+    }
+
+    /// Returns `true` if the segment is conforming.
+    ///
+    /// Code in a conforming segment may be executed from an equal or less privileged
+    /// [`PrivilegeLevel`] without changing the current privilege level.
+    pub const fn conforming(self) -> bool {
+        self.value & Self::DIRECTION_CONFORMING_BIT == Self::DIRECTION_CONFORMING_BIT
+    }
+
+    /// Sets whether the segment is conforming.
+    ///
+    /// Code in a conforming segment may be executed from an equal or less privileged
+    /// [`PrivilegeLevel`] without changing the current privilege level.
+    pub const fn set_conforming(mut self, conforming: bool) -> Self {
+        self.value = (self.value & !Self::DIRECTION_CONFORMING_BIT) | ((conforming as u64) << 42);
+        self
+    }
+
+    /// Returns `true` if the segment contains 64-bit code.
+    pub const fn long_mode(self) -> bool {
+        self.value & Self::LONG_MODE_BIT == Self::LONG_MODE_BIT
+    }
+
+    /// Sets whether the segment contains 64-bit code.
+    ///
+    /// If this is set, [`SegmentDescriptor::set_default_size()`] must be cleared.
+    pub const fn set_long_mode(mut self, long_mode: bool) -> Self {
+        self.value = (self.value & !Self::LONG_MODE_BIT) | ((long_mode as u64) << 53);
+        self
+    }
+
+    /// Returns `true` if the default operand and address size of the segment is 32 bits, and
+    /// `false` if it is 16 bits.
+    pub const fn default_size(self) -> bool {
+        self.value & Self::SIZE_BIT == Self::SIZE_BIT
+    }
+
+    /// Sets whether the default operand and address size of the segment is 32 bits.
+    pub const fn set_default_size(mut self, default_size: bool) -> Self {
+        self.value = (self.value & !Self::SIZE_BIT) | ((default_size as u64) << 54);
+        self
+    }
+}
+
+impl SegmentDescriptor<Data> {
+    /// A flat data segment for [`PrivilegeLevel::Ring0`].
+    pub const KERNEL_DATA: Self = Self::new()
+        .set_limit(0xF_FFFF)
+        .set_granularity(true)
+        .set_big(true);
+    /// A flat data segment for [`PrivilegeLevel::Ring3`].
+    pub const USER_DATA: Self = Self::KERNEL_DATA.set_dpl(PrivilegeLevel::Ring3);
+
+    /// Creates a new present, writable, expand-up data [`SegmentDescriptor`] with a base and limit
+    /// of 0.
+    pub const fn new() -> Self {
+        Self {
+            value: Self::PRESENT_BIT | Self::CODE_DATA_BIT | Self::READ_WRITE_BIT,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the segment may be written to.
+    pub const fn writable(self) -> bool {
+        self.value & Self::READ_WRITE_BIT == Self::READ_WRITE_BIT
+    }
+
+    /// Sets whether the segment may be written to.
+    pub const fn set_writable(mut self, writable: bool) -> Self {
+        self.value = (self.value & !Self::READ_WRITE_BIT) | ((writable as u64) << 41);
+        self
+    }
+
+    /// Returns `true` if the segment expands down.
+    ///
+    /// The valid offsets of an expand-down segment range from the limit + 1 to the maximum
+    /// offset.
+    pub const fn expand_down(self) -> bool {
+        self.value & Self::DIRECTION_CONFORMING_BIT == Self::DIRECTION_CONFORMING_BIT
+    }
+
+    /// Sets whether the segment expands down.
+    ///
+    /// The valid offsets of an expand-down segment range from the limit + 1 to the maximum
+    /// offset.
+    pub const fn set_expand_down(mut self, expand_down: bool) -> Self {
+        self.value = (self.value & !Self::DIRECTION_CONFORMING_BIT) | ((expand_down as u64) << 42);
+        self
+    }
+
+    /// Returns `true` if the segment is big.
+    ///
+    /// For a stack segment, this determines whether the stack pointer is 32 bits wide. For an
+    /// expand-down segment, this determines whether the upper bound of the segment is 4 GiB or
+    /// 64 KiB.
+    pub const fn big(self) -> bool {
+        self.value & Self::SIZE_BIT == Self::SIZE_BIT
+    }
+
+    /// Sets whether the segment is big.
+    ///
+    /// For a stack segment, this determines whether the stack pointer is 32 bits wide. For an
+    /// expand-down segment, this determines whether the upper bound of the segment is 4 GiB or
+    /// 64 KiB.
+    pub const fn set_big(mut self, big: bool) -> Self {
+        self.value = (self.value & !Self::SIZE_BIT) | ((big as u64) << 54);
+        self
+    }
+}
+
+impl SegmentDescriptor<System> {
+    /// Creates a new present system [`SegmentDescriptor`] of type `system_type` with a base and
+    /// limit of 0.
+    pub const fn new(system_type: SystemSegmentType) -> Self {
+        Self {
+            value: Self::PRESENT_BIT | ((system_type as u64) << 40),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the [`SystemSegmentType`] of the segment.
+    ///
+    /// Returns [`None`] if the type field does not describe a [`SystemSegmentType`].
+    pub const fn system_type(self) -> Option<SystemSegmentType> {
+        SystemSegmentType::from_u8(((self.value & Self::TYPE_MASK) >> 40) as u8)
+    }
+
+    /// Sets the [`SystemSegmentType`] of the segment.
+    pub const fn set_system_type(mut self, system_type: SystemSegmentType) -> Self {
+        self.value = (self.value & !Self::TYPE_MASK) | ((system_type as u64) << 40);
+        self
+    }
+}
+
+/// A 16-byte system segment descriptor used in IA-32e mode.
+///
+/// In IA-32e mode, LDT and TSS descriptors are expanded to 16 bytes so that they can hold a
+/// 64-bit base address. These descriptors occupy two consecutive entries of a [`SegmentTable`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct SystemDescriptor64 {
+    /// The lower 8 bytes of the [`SystemDescriptor64`].
+    low: SegmentDescriptor<System>,
+    /// The upper 8 bytes of the [`SystemDescriptor64`], which hold the upper 32 bits of the base.
+    high: u64,
+}
+
+impl SystemDescriptor64 {
+    /// Creates a new available [`SystemDescriptor64`] which describes `tss`.
+    pub fn tss(tss: &'static TaskStateSegment64) -> Self {
+        Self::new(SystemSegmentType::TssAvailable)
+            .set_base(core::ptr::from_ref(tss) as u64)
+            .set_limit((core::mem::size_of::<TaskStateSegment64>() - 1) as u32)
+    }
+
+    /// Creates a new [`SystemDescriptor64`] which describes the [`SegmentDescriptor`]s in use in
+    /// `ldt`, like [`SegmentTable::pointer()`].
+    ///
+    /// # Panics
+    /// Panics if `ldt` is not intended to be used as an LDT or if `ldt` is empty.
+    pub fn ldt<const N: usize>(ldt: &'static SegmentTable<N>) -> Self {
+        assert!(ldt.local() && ldt.len != 0);
+
+        let limit = ldt.len * core::mem::size_of::<SegmentDescriptor<Unclassified>>() - 1;
+        Self::new(SystemSegmentType::Ldt)
+            .set_base(ldt.descriptors.as_ptr() as u64)
+            .set_limit(limit as u32)
+    }
+
+    /// Creates a new [`SystemDescriptor64`] from the raw values of the two entries it occupies.
+    ///
+    /// Returns [`None`] if `low` does not describe an LDT or a 64-bit TSS, or if the reserved type
+    /// field of `high` is not zero.
+    pub const fn from_raw(low: u64, high: u64) -> Option<Self> {
+        let Some(low) = SegmentDescriptor::from_raw(low).system() else {
+            return None;
+        };
+
+        match low.system_type() {
+            Some(SystemSegmentType::Ldt)
+            | Some(SystemSegmentType::TssAvailable)
+            | Some(SystemSegmentType::TssBusy) => {}
+            _ => return None,
+        }
+
+        if high & (0x1F << 40) != 0 {
+            return None;
+        }
+
+        Some(Self { low, high })
+    }
+
+    /// Returns the raw values of the two entries this [`SystemDescriptor64`] occupies.
+    pub const fn to_raw(self) -> (u64, u64) {
+        (self.low.to_raw(), self.high)
+    }
+
+    /// Creates a new present [`SystemDescriptor64`] of type `system_type` with a base and limit
+    /// of 0.
+    ///
+    /// # Panics
+    /// Panics if `system_type` is not supported in IA-32e mode.
+    const fn new(system_type: SystemSegmentType) -> Self {
+        assert!(matches!(
+            system_type,
+            SystemSegmentType::Ldt | SystemSegmentType::TssAvailable | SystemSegmentType::TssBusy
+        ));
+
+        Self {
+            low: SegmentDescriptor::<System>::new(system_type),
+            high: 0,
+        }
+    }
+
+    /// Returns the linear address of the first byte of the segment.
+    pub const fn base(self) -> u64 {
+        (self.low.base() as u64) | ((self.high & 0xFFFF_FFFF) << 32)
+    }
+
+    /// Sets the linear address of the first byte of the segment.
+    pub const fn set_base(mut self, base: u64) -> Self {
+        self.low = self.low.set_base(base as u32);
+        self.high = (self.high & !0xFFFF_FFFF) | (base >> 32);
+        self
+    }
+
+    /// Returns the 20-bit segment limit.
+    pub const fn limit(self) -> u32 {
+        self.low.limit()
+    }
+
+    /// Sets the 20-bit segment limit.
+    ///
+    /// # Panics
+    /// Panics if `limit` does not fit in 20 bits.
+    pub const fn set_limit(mut self, limit: u32) -> Self {
+        self.low = self.low.set_limit(limit);
+        self
+    }
+
+    /// Returns the [`SystemSegmentType`] of the segment.
+    pub const fn system_type(self) -> SystemSegmentType {
+        match self.low.system_type() {
+            Some(system_type) => system_type,
+            None => unreachable!(),
+        }
+    }
+
+    /// Returns `true` if this [`SystemDescriptor64`] describes a busy TSS.
+    ///
+    /// The processor marks a TSS as busy when it is loaded into the task register.
+    pub const fn busy(self) -> bool {
+        matches!(self.system_type(), SystemSegmentType::TssBusy)
+    }
+
+    /// Sets whether this [`SystemDescriptor64`] describes a busy TSS.
+    ///
+    /// A TSS must be marked as available before it can be loaded into the task register.
+    ///
+    /// # Panics
+    /// Panics if this [`SystemDescriptor64`] does not describe a TSS.
+    pub const fn set_busy(mut self, busy: bool) -> Self {
+        assert!(!matches!(self.system_type(), SystemSegmentType::Ldt));
+
+        let system_type = if busy {
+            SystemSegmentType::TssBusy
+        } else {
+            SystemSegmentType::TssAvailable
+        };
+
+        self.low = self.low.set_system_type(system_type);
+        self
+    }
+
+    /// Returns the descriptor [`PrivilegeLevel`] of the segment.
+    pub const fn dpl(self) -> PrivilegeLevel {
+        self.low.dpl()
+    }
+
+    /// Sets the descriptor [`PrivilegeLevel`] of the segment.
+    pub const fn set_dpl(mut self, dpl: PrivilegeLevel) -> Self {
+        self.low = self.low.set_dpl(dpl);
+        self
+    }
+
+    /// Returns `true` if the segment is present in memory.
+    pub const fn present(self) -> bool {
+        self.low.present()
+    }
+
+    /// Sets whether the segment is present in memory.
+    pub const fn set_present(mut self, present: bool) -> Self {
+        self.low = self.low.set_present(present);
+        self
+    }
+}
+
+impl Default for SegmentDescriptor<Unclassified> {
+    fn default() -> Self {
+        Self::NULL
+    }
+}
+
+impl Default for SegmentDescriptor<Code> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for SegmentDescriptor<Data> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The types of system segments that may be described by a [`SegmentDescriptor`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SystemSegmentType {
+    /// An available 16-bit task state segment.
+    ///
+    /// This is not supported in IA-32e mode.
+    Tss16Available = 0x1,
+    /// A local descriptor table.
+    Ldt = 0x2,
+    /// A busy 16-bit task state segment.
+    ///
+    /// This is not supported in IA-32e mode.
+    Tss16Busy = 0x3,
+    /// An available 32-bit or 64-bit task state segment.
+    TssAvailable = 0x9,
+    /// A busy 32-bit or 64-bit task state segment.
+    TssBusy = 0xB,
+}
+
+impl SystemSegmentType {
+    /// Creates a [`SystemSegmentType`] from the value of the type field of a
+    /// [`SegmentDescriptor`].
+    ///
+    /// This function returns [`None`] if `val` does not describe a [`SystemSegmentType`].
+    pub const fn from_u8(val: u8) -> Option<Self> {
+        match val {
+            0x1 => Some(Self::Tss16Available),
+            0x2 => Some(Self::Ldt),
+            0x3 => Some(Self::Tss16Busy),
+            0x9 => Some(Self::TssAvailable),
+            0xB => Some(Self::TssBusy),
+            _ => None,
+        }
+    }
+}
+
+/// Marker struct that indicates that the [`SegmentDescriptor`] has not been classified.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Unclassified;
+
+/// Marker struct that indicates that the [`SegmentDescriptor`] describes a code segment.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Code;
+
+/// Marker struct that indicates that the [`SegmentDescriptor`] describes a data segment.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Data;
+
+/// Marker struct that indicates that the [`SegmentDescriptor`] describes a system segment.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct System;
+
+/// Marker trait that indicates that the implementer is a valid kind of [`SegmentDescriptor`].
+pub trait SegmentDescriptorKind: Copy + private::SegmentDescriptorKindSealed {}
+impl SegmentDescriptorKind for Unclassified {}
+impl SegmentDescriptorKind for Code {}
+impl SegmentDescriptorKind for Data {}
+impl SegmentDescriptorKind for System {}
+
+/// Marker trait that indicates that the [`SegmentDescriptorKind`] is either [`Code`] or
+/// [`Data`].
+pub trait CodeDataKind: SegmentDescriptorKind {}
+impl CodeDataKind for Code {}
+impl CodeDataKind for Data {}
+
+mod private {
+    //! Module used to seal the various traits used to implement the segment descriptor
+    //! abstraction.
+
+    use crate::structures::segment_table::{Code, Data, System, Unclassified};
+
+    /// Marker trait used to seal [`SegmentDescriptorKind`][sdk].
+    ///
+    /// [sdk]: crate::structures::segment_table::SegmentDescriptorKind
+    pub trait SegmentDescriptorKindSealed {}
+
+    impl SegmentDescriptorKindSealed for Unclassified {}
+    impl SegmentDescriptorKindSealed for Code {}
+    impl SegmentDescriptorKindSealed for Data {}
+    impl SegmentDescriptorKindSealed for System {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        structures::{
+            segment_table::{
+                Code, Data, SegmentDescriptor, SegmentTable, System, SystemDescriptor64,
+                SystemSegmentType,
+            },
+            task_state::TaskStateSegment64,
+        },
+        PrivilegeLevel,
+    };
+
+    #[test]
+    fn flat_descriptors() {
+        assert_eq!(
+            SegmentDescriptor::<Code>::KERNEL_CODE64.to_raw(),
+            0x00AF_9A00_0000_FFFF
+        );
+        assert_eq!(
+            SegmentDescriptor::<Code>::USER_CODE64.to_raw(),
+            0x00AF_FA00_0000_FFFF
+        );
+        assert_eq!(
+            SegmentDescriptor::<Code>::KERNEL_CODE32.to_raw(),
+            0x00CF_9A00_0000_FFFF
+        );
+        assert_eq!(
+            SegmentDescriptor::<Data>::KERNEL_DATA.to_raw(),
+            0x00CF_9200_0000_FFFF
+        );
+        assert_eq!(
+            SegmentDescriptor::<Data>::USER_DATA.to_raw(),
+            0x00CF_F200_0000_FFFF
+        );
+    }
+
+    #[test]
+    fn base_limit_roundtrip() {
+        let descriptor = SegmentDescriptor::<Data>::new()
+            .set_base(0xDEAD_BEEF)
+            .set_limit(0xA_BCDE);
+
+        assert_eq!(descriptor.base(), 0xDEAD_BEEF);
+        assert_eq!(descriptor.limit(), 0xA_BCDE);
+        assert!(descriptor.present());
+        assert!(descriptor.writable());
+        assert!(!descriptor.expand_down());
+    }
+
+    #[test]
+    fn classification() {
+        let code = SegmentDescriptor::<Code>::KERNEL_CODE64.unclassified();
+        let data = SegmentDescriptor::<Data>::KERNEL_DATA.unclassified();
+        let system = SegmentDescriptor::<System>::new(SystemSegmentType::Ldt).unclassified();
+
+        assert!(code.code().is_some_and(|code| code.long_mode()));
+        assert!(code.data().is_none() && code.system().is_none());
+        assert!(data.data().is_some_and(|data| data.big()));
+        assert!(data.code().is_none() && data.system().is_none());
+        assert_eq!(
+            system.system().and_then(|system| system.system_type()),
+            Some(SystemSegmentType::Ldt)
+        );
+        assert!(system.code().is_none() && system.data().is_none());
+    }
+
+    #[test]
+    fn table_selectors() {
+        let mut gdt = SegmentTable::<3>::new();
+        assert!(gdt.is_empty());
+
+        let code = gdt.push(SegmentDescriptor::<Code>::KERNEL_CODE64).unwrap();
+        let data = gdt.push(SegmentDescriptor::<Data>::USER_DATA).unwrap();
+        assert!(gdt.push(SegmentDescriptor::<Data>::KERNEL_DATA).is_err());
+
+        assert_eq!(code.index(), 1);
+        assert!(!code.ldt());
+        assert_eq!(code.rpl(), PrivilegeLevel::Ring0);
+        assert_eq!(data.index(), 2);
+        assert_eq!(data.rpl(), PrivilegeLevel::Ring3);
+        assert_eq!(gdt.as_slice().len(), 3);
+        assert_eq!(gdt.get(0), Some(SegmentDescriptor::NULL));
+        assert_eq!(gdt.pointer().limit(), 23);
+
+        let mut ldt = SegmentTable::<1>::new_local();
+        assert_eq!(ldt.pointer().limit(), 0);
+        let code = ldt.push(SegmentDescriptor::<Code>::USER_CODE32).unwrap();
+
+        assert_eq!(code.index(), 0);
+        assert!(code.ldt());
+        assert_eq!(code.rpl(), PrivilegeLevel::Ring3);
+    }
+
+    #[test]
+    fn system_descriptor_roundtrip() {
+        let descriptor = SystemDescriptor64::new(SystemSegmentType::TssAvailable)
+            .set_base(0xFFFF_8000_DEAD_BEEF)
+            .set_limit(103);
+        let (low, high) = descriptor.to_raw();
+
+        assert_eq!(low, 0xDE00_89AD_BEEF_0067);
+        assert_eq!(high, 0xFFFF_8000);
+        assert_eq!(SystemDescriptor64::from_raw(low, high), Some(descriptor));
+        assert_eq!(SystemDescriptor64::from_raw(low | (1 << 44), high), None);
+    }
+
+    #[test]
+    fn ldt_descriptor() {
+        let mut ldt = SegmentTable::<4>::new_local();
+        ldt.push(SegmentDescriptor::<Code>::USER_CODE32).unwrap();
+        ldt.push(SegmentDescriptor::<Data>::USER_DATA).unwrap();
+        let ldt: &'static SegmentTable<4> = std::boxed::Box::leak(std::boxed::Box::new(ldt));
+
+        let descriptor = SystemDescriptor64::ldt(ldt);
+        assert_eq!(descriptor.limit(), 15);
+        assert_eq!(descriptor.limit(), u32::from(ldt.pointer().limit()));
+        assert_eq!(descriptor.base(), ldt.as_slice().as_ptr() as u64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_ldt_descriptor() {
+        static LDT: SegmentTable<4> = SegmentTable::new_local();
+        let _ = SystemDescriptor64::ldt(&LDT);
+    }
+
+    #[test]
+    fn tss_in_table() {
+        static TSS: TaskStateSegment64 = TaskStateSegment64::new();
+
+        let mut gdt = SegmentTable::<4>::new();
+        gdt.push(SegmentDescriptor::<Code>::KERNEL_CODE64).unwrap();
+        let tss = gdt.push_system(SystemDescriptor64::tss(&TSS)).unwrap();
+        assert_eq!(tss.index(), 2);
+        assert!(gdt.push_system(SystemDescriptor64::tss(&TSS)).is_err());
+
+        // Simulate the processor marking the TSS as busy when it is loaded.
+        let low = gdt.get(2).and_then(|entry| entry.system()).unwrap();
+        gdt.set(2, low.set_system_type(SystemSegmentType::TssBusy))
+            .unwrap();
+
+        let descriptor = gdt.get_system(2).unwrap();
+        assert!(descriptor.busy());
+        assert_eq!(descriptor.base(), core::ptr::from_ref(&TSS) as u64);
+        assert_eq!(descriptor.limit(), 103);
+
+        gdt.set_system(2, descriptor.set_busy(false)).unwrap();
+        assert_eq!(
+            gdt.get_system(2).map(|descriptor| descriptor.system_type()),
+            Some(SystemSegmentType::TssAvailable)
+        );
+    }
+}
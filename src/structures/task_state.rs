@@ -231,6 +231,34 @@ impl TaskStateSegment64 {
         self.ist7_high = (val >> 32) as u32;
     }
 
+    /// Returns the address of the stack pointer when entering an interrupt handler that uses
+    /// the interrupt stack `index`.
+    pub const fn ist(&self, index: InterruptStackIndex) -> u64 {
+        match index {
+            InterruptStackIndex::Ist1 => self.is1(),
+            InterruptStackIndex::Ist2 => self.is2(),
+            InterruptStackIndex::Ist3 => self.is3(),
+            InterruptStackIndex::Ist4 => self.is4(),
+            InterruptStackIndex::Ist5 => self.is5(),
+            InterruptStackIndex::Ist6 => self.is6(),
+            InterruptStackIndex::Ist7 => self.is7(),
+        }
+    }
+
+    /// Sets the address of the stack pointer when entering an interrupt handler that uses the
+    /// interrupt stack `index`.
+    pub const fn set_ist(&mut self, index: InterruptStackIndex, val: u64) {
+        match index {
+            InterruptStackIndex::Ist1 => self.set_ist1(val),
+            InterruptStackIndex::Ist2 => self.set_ist2(val),
+            InterruptStackIndex::Ist3 => self.set_ist3(val),
+            InterruptStackIndex::Ist4 => self.set_ist4(val),
+            InterruptStackIndex::Ist5 => self.set_ist5(val),
+            InterruptStackIndex::Ist6 => self.set_ist6(val),
+            InterruptStackIndex::Ist7 => self.set_ist7(val),
+        }
+    }
+
     /// Returns the offset from the base of this [`TaskStateSegment64`] to the start of the I/O
     /// permission bit map.
     pub const fn io_map_base(&self) -> u16 {
@@ -243,3 +271,42 @@ impl TaskStateSegment64 {
         self.io_map_base = val;
     }
 }
+
+/// An index into the interrupt stack table of a [`TaskStateSegment64`].
+///
+/// An interrupt handler can be configured to switch to one of these stacks when it is entered.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InterruptStackIndex {
+    /// Interrupt stack 1, set by [`TaskStateSegment64::set_ist1()`].
+    Ist1 = 1,
+    /// Interrupt stack 2, set by [`TaskStateSegment64::set_ist2()`].
+    Ist2 = 2,
+    /// Interrupt stack 3, set by [`TaskStateSegment64::set_ist3()`].
+    Ist3 = 3,
+    /// Interrupt stack 4, set by [`TaskStateSegment64::set_ist4()`].
+    Ist4 = 4,
+    /// Interrupt stack 5, set by [`TaskStateSegment64::set_ist5()`].
+    Ist5 = 5,
+    /// Interrupt stack 6, set by [`TaskStateSegment64::set_ist6()`].
+    Ist6 = 6,
+    /// Interrupt stack 7, set by [`TaskStateSegment64::set_ist7()`].
+    Ist7 = 7,
+}
+
+impl InterruptStackIndex {
+    /// Creates an [`InterruptStackIndex`] from a numeric value.
+    ///
+    /// This function returns [`None`] if `val` is 0 or greater than 7.
+    pub const fn from_u8(val: u8) -> Option<Self> {
+        match val {
+            1 => Some(Self::Ist1),
+            2 => Some(Self::Ist2),
+            3 => Some(Self::Ist3),
+            4 => Some(Self::Ist4),
+            5 => Some(Self::Ist5),
+            6 => Some(Self::Ist6),
+            7 => Some(Self::Ist7),
+            _ => None,
+        }
+    }
+}